@@ -0,0 +1,197 @@
+//! Splits text read from files or standard input on a regular expression,
+//! writing each segment out on its own. Only built with the `cli` feature.
+
+use std::env;
+use std::fs;
+use std::io::{self, BufWriter, Read, Write};
+use std::process::ExitCode;
+
+use regex::bytes::Regex;
+use regex_split::bytes::Splitter;
+use regex_split::stream::StreamSplitter;
+use regex_split::Keep;
+
+const USAGE: &str = "\
+Usage: regex-split [OPTIONS] PATTERN [FILE]...
+
+Splits each FILE, or standard input when there is none or FILE is -, into
+segments delimited by a match of PATTERN, and writes them out one after the
+other, each followed by a newline.
+
+Inclusive splitting without a limit streams the input, holding no more of it
+than the segment being split off. The other modes, and a limit, read each
+input whole before splitting it.
+
+Options:
+  -m, --mode MODE   where segments keep their delimiters: inclusive (at the
+                    end, the default), left (at the front) or exclusive
+                    (nowhere)
+  -z, --null        end each segment with a NUL byte instead of a newline
+  -n, --limit N     split each input into at most N segments, the last of
+                    which holds the rest of it
+  -s, --skip-empty  leave out empty segments
+  -h, --help        print this help and exit
+";
+
+#[derive(Debug)]
+struct Args {
+    pattern: String,
+    files: Vec<String>,
+    keep: Keep,
+    terminator: u8,
+    limit: Option<usize>,
+    skip_empty: bool,
+}
+
+fn main() -> ExitCode {
+    let args = match parse(env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            print!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("regex-split: {e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    let regex = match Regex::new(&args.pattern) {
+        Ok(regex) => regex,
+        Err(e) => {
+            eprintln!("regex-split: {e}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let mut splitter = Splitter::new(&regex)
+        .keep(args.keep)
+        .skip_empty(args.skip_empty);
+    if let Some(limit) = args.limit {
+        splitter = splitter.limit(limit);
+    }
+
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    let mut status = ExitCode::SUCCESS;
+    // Inclusive segments can be split off as the input comes in; the other
+    // modes, and a limit, go through the whole-buffer splitter.
+    let streaming = args.keep == Keep::Right && args.limit.is_none();
+    for file in &args.files {
+        let mut write = |segment: &[u8]| {
+            out.write_all(segment)?;
+            out.write_all(&[args.terminator])
+        };
+        let result = if streaming {
+            open(file).and_then(|reader| {
+                StreamSplitter::new(&regex, reader)
+                    .capacity(usize::MAX)
+                    .try_for_each(|segment| match segment? {
+                        segment if args.skip_empty && segment.is_empty() => Ok(()),
+                        segment => write(&segment),
+                    })
+            })
+        } else {
+            read(file).and_then(|text| splitter.split(&text).try_for_each(write))
+        };
+        match result {
+            Ok(()) => {}
+            // The reader went away, as `head` does; there's no one to tell.
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => return ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("regex-split: {file}: {e}");
+                status = ExitCode::FAILURE;
+            }
+        }
+    }
+    match out.flush() {
+        Err(e) if e.kind() != io::ErrorKind::BrokenPipe => {
+            eprintln!("regex-split: {e}");
+            ExitCode::FAILURE
+        }
+        _ => status,
+    }
+}
+
+// Parses the arguments, or returns `None` when help was asked for.
+fn parse(mut args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut pattern = None;
+    let mut files = Vec::new();
+    let mut keep = Keep::Right;
+    let mut terminator = b'\n';
+    let mut limit = None;
+    let mut skip_empty = false;
+    let mut options = true;
+
+    while let Some(arg) = args.next() {
+        if !options || arg == "-" || !arg.starts_with('-') {
+            if pattern.is_none() {
+                pattern = Some(arg);
+            } else {
+                files.push(arg);
+            }
+            continue;
+        }
+
+        // Options may take their value as `--limit=3` as well as `--limit 3`.
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name, Some(value.to_owned())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = |name: &str| {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{name} needs a value"))
+        };
+        match name {
+            "--" => options = false,
+            "-h" | "--help" => return Ok(None),
+            "-z" | "--null" => terminator = b'\0',
+            "-s" | "--skip-empty" => skip_empty = true,
+            "-m" | "--mode" => {
+                keep = match value(name)?.as_str() {
+                    "inclusive" => Keep::Right,
+                    "left" => Keep::Left,
+                    "exclusive" => Keep::Neither,
+                    mode => return Err(format!("unknown mode: {mode}")),
+                }
+            }
+            "-n" | "--limit" => {
+                let n = value(name)?;
+                limit = Some(n.parse().map_err(|_| format!("invalid limit: {n}"))?);
+            }
+            _ => return Err(format!("unknown option: {arg}")),
+        }
+    }
+
+    let pattern = pattern.ok_or("missing PATTERN")?;
+    if files.is_empty() {
+        files.push("-".to_owned());
+    }
+    Ok(Some(Args {
+        pattern,
+        files,
+        keep,
+        terminator,
+        limit,
+        skip_empty,
+    }))
+}
+
+fn open(file: &str) -> io::Result<Box<dyn Read>> {
+    if file == "-" {
+        Ok(Box::new(io::stdin().lock()))
+    } else {
+        Ok(Box::new(fs::File::open(file)?))
+    }
+}
+
+fn read(file: &str) -> io::Result<Vec<u8>> {
+    if file == "-" {
+        let mut text = Vec::new();
+        io::stdin().lock().read_to_end(&mut text)?;
+        Ok(text)
+    } else {
+        fs::read(file)
+    }
+}
@@ -1,93 +1,834 @@
+use std::borrow::{Borrow, Cow};
 use std::iter::FusedIterator;
+use std::str::{self, Utf8Error};
+use std::sync::Arc;
 
-use regex::bytes::{Matches, Regex};
+#[cfg(feature = "bytes")]
+use ::bytes::Bytes;
+#[cfg(feature = "bstr")]
+use bstr::BStr;
+use regex::bytes::{Match, Regex, Replacer};
 
+#[cfg(feature = "bytes")]
+use crate::haystack::OwnedSplit;
+use crate::haystack::{self, MatchesAt};
+use crate::{
+    RangeFinder, SegmentStats, Side, SplitInclusiveLeftRanges, SplitInclusiveRanges, TrailingEmpty,
+};
+
+/// Split methods on a regex. These search with the regex itself: the
+/// `aho-corasick` and `memchr` fast paths are only taken by a [`Splitter`]
+/// made with `Splitter::new`, which works out whether the pattern allows
+/// them once and keeps the answer for every split after.
 pub trait RegexSplit {
     fn split_inclusive<'r, 't>(&'r self, text: &'t [u8]) -> SplitInclusive<'r, 't>;
     fn split_inclusive_left<'r, 't>(&'r self, text: &'t [u8]) -> SplitInclusiveLeft<'r, 't>;
+    fn rsplitn_inclusive<'r, 't>(&'r self, text: &'t [u8], n: usize) -> RSplitNInclusive<'r, 't>;
+    fn split_inclusive_terminator<'r, 't>(&'r self, text: &'t [u8]) -> SplitInclusive<'r, 't>;
+    fn split_inclusive_left_terminator<'r, 't>(
+        &'r self,
+        text: &'t [u8],
+    ) -> SplitInclusiveLeft<'r, 't>;
+    fn split_inclusive_at<'r, 't>(&'r self, text: &'t [u8], start: usize)
+        -> SplitInclusive<'r, 't>;
+    fn split_once_inclusive<'t>(&self, text: &'t [u8]) -> Option<(&'t [u8], &'t [u8])>;
+    fn split_once_inclusive_left<'t>(&self, text: &'t [u8]) -> Option<(&'t [u8], &'t [u8])>;
+    fn rsplit_once_inclusive<'t>(&self, text: &'t [u8]) -> Option<(&'t [u8], &'t [u8])>;
+    fn rsplit_once_inclusive_left<'t>(&self, text: &'t [u8]) -> Option<(&'t [u8], &'t [u8])>;
+    fn partition_at_nth_match<'t>(&self, text: &'t [u8], n: usize) -> Option<(&'t [u8], &'t [u8])>;
+    fn partition_at_nth_match_left<'t>(
+        &self,
+        text: &'t [u8],
+        n: usize,
+    ) -> Option<(&'t [u8], &'t [u8])>;
+    fn split_inclusive_rev<'r, 't>(&'r self, text: &'t [u8]) -> SplitInclusiveRev<'r, 't>;
+    fn split_inclusive_left_rev<'r, 't>(&'r self, text: &'t [u8]) -> SplitInclusiveLeftRev<'r, 't>;
+    fn split_inclusive_both<'r, 't>(&'r self, text: &'t [u8]) -> SplitInclusiveBoth<'r, 't>;
+    fn split_inclusive_by<'r, 't, F>(
+        &'r self,
+        text: &'t [u8],
+        side: F,
+    ) -> SplitInclusiveBy<'r, 't, F>
+    where
+        F: FnMut(&Match<'t>) -> Side;
+    fn split_on_group<'r, 't>(&'r self, text: &'t [u8], group: usize) -> SplitOnGroup<'r, 't>;
+    fn split_on_name<'r, 't>(&'r self, text: &'t [u8], name: &str) -> SplitOnGroup<'r, 't>;
+    fn split_around<'r, 't>(&'r self, text: &'t [u8]) -> SplitAround<'r, 't>;
+    fn split_inclusive_captures<'r, 't>(&'r self, text: &'t [u8])
+        -> SplitInclusiveCaptures<'r, 't>;
+    fn split_with_delimiters<'r, 't>(&'r self, text: &'t [u8]) -> SplitWithDelimiters<'r, 't>;
+    fn matches_with_context<'r, 't>(
+        &'r self,
+        text: &'t [u8],
+        n: usize,
+    ) -> MatchesWithContext<'r, 't>;
+    fn group_by_capture<'r, 't>(&'r self, text: &'t [u8], name: &str) -> GroupByCapture<'r, 't>;
+    fn split_between<'r, 't>(&'r self, text: &'t [u8], end: &'r Regex) -> SplitBetween<'r, 't>;
+    fn split_inclusive_outside<'r, 't>(
+        &'r self,
+        text: &'t [u8],
+        mask: &'r Regex,
+    ) -> SplitInclusiveOutside<'r, 't>;
+    fn split_inclusive_tagged<'r, 't>(&'r self, text: &'t [u8]) -> SplitInclusiveTagged<'r, 't>;
+    fn chunks_by_matches<'r, 't>(&'r self, text: &'t [u8], n: usize) -> ChunksByMatches<'r, 't>;
+    fn split_parts<'r, 't>(&'r self, text: &'t [u8]) -> SplitParts<'r, 't>;
+    fn split_inclusive_indices<'r, 't>(&'r self, text: &'t [u8]) -> SplitInclusiveIndices<'r, 't>;
+    fn split_inclusive_left_indices<'r, 't>(
+        &'r self,
+        text: &'t [u8],
+    ) -> SplitInclusiveLeftIndices<'r, 't>;
+    fn split_inclusive_ranges<'r, 't>(&'r self, text: &'t [u8]) -> SplitInclusiveRanges<'r, 't>;
+    fn split_inclusive_left_ranges<'r, 't>(
+        &'r self,
+        text: &'t [u8],
+    ) -> SplitInclusiveLeftRanges<'r, 't>;
+    fn split_inclusive_segments<'r, 't>(&'r self, text: &'t [u8])
+        -> SplitInclusiveSegments<'r, 't>;
+    fn split_inclusive_left_segments<'r, 't>(
+        &'r self,
+        text: &'t [u8],
+    ) -> SplitInclusiveLeftSegments<'r, 't>;
+    fn sections<'r, 't>(&'r self, text: &'t [u8]) -> Sections<'r, 't>;
+    fn count_segments(&self, text: &[u8]) -> usize;
+    fn stats(&self, text: &[u8]) -> SegmentStats;
+    fn map_segments<'t, F>(&self, text: &'t [u8], f: F) -> Cow<'t, [u8]>
+    where
+        F: FnMut(&'t [u8]) -> Cow<'t, [u8]>;
+    fn replace_within_segments<'t, R>(&self, text: &'t [u8], re: &Regex, rep: R) -> Cow<'t, [u8]>
+    where
+        R: Replacer;
+    fn split_inclusive_owned(&self, text: Vec<u8>) -> SplitInclusiveOwned;
+    fn split_inclusive_left_owned(&self, text: Vec<u8>) -> SplitInclusiveLeftOwned;
+    fn split_inclusive_arc(&self, text: Arc<[u8]>) -> SplitInclusiveArc;
+    fn split_inclusive_left_arc(&self, text: Arc<[u8]>) -> SplitInclusiveLeftArc;
+    fn split_inclusive_cow<'r, 'a>(&'r self, text: Cow<'a, [u8]>) -> SplitInclusiveCow<'r, 'a>;
+    fn split_inclusive_left_cow<'r, 'a>(
+        &'r self,
+        text: Cow<'a, [u8]>,
+    ) -> SplitInclusiveLeftCow<'r, 'a>;
+    #[cfg(feature = "bytes")]
+    fn split_inclusive_bytes(&self, text: Bytes) -> SplitInclusiveBytes;
+    #[cfg(feature = "bytes")]
+    fn split_inclusive_left_bytes(&self, text: Bytes) -> SplitInclusiveLeftBytes;
+    #[cfg(feature = "bstr")]
+    fn split_inclusive_bstr<'r, 't>(&'r self, text: &'t BStr) -> SplitInclusiveBStr<'r, 't>;
+    #[cfg(feature = "bstr")]
+    fn split_inclusive_left_bstr<'r, 't>(
+        &'r self,
+        text: &'t BStr,
+    ) -> SplitInclusiveLeftBStr<'r, 't>;
+    fn split_inclusive_utf8<'r, 't>(&'r self, text: &'t [u8]) -> SplitInclusiveUtf8<'r, 't>;
+    fn split_inclusive_lossy<'r, 't>(&'r self, text: &'t [u8]) -> SplitInclusiveLossy<'r, 't>;
+}
+
+pub trait ByteSplitExt {
+    fn split_inclusive_re<'r, 't>(&'t self, re: &'r Regex) -> SplitInclusive<'r, 't>;
+    fn split_inclusive_left_re<'r, 't>(&'t self, re: &'r Regex) -> SplitInclusiveLeft<'r, 't>;
+    fn rsplitn_inclusive_re<'r, 't>(&'t self, re: &'r Regex, n: usize) -> RSplitNInclusive<'r, 't>;
 }
 
+/// A regular expression together with options for splitting text with it.
+/// This is the most flexible way to split; the methods of [`RegexSplit`] are
+/// shortcuts for common configurations.
+///
+/// # Example
+///
+/// ```rust
+/// # use regex::bytes::Regex;
+/// # use crate::regex_split::{bytes::Splitter, Keep};
+/// # fn main() {
+/// let re = Regex::new(r"\n").unwrap();
+/// let text = &b"one\n\ntwo\nthree\nfour"[..];
+/// let v: Vec<&[u8]> = Splitter::new(&re)
+///     .keep(Keep::Left)
+///     .skip_empty(true)
+///     .limit(3)
+///     .split(text)
+///     .collect();
+/// assert_eq!(v, [&b"one"[..], &b"\n"[..], &b"\ntwo\nthree\nfour"[..]]);
+/// # }
+/// ```
+pub type Splitter<'r, M = Regex> = haystack::Splitter<'r, [u8], M>;
+
+/// Yields the substrings of a text delimited by regular expression matches,
+/// as configured by a [`Splitter`].
+///
+/// Iterating from the back with `next_back` or `rev` works too, but since
+/// the regex crate can't search backwards, the first call to `next_back`
+/// splits the rest of the text up front and buffers the substrings.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type Split<'r, 't, M = Regex> = haystack::Split<'r, 't, [u8], M>;
+
+/// Yields the substrings of a text as `Ok`, as configured by a
+/// [`Splitter`], for a matcher whose searches can fail. Made with
+/// [`Splitter::try_split`](Splitter#method.try_split).
+///
+/// Where a [`Split`] stops early and leaves its `error` method to report
+/// why, this yields the error as an `Err` and then ends. When a search
+/// fails, the substring it was looking for the end of isn't yielded, since
+/// where it ends isn't known.
+///
+/// # Example
+///
+/// ```rust
+/// # use regex::bytes::Regex;
+/// # use crate::regex_split::{bytes::Splitter, SplitError, ZeroWidth};
+/// # fn main() {
+/// let re = Regex::new(r",|$").unwrap();
+/// let v: Vec<_> = Splitter::new(&re)
+///     .zero_width(ZeroWidth::Error)
+///     .try_split(b"a,b")
+///     .collect();
+/// assert_eq!(v.len(), 2);
+/// assert_eq!(v[0], Ok(&b"a,"[..]));
+/// assert!(matches!(v[1], Err(SplitError::ZeroWidth(e)) if e.offset() == 3));
+/// # }
+/// ```
+pub type TrySplit<'r, 't, M = Regex> = haystack::TrySplit<'r, 't, [u8], M>;
+
+/// Yields all substrings delimited by a regular expression match inclusive of
+/// the match.
+///
+/// Iterating from the back with `next_back` or `rev` works too, but since
+/// the regex crate can't search backwards, the first call to `next_back`
+/// splits the rest of the text up front and buffers the substrings.
+///
+/// ```rust
+/// # use regex::bytes::Regex;
+/// # use crate::regex_split::bytes::RegexSplit;
+/// # fn main() {
+/// let re = Regex::new(r"\n").unwrap();
+/// let text = b"one\ntwo\nthree";
+/// let v: Vec<&[u8]> = re.split_inclusive(text).rev().take(2).collect();
+/// assert_eq!(v, [&b"three"[..], &b"two\n"[..]]);
+/// # }
+/// ```
+///
+/// `count`, `nth` and `last` run the search without slicing out the
+/// substrings they pass over, and `fold` (and so `for_each`) drives it in a
+/// single loop.
+///
+/// ```rust
+/// # use regex::bytes::Regex;
+/// # use crate::regex_split::bytes::RegexSplit;
+/// # fn main() {
+/// let re = Regex::new(r"\n").unwrap();
+/// let text = b"one\ntwo\nthree\nfour";
+/// assert_eq!(re.split_inclusive(text).count(), 4);
+/// assert_eq!(re.split_inclusive(text).fold(0, |n, s| n + s.len()), text.len());
+/// assert_eq!(re.split_inclusive(text).nth(1), Some(&b"two\n"[..]));
+/// assert_eq!(re.split_inclusive(text).last(), Some(&b"four"[..]));
+/// # }
+/// ```
+///
+/// The iterator is cheap to clone, since it only holds on to its position in
+/// the text, so a copy can be taken to look ahead without losing your place.
+///
+/// ```rust
+/// # use regex::bytes::Regex;
+/// # use crate::regex_split::bytes::RegexSplit;
+/// # fn main() {
+/// let re = Regex::new(",").unwrap();
+/// let mut iter = re.split_inclusive(b"a,b,c");
+/// iter.next();
+/// assert_eq!(iter.clone().count(), 2);
+/// let v: Vec<_> = iter.collect();
+/// assert_eq!(v, [&b"b,"[..], &b"c"[..]]);
+/// # }
+/// ```
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitInclusive<'r, 't> = haystack::SplitInclusive<'r, 't, [u8]>;
+
 /// Yields all substrings delimited by a regular expression match inclusive of
 /// the match.
 ///
+/// Iterating from the back with `next_back` or `rev` works too, but since
+/// the regex crate can't search backwards, the first call to `next_back`
+/// splits the rest of the text up front and buffers the substrings.
+///
+/// ```rust
+/// # use regex::bytes::Regex;
+/// # use crate::regex_split::bytes::RegexSplit;
+/// # fn main() {
+/// let re = Regex::new(r"\n").unwrap();
+/// let text = b"one\ntwo\nthree";
+/// let v: Vec<&[u8]> = re.split_inclusive_left(text).rev().take(2).collect();
+/// assert_eq!(v, [&b"\nthree"[..], &b"\ntwo"[..]]);
+/// # }
+/// ```
+///
 /// `'r` is the lifetime of the compiled regular expression and `'t` is the
 /// lifetime of the byte string being split.
-#[derive(Debug)]
-pub struct SplitInclusive<'r, 't> {
-    finder: Matches<'r, 't>,
-    last: usize,
+pub type SplitInclusiveLeft<'r, 't> = haystack::SplitInclusiveLeft<'r, 't, [u8]>;
 
-    // The internals of finder are private, meaning we need to keep a reference
-    // to the text for ourselves. This differs from the previous
-    // implementation.
-    text: &'t [u8],
+/// Yields at most `n` substrings delimited by a regular expression match
+/// inclusive of the match, starting from the end of the text. The last
+/// substring yielded holds whatever remains at the front of the text.
+///
+/// The regex crate can't search backwards, so the first call to `next` scans
+/// the text once, remembering only the positions of the last `n - 1` matches.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type RSplitNInclusive<'r, 't> = haystack::RSplitNInclusive<'r, 't, [u8]>;
+
+/// Yields all substrings delimited by a regular expression match inclusive of
+/// the match, starting from the end of the text.
+///
+/// The regex crate can't search backwards, so the first call to `next` scans
+/// the text once, recording the position of every match.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitInclusiveRev<'r, 't> = haystack::SplitInclusiveRev<'r, 't, [u8]>;
+
+/// Yields all substrings delimited by a regular expression match inclusive of
+/// the match, starting from the end of the text, with the match at the front
+/// of each substring.
+///
+/// The regex crate can't search backwards, so the first call to `next` scans
+/// the text once, recording the position of every match.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitInclusiveLeftRev<'r, 't> = haystack::SplitInclusiveLeftRev<'r, 't, [u8]>;
+
+/// Yields all substrings delimited by a regular expression match, with each
+/// match included both at the end of the substring before it and at the start
+/// of the substring after it. Neighboring substrings therefore overlap.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitInclusiveBoth<'r, 't> = haystack::SplitInclusiveBoth<'r, 't, [u8]>;
+
+/// Yields all substrings of an owned text delimited by a regular expression
+/// match inclusive of the match, as owned copies. The iterator owns the text
+/// and a clone of the regular expression, so it borrows nothing and can be
+/// returned from a function or kept around.
+pub type SplitInclusiveOwned = haystack::SplitInclusiveOwned<[u8]>;
+
+/// Yields all substrings of an owned text delimited by a regular expression
+/// match, with the match at the front of each substring, as owned copies.
+/// Like [`SplitInclusiveOwned`], it borrows nothing.
+pub type SplitInclusiveLeftOwned = haystack::SplitInclusiveLeftOwned<[u8]>;
+
+/// A substring of a shared text, which holds on to the text rather than
+/// borrowing it. Cloning one is cheap and copies none of the text.
+pub type ArcSegment = haystack::ArcSegment<[u8]>;
+
+impl ArcSegment {
+    /// Returns the substring.
+    pub fn as_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+/// Yields all substrings of a shared text delimited by a regular expression
+/// match inclusive of the match, as [`ArcSegment`]s sharing the text. Like
+/// [`SplitInclusiveOwned`], it borrows nothing, but it copies none of the
+/// text either.
+pub type SplitInclusiveArc = haystack::SplitInclusiveArc<[u8]>;
+
+/// Yields all substrings of a shared text delimited by a regular expression
+/// match, with the match at the front of each substring, as [`ArcSegment`]s
+/// sharing the text.
+pub type SplitInclusiveLeftArc = haystack::SplitInclusiveLeftArc<[u8]>;
+
+/// Yields all substrings of a [`Bytes`] delimited by a regular expression
+/// match inclusive of the match, as `Bytes` sharing the text's buffer. Only
+/// available with the `bytes` feature.
+#[cfg(feature = "bytes")]
+#[derive(Clone, Debug)]
+pub struct SplitInclusiveBytes {
+    inner: OwnedSplit<Bytes>,
 }
 
-impl<'r, 't> Iterator for SplitInclusive<'r, 't> {
-    type Item = &'t [u8];
+#[cfg(feature = "bytes")]
+impl Iterator for SplitInclusiveBytes {
+    type Item = Bytes;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.finder.next() {
-            None => {
-                if self.last > self.text.len() {
-                    None
-                } else {
-                    let s = &self.text[self.last..];
-                    self.last = self.text.len() + 1; // Next call will return None
-                    Some(s)
-                }
-            }
-            Some(m) => {
-                let matched = &self.text[self.last..m.end()];
-                self.last = m.end();
-                Some(matched)
-            }
-        }
+        let range = self.inner.next_range(false)?;
+        Some(self.inner.text.slice_ref(&self.inner.text[range]))
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl FusedIterator for SplitInclusiveBytes {}
+
+/// Yields all substrings of a [`Bytes`] delimited by a regular expression
+/// match, with the match at the front of each substring, as `Bytes` sharing
+/// the text's buffer. Only available with the `bytes` feature.
+#[cfg(feature = "bytes")]
+#[derive(Clone, Debug)]
+pub struct SplitInclusiveLeftBytes {
+    inner: OwnedSplit<Bytes>,
+}
+
+#[cfg(feature = "bytes")]
+impl Iterator for SplitInclusiveLeftBytes {
+    type Item = Bytes;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let range = self.inner.next_range(true)?;
+        Some(self.inner.text.slice_ref(&self.inner.text[range]))
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl FusedIterator for SplitInclusiveLeftBytes {}
+
+/// Yields all substrings of a [`BStr`] delimited by a regular expression
+/// match inclusive of the match, as `BStr`s. Only available with the `bstr`
+/// feature.
+#[cfg(feature = "bstr")]
+#[derive(Clone, Debug)]
+pub struct SplitInclusiveBStr<'r, 't> {
+    inner: SplitInclusive<'r, 't>,
+}
+
+#[cfg(feature = "bstr")]
+impl<'r, 't> Iterator for SplitInclusiveBStr<'r, 't> {
+    type Item = &'t BStr;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(BStr::new)
+    }
+}
+
+#[cfg(feature = "bstr")]
+impl<'r, 't> DoubleEndedIterator for SplitInclusiveBStr<'r, 't> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(BStr::new)
+    }
+}
+
+#[cfg(feature = "bstr")]
+impl<'r, 't> FusedIterator for SplitInclusiveBStr<'r, 't> {}
+
+/// Yields all substrings of a [`BStr`] delimited by a regular expression
+/// match, with the match at the front of each substring, as `BStr`s. Only
+/// available with the `bstr` feature.
+#[cfg(feature = "bstr")]
+#[derive(Clone, Debug)]
+pub struct SplitInclusiveLeftBStr<'r, 't> {
+    inner: SplitInclusiveLeft<'r, 't>,
+}
+
+#[cfg(feature = "bstr")]
+impl<'r, 't> Iterator for SplitInclusiveLeftBStr<'r, 't> {
+    type Item = &'t BStr;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(BStr::new)
+    }
+}
+
+#[cfg(feature = "bstr")]
+impl<'r, 't> DoubleEndedIterator for SplitInclusiveLeftBStr<'r, 't> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(BStr::new)
     }
 }
 
-impl<'r, 't> FusedIterator for SplitInclusive<'r, 't> {}
+#[cfg(feature = "bstr")]
+impl<'r, 't> FusedIterator for SplitInclusiveLeftBStr<'r, 't> {}
 
 /// Yields all substrings delimited by a regular expression match inclusive of
-/// the match.
+/// the match, as `&str`s. Each substring is checked for valid UTF-8 only when
+/// it is yielded, so an invalid one doesn't stop the rest.
 ///
 /// `'r` is the lifetime of the compiled regular expression and `'t` is the
 /// lifetime of the byte string being split.
-#[derive(Debug)]
-pub struct SplitInclusiveLeft<'r, 't> {
-    finder: Matches<'r, 't>,
-    last: usize,
+#[derive(Clone, Debug)]
+pub struct SplitInclusiveUtf8<'r, 't> {
+    inner: SplitInclusive<'r, 't>,
+}
 
-    // The internals of finder are private, meaning we need to keep a reference
-    // to the text for ourselves. This differs from the previous
-    // implementation.
-    text: &'t [u8],
+impl<'r, 't> Iterator for SplitInclusiveUtf8<'r, 't> {
+    type Item = Result<&'t str, Utf8Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(str::from_utf8)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'r, 't> DoubleEndedIterator for SplitInclusiveUtf8<'r, 't> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(str::from_utf8)
+    }
+}
+
+impl<'r, 't> FusedIterator for SplitInclusiveUtf8<'r, 't> {}
+
+/// Yields all substrings delimited by a regular expression match inclusive of
+/// the match, as text with any invalid UTF-8 replaced by U+FFFD. Valid
+/// substrings are borrowed from the byte string.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+#[derive(Clone, Debug)]
+pub struct SplitInclusiveLossy<'r, 't> {
+    inner: SplitInclusive<'r, 't>,
 }
 
-impl<'r, 't> Iterator for SplitInclusiveLeft<'r, 't> {
-    type Item = &'t [u8];
+impl<'r, 't> Iterator for SplitInclusiveLossy<'r, 't> {
+    type Item = Cow<'t, str>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.finder.next() {
-            None => {
-                if self.last > self.text.len() {
-                    None
-                } else {
-                    let s = &self.text[self.last..];
-                    self.last = self.text.len() + 1; // Next call will return None
-                    Some(s)
-                }
-            }
-            Some(m) => {
-                let matched = &self.text[self.last..m.start()];
-                self.last = m.start();
-                Some(matched)
-            }
-        }
+        self.inner.next().map(String::from_utf8_lossy)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'r, 't> DoubleEndedIterator for SplitInclusiveLossy<'r, 't> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(String::from_utf8_lossy)
+    }
+}
+
+impl<'r, 't> FusedIterator for SplitInclusiveLossy<'r, 't> {}
+
+/// Yields all substrings of a text given as a `Cow` delimited by a regular
+/// expression match inclusive of the match. Substrings of a borrowed text
+/// are borrowed too; those of an owned text are owned copies.
+pub type SplitInclusiveCow<'r, 'a> = haystack::SplitInclusiveCow<'r, 'a, [u8]>;
+
+/// Yields all substrings of a text given as a `Cow` delimited by a regular
+/// expression match, with the match at the front of each substring,
+/// borrowed or owned as the text is.
+pub type SplitInclusiveLeftCow<'r, 'a> = haystack::SplitInclusiveLeftCow<'r, 'a, [u8]>;
+
+/// Yields all substrings delimited by a regular expression match, with each
+/// match given to the substring before it, the one after it, both or neither,
+/// as decided by a closure.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitInclusiveBy<'r, 't, F> = haystack::SplitInclusiveBy<'r, 't, [u8], F>;
+
+/// Yields all substrings delimited by one capture group of a regular
+/// expression's matches, with the group's text included at the end of the
+/// substring. The rest of each match is left in the substrings around it.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitOnGroup<'r, 't> = haystack::SplitOnGroup<'r, 't, [u8]>;
+
+/// Yields all substrings delimited by a regular expression match, with the
+/// match's `left` group included at the end of the substring before it and
+/// its `right` group included at the start of the substring after it.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitAround<'r, 't> = haystack::SplitAround<'r, 't, [u8]>;
+
+/// Yields all substrings delimited by a regular expression match, with the
+/// match included at the end of each substring, each paired with the capture
+/// groups of that match. The final substring is paired with `None`.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitInclusiveCaptures<'r, 't> = haystack::SplitInclusiveCaptures<'r, 't, [u8]>;
+
+/// Yields all substrings delimited by a regular expression match, with the
+/// match included at the end of each substring, each paired with the name of
+/// the named group that took part in that match.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitInclusiveTagged<'r, 't> = haystack::SplitInclusiveTagged<'r, 't, [u8]>;
+
+/// Yields the sections of a text introduced by regular expression matches,
+/// each paired with the text one capture group of the introducing match
+/// captured. Any text before the first match is paired with `None`.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type GroupByCapture<'r, 't> = haystack::GroupByCapture<'r, 't, [u8]>;
+
+/// Yields the regions of a text that lie between a match of one regular
+/// expression and the next match of another, skipping the text outside them.
+///
+/// `'r` is the lifetime of the compiled regular expressions and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitBetween<'r, 't> = haystack::SplitBetween<'r, 't, [u8]>;
+
+/// Yields all substrings delimited by a regular expression match, with the
+/// match included at the end of the substring, except that matches overlapping
+/// any region matched by a second, masking regular expression don't split.
+///
+/// `'r` is the lifetime of the compiled regular expressions and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitInclusiveOutside<'r, 't> = haystack::SplitInclusiveOutside<'r, 't, [u8]>;
+
+/// Yields contiguous slices of a text that each run through `n` matches of a
+/// regular expression, with the last match included at the end of the slice.
+/// The final slice holds whatever is left and may have fewer matches.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type ChunksByMatches<'r, 't> = haystack::ChunksByMatches<'r, 't, [u8]>;
+
+/// Yields slices of a text that each cover a run of consecutive substrings
+/// from a split, with neighboring runs sharing some substrings. Built by the
+/// `windows` method of the split iterators.
+///
+/// `'t` is the lifetime of the byte string being split and `I` is the split
+/// iterator.
+pub type Windows<'t, I> = haystack::Windows<'t, [u8], I>;
+
+/// Yields the substrings from a split, each paired with the
+/// [`Position`](crate::Position) where it begins. Built by the `located`
+/// method of the split iterators.
+///
+/// `'t` is the lifetime of the byte string being split and `I` is the split
+/// iterator.
+pub type Located<'t, I> = haystack::Located<'t, [u8], I>;
+
+/// Yields all substrings delimited by a regular expression match, exclusive of
+/// the match, each paired with the match that ended it. The final substring
+/// is paired with `None`.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitWithDelimiters<'r, 't> = haystack::SplitWithDelimiters<'r, 't, [u8]>;
+
+/// Yields each match of a regular expression in a slice of the text with up
+/// to some number of bytes of context on either side, paired with the range
+/// of the match within the slice.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being searched.
+pub type MatchesWithContext<'r, 't> = haystack::MatchesWithContext<'r, 't, [u8]>;
+
+/// A piece of the text yielded by `split_parts`: either a run of text between
+/// matches or a match of the regular expression.
+pub type Part<'t> = haystack::Part<'t, [u8]>;
+
+impl<'t> Part<'t> {
+    /// Returns the text this part covers, whichever kind of part it is.
+    pub fn as_bytes(&self) -> &'t [u8] {
+        self.text()
     }
 }
 
-impl<'r, 't> FusedIterator for SplitInclusiveLeft<'r, 't> {}
+/// Yields the text between matches and the matches themselves, in order, so
+/// that every byte of the text is covered by exactly one part.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitParts<'r, 't> = haystack::SplitParts<'r, 't, [u8]>;
+
+/// Yields the same substrings as [`SplitInclusive`], each paired with its byte
+/// offset in the text being split.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitInclusiveIndices<'r, 't> = haystack::SplitInclusiveIndices<'r, 't, [u8]>;
+
+/// Yields the same substrings as [`SplitInclusiveLeft`], each paired with its
+/// byte offset in the text being split.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitInclusiveLeftIndices<'r, 't> = haystack::SplitInclusiveLeftIndices<'r, 't, [u8]>;
+
+/// A substring yielded by `split_inclusive_segments` or
+/// `split_inclusive_left_segments`, which can be taken apart into the match
+/// that delimits it and the rest of its text, its body.
+pub type Segment<'t> = haystack::Segment<'t, [u8]>;
+
+/// Yields the same substrings as [`SplitInclusive`] as [`Segment`]s, which
+/// keep track of where the delimiting match is.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitInclusiveSegments<'r, 't> = haystack::SplitInclusiveSegments<'r, 't, [u8]>;
+
+/// Yields the same substrings as [`SplitInclusiveLeft`] as [`Segment`]s,
+/// which keep track of where the delimiting match is.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitInclusiveLeftSegments<'r, 't> = haystack::SplitInclusiveLeftSegments<'r, 't, [u8]>;
+
+/// Yields the substrings from a split into [`Segment`]s, each with its index
+/// and delimiting match. Built by the `enumerated` method of the segment
+/// iterators.
+pub type Enumerated<I> = haystack::Enumerated<I>;
+
+/// A section of a text yielded by `sections`: a header matched by the
+/// regular expression and the body that follows it up to the next header.
+pub type Section<'t> = haystack::Section<'t, [u8]>;
+
+/// Yields the sections of a text that begin with a regular expression match,
+/// as [`Section`]s.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type Sections<'r, 't> = haystack::Sections<'r, 't, [u8]>;
+
+/// A substring of a text split by several regular expressions in turn, as
+/// built by [`split_tree`], together with the substrings it was split into.
+pub type SegmentTree<'t> = haystack::SegmentTree<'t, [u8]>;
+
+/// Splits `text` with each of `levels` in turn: the first regular expression
+/// splits the whole text, the second splits each of the resulting substrings,
+/// and so on, as with `split_inclusive`. Returns a tree whose root covers the
+/// whole text and whose nodes at depth `n` were split out by `levels[n - 1]`.
+/// A substring with no match at some level has one child, covering all of it.
+///
+/// Substrings are split on their own, so `^` and `$` match at their ends
+/// without multi-line mode.
+///
+/// # Example
+///
+/// ```rust
+/// # use regex::bytes::Regex;
+/// # use crate::regex_split::bytes::split_tree;
+/// # fn main() {
+/// let paragraphs = Regex::new(r"\n\n").unwrap();
+/// let sentences = Regex::new(r"\. ").unwrap();
+/// let text = b"One. Two.\n\nThree.";
+/// let tree = split_tree(text, &[&paragraphs, &sentences]);
+/// let v: Vec<Vec<&[u8]>> = tree
+///     .children()
+///     .iter()
+///     .map(|paragraph| paragraph.children().iter().map(|s| s.full()).collect())
+///     .collect();
+/// assert_eq!(v, [vec![&b"One. "[..], &b"Two.\n\n"[..]], vec![&b"Three."[..]]]);
+/// assert_eq!(tree.children()[1].range(), 11..17);
+/// # }
+/// ```
+pub fn split_tree<'t, R>(text: &'t [u8], levels: &[R]) -> SegmentTree<'t>
+where
+    R: Borrow<Regex>,
+{
+    haystack::split_tree(text, levels)
+}
+
+/// Yields the pieces of a text split by a list of regular expressions in
+/// order of priority, as built by [`split_recursive`].
+///
+/// `'r` is the lifetime of the list of regular expressions and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitRecursive<'r, 't, R> = haystack::SplitRecursive<'r, 't, [u8], R>;
+
+/// Splits `text` into pieces no longer than `max_len` where possible, trying
+/// `patterns` in order of priority, like the recursive character splitting
+/// of text chunkers. A region longer than `max_len` is split with the first
+/// pattern that matches in it, as with `split_inclusive`, and any piece still
+/// too long is split the same way with the patterns after that one. Pieces
+/// that no remaining pattern can split are yielded as they are, however
+/// long.
+///
+/// Each piece is paired with the index in `patterns` of the pattern whose
+/// match ends it, or `None` for the piece at the end of the text.
+///
+/// This function will *not* copy the text given.
+///
+/// # Example
+///
+/// ```rust
+/// # use regex::bytes::Regex;
+/// # use crate::regex_split::bytes::split_recursive;
+/// # fn main() {
+/// let patterns = [Regex::new(r"\n\n").unwrap(), Regex::new(r"\. ").unwrap()];
+/// let text = b"Short.\n\nA longer one. It goes on.";
+/// let v: Vec<_> = split_recursive(text, &patterns, 16).collect();
+/// assert_eq!(v, [
+///     (&b"Short.\n\n"[..], Some(0)),
+///     (&b"A longer one. "[..], Some(1)),
+///     (&b"It goes on."[..], None),
+/// ]);
+/// # }
+/// ```
+pub fn split_recursive<'r, 't, R>(
+    text: &'t [u8],
+    patterns: &'r [R],
+    max_len: usize,
+) -> SplitRecursive<'r, 't, R>
+where
+    R: Borrow<Regex>,
+{
+    haystack::SplitRecursive::new(text, patterns, max_len)
+}
+
+/// Splits text on a match of any of the patterns in a `RegexSet`, telling
+/// which pattern matched. A `RegexSet` can only tell which patterns match, not
+/// where, so the patterns are compiled again one by one.
+///
+/// # Example
+///
+/// ```rust
+/// # use regex::bytes::RegexSet;
+/// # use crate::regex_split::bytes::SetSplitter;
+/// # fn main() {
+/// let set = RegexSet::new([r"(?m)^[A-Z]+: ", r"(?m)^\[[a-z]+\] "]).unwrap();
+/// let splitter = SetSplitter::new(&set).unwrap();
+/// let text = b"ALICE: hi\n[bob] hello\nALICE: bye";
+/// let v: Vec<_> = splitter.split_inclusive_left(text).collect();
+/// assert_eq!(v, [
+///     (&b""[..], None),
+///     (&b"ALICE: hi\n"[..], Some(0)),
+///     (&b"[bob] hello\n"[..], Some(1)),
+///     (&b"ALICE: bye"[..], Some(0)),
+/// ]);
+/// # }
+/// ```
+pub type SetSplitter = haystack::SetSplitter<[u8]>;
+
+/// Splits text on a match of any of several regular expressions, telling
+/// which one matched. Where matches of different regular expressions overlap,
+/// the one listed first wins, even if the other begins earlier.
+///
+/// # Example
+///
+/// ```rust
+/// # use regex::bytes::Regex;
+/// # use crate::regex_split::bytes::MultiSplitter;
+/// # fn main() {
+/// let sentence = Regex::new(r"[.!?] +").unwrap();
+/// let ellipsis = Regex::new(r"\.\.\. +").unwrap();
+/// let splitter = MultiSplitter::new(&[&ellipsis, &sentence]);
+/// let text = b"Wait... What? Oh.";
+/// let v: Vec<_> = splitter.split_inclusive(text).collect();
+/// assert_eq!(v, [
+///     (&b"Wait... "[..], Some(0)),
+///     (&b"What? "[..], Some(1)),
+///     (&b"Oh."[..], None),
+/// ]);
+/// # }
+/// ```
+pub type MultiSplitter = haystack::MultiSplitter<[u8]>;
+
+/// Yields all substrings delimited by a match of any pattern of a
+/// [`SetSplitter`] or [`MultiSplitter`], with the match included at the end of
+/// the substring, each paired with the index of the pattern that matched.
+///
+/// `'s` is the lifetime of the splitter and `'t` is the lifetime of the byte
+/// string being split.
+pub type SetSplitInclusive<'s, 't> = haystack::SetSplitInclusive<'s, 't, [u8]>;
+
+/// Yields all substrings delimited by a match of any pattern of a
+/// [`SetSplitter`] or [`MultiSplitter`], with the match included at the start
+/// of the substring, each paired with the index of the pattern that matched.
+///
+/// `'s` is the lifetime of the splitter and `'t` is the lifetime of the byte
+/// string being split.
+pub type SetSplitInclusiveLeft<'s, 't> = haystack::SetSplitInclusiveLeft<'s, 't, [u8]>;
 
 impl RegexSplit for Regex {
     /// Returns an iterator of substrings of `text` separated by a match of the
@@ -114,11 +855,7 @@ impl RegexSplit for Regex {
     /// # }
     /// ```
     fn split_inclusive<'r, 't>(&'r self, text: &'t [u8]) -> SplitInclusive<'r, 't> {
-        SplitInclusive {
-            finder: self.find_iter(text),
-            last: 0,
-            text,
-        }
+        SplitInclusive::new(self, text)
     }
 
     /// Returns an iterator of substrings of `text` separated by a match of the
@@ -145,10 +882,1380 @@ impl RegexSplit for Regex {
     /// # }
     /// ```
     fn split_inclusive_left<'r, 't>(&'r self, text: &'t [u8]) -> SplitInclusiveLeft<'r, 't> {
-        SplitInclusiveLeft {
-            finder: self.find_iter(text),
-            last: 0,
-            text,
-        }
+        SplitInclusiveLeft::new(self, text)
+    }
+    /// Returns an iterator of at most `n` substrings of `text` separated by a
+    /// match of the regular expression, starting from the end of the text.
+    /// Like `split_inclusive`, the matched part is left as the terminator of
+    /// each substring. The final item yielded is the unsplit remainder at the
+    /// front of the text.
+    ///
+    /// Only the positions of the last `n - 1` matches are kept, so this is
+    /// cheap even when the text contains many more matches than that.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = b"Mary had a little lamb\nlittle lamb\r\nlittle lamb.";
+    /// let v: Vec<&[u8]> = re.rsplitn_inclusive(text, 2).collect();
+    /// assert_eq!(v, [
+    ///     &b"little lamb."[..],
+    ///     &b"Mary had a little lamb\nlittle lamb\r\n"[..],
+    /// ]);
+    /// # }
+    /// ```
+    fn rsplitn_inclusive<'r, 't>(&'r self, text: &'t [u8], n: usize) -> RSplitNInclusive<'r, 't> {
+        RSplitNInclusive::new(self, text, n)
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of the
+    /// regular expression, exactly like `split_inclusive`, except that the
+    /// trailing substring is skipped if it is empty. This happens when the
+    /// text ends with a match, just as with `split_terminator` in std.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = b"Mary had a little lamb\nlittle lamb\r\nlittle lamb.\n";
+    /// let v: Vec<&[u8]> = re.split_inclusive_terminator(text).collect();
+    /// assert_eq!(v, [
+    ///     &b"Mary had a little lamb\n"[..],
+    ///     &b"little lamb\r\n"[..],
+    ///     &b"little lamb.\n"[..],
+    /// ]);
+    /// # }
+    /// ```
+    fn split_inclusive_terminator<'r, 't>(&'r self, text: &'t [u8]) -> SplitInclusive<'r, 't> {
+        self.split_inclusive(text)
+            .trailing_empty(TrailingEmpty::Drop)
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of the
+    /// regular expression, exactly like `split_inclusive_left`, except that
+    /// the trailing substring is skipped if it is empty. This happens when
+    /// the text ends with an empty match.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"(?m)$").unwrap();
+    /// let text = b"Mary had a little lamb\nlittle lamb\nlittle lamb.";
+    /// let v: Vec<&[u8]> = re.split_inclusive_left_terminator(text).collect();
+    /// assert_eq!(v, [
+    ///     &b"Mary had a little lamb"[..],
+    ///     &b"\nlittle lamb"[..],
+    ///     &b"\nlittle lamb."[..],
+    /// ]);
+    /// # }
+    /// ```
+    fn split_inclusive_left_terminator<'r, 't>(
+        &'r self,
+        text: &'t [u8],
+    ) -> SplitInclusiveLeft<'r, 't> {
+        self.split_inclusive_left(text)
+            .trailing_empty(TrailingEmpty::Drop)
+    }
+
+    /// Returns an iterator of substrings of `text[start..]` separated by a
+    /// match of the regular expression, like `split_inclusive`. Unlike
+    /// splitting a subslice, the regular expression still sees all of `text`,
+    /// so `^`, `\A` and `\b` mean the same thing they would if splitting had
+    /// begun at the start.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start` is greater than the length of `text`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"(?m)\n^#").unwrap();
+    /// let text = b"# one\n# two\n# three";
+    /// let v: Vec<&[u8]> = re.split_inclusive_at(text, 2).collect();
+    /// assert_eq!(v, [
+    ///     &b"one\n#"[..],
+    ///     &b" two\n#"[..],
+    ///     &b" three"[..],
+    /// ]);
+    ///
+    /// let re = Regex::new(r"^#").unwrap();
+    /// assert_eq!(re.split_inclusive_at(text, 2).count(), 1);
+    /// # }
+    /// ```
+    fn split_inclusive_at<'r, 't>(
+        &'r self,
+        text: &'t [u8],
+        start: usize,
+    ) -> SplitInclusive<'r, 't> {
+        SplitInclusive::new_at(self, text, start)
+    }
+
+    /// Splits `text` in two at the first match of the regular expression,
+    /// leaving the matched part at the end of the first half. Returns `None`
+    /// if there is no match.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n\n").unwrap();
+    /// let text = b"From: Mary\n\nlittle lamb\n\nlittle lamb";
+    /// let (head, body) = re.split_once_inclusive(text).unwrap();
+    /// assert_eq!(head, &b"From: Mary\n\n"[..]);
+    /// assert_eq!(body, &b"little lamb\n\nlittle lamb"[..]);
+    /// # }
+    /// ```
+    fn split_once_inclusive<'t>(&self, text: &'t [u8]) -> Option<(&'t [u8], &'t [u8])> {
+        let m = self.find(text)?;
+        Some((&text[..m.end()], &text[m.end()..]))
+    }
+
+    /// Splits `text` in two at the first match of the regular expression,
+    /// leaving the matched part at the start of the second half. Returns
+    /// `None` if there is no match.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n\n").unwrap();
+    /// let text = b"From: Mary\n\nlittle lamb\n\nlittle lamb";
+    /// let (head, body) = re.split_once_inclusive_left(text).unwrap();
+    /// assert_eq!(head, &b"From: Mary"[..]);
+    /// assert_eq!(body, &b"\n\nlittle lamb\n\nlittle lamb"[..]);
+    /// # }
+    /// ```
+    fn split_once_inclusive_left<'t>(&self, text: &'t [u8]) -> Option<(&'t [u8], &'t [u8])> {
+        let m = self.find(text)?;
+        Some((&text[..m.start()], &text[m.start()..]))
+    }
+
+    /// Splits `text` in two at the last match of the regular expression,
+    /// leaving the matched part at the end of the first half. Returns `None`
+    /// if there is no match.
+    ///
+    /// The regex crate can't search backwards, so this scans the whole text.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n\n").unwrap();
+    /// let text = b"From: Mary\n\nlittle lamb\n\nlittle lamb";
+    /// let (head, tail) = re.rsplit_once_inclusive(text).unwrap();
+    /// assert_eq!(head, &b"From: Mary\n\nlittle lamb\n\n"[..]);
+    /// assert_eq!(tail, &b"little lamb"[..]);
+    /// # }
+    /// ```
+    fn rsplit_once_inclusive<'t>(&self, text: &'t [u8]) -> Option<(&'t [u8], &'t [u8])> {
+        let m = self.find_iter(text).last()?;
+        Some((&text[..m.end()], &text[m.end()..]))
+    }
+
+    /// Splits `text` in two at the last match of the regular expression,
+    /// leaving the matched part at the start of the second half. Returns
+    /// `None` if there is no match.
+    ///
+    /// The regex crate can't search backwards, so this scans the whole text.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n\n").unwrap();
+    /// let text = b"From: Mary\n\nlittle lamb\n\nlittle lamb";
+    /// let (head, tail) = re.rsplit_once_inclusive_left(text).unwrap();
+    /// assert_eq!(head, &b"From: Mary\n\nlittle lamb"[..]);
+    /// assert_eq!(tail, &b"\n\nlittle lamb"[..]);
+    /// # }
+    /// ```
+    fn rsplit_once_inclusive_left<'t>(&self, text: &'t [u8]) -> Option<(&'t [u8], &'t [u8])> {
+        let m = self.find_iter(text).last()?;
+        Some((&text[..m.start()], &text[m.start()..]))
+    }
+
+    /// Splits `text` in two at the `n`th match of the regular expression,
+    /// counting from zero, leaving the matched part at the end of the first
+    /// half. Returns `None` if there are `n` matches or fewer. Only searches
+    /// as far as the `n`th match.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text = b"header\nrecord one\nrecord two\nrecord three";
+    /// let (skipped, rest) = re.partition_at_nth_match(text, 1).unwrap();
+    /// assert_eq!(skipped, &b"header\nrecord one\n"[..]);
+    /// assert_eq!(rest, &b"record two\nrecord three"[..]);
+    /// assert_eq!(re.partition_at_nth_match(text, 3), None);
+    /// # }
+    /// ```
+    fn partition_at_nth_match<'t>(&self, text: &'t [u8], n: usize) -> Option<(&'t [u8], &'t [u8])> {
+        let m = self.find_iter(text).nth(n)?;
+        Some((&text[..m.end()], &text[m.end()..]))
+    }
+
+    /// Splits `text` in two at the `n`th match of the regular expression,
+    /// counting from zero, leaving the matched part at the start of the
+    /// second half. Returns `None` if there are `n` matches or fewer. Only
+    /// searches as far as the `n`th match.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"(?m)^>").unwrap();
+    /// let text = b">one\nACGT\n>two\nTTGA\n>three\nGGCA";
+    /// let (skipped, rest) = re.partition_at_nth_match_left(text, 2).unwrap();
+    /// assert_eq!(skipped, &b">one\nACGT\n>two\nTTGA\n"[..]);
+    /// assert_eq!(rest, &b">three\nGGCA"[..]);
+    /// # }
+    /// ```
+    fn partition_at_nth_match_left<'t>(
+        &self,
+        text: &'t [u8],
+        n: usize,
+    ) -> Option<(&'t [u8], &'t [u8])> {
+        let m = self.find_iter(text).nth(n)?;
+        Some((&text[..m.start()], &text[m.start()..]))
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of the
+    /// regular expression, yielding the same substrings as `split_inclusive`
+    /// but starting from the end of the text.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = b"Mary had a little lamb\nlittle lamb\r\nlittle lamb.";
+    /// let v: Vec<&[u8]> = re.split_inclusive_rev(text).collect();
+    /// assert_eq!(v, [
+    ///     &b"little lamb."[..],
+    ///     &b"little lamb\r\n"[..],
+    ///     &b"Mary had a little lamb\n"[..],
+    /// ]);
+    /// # }
+    /// ```
+    fn split_inclusive_rev<'r, 't>(&'r self, text: &'t [u8]) -> SplitInclusiveRev<'r, 't> {
+        SplitInclusiveRev::new(self, text)
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of the
+    /// regular expression, yielding the same substrings as
+    /// `split_inclusive_left` but starting from the end of the text.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = b"Mary had a little lamb\nlittle lamb\r\nlittle lamb.";
+    /// let v: Vec<&[u8]> = re.split_inclusive_left_rev(text).collect();
+    /// assert_eq!(v, [
+    ///     &b"\r\nlittle lamb."[..],
+    ///     &b"\nlittle lamb"[..],
+    ///     &b"Mary had a little lamb"[..],
+    /// ]);
+    /// # }
+    /// ```
+    fn split_inclusive_left_rev<'r, 't>(&'r self, text: &'t [u8]) -> SplitInclusiveLeftRev<'r, 't> {
+        SplitInclusiveLeftRev::new(self, text)
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of the
+    /// regular expression, where each match appears twice: as the terminator
+    /// of the substring before it and as the beginning of the substring after
+    /// it.
+    ///
+    /// This method will *not* copy the text given; the substrings simply
+    /// overlap.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = b"Mary had a little lamb\nlittle lamb\r\nlittle lamb.";
+    /// let v: Vec<&[u8]> = re.split_inclusive_both(text).collect();
+    /// assert_eq!(v, [
+    ///     &b"Mary had a little lamb\n"[..],
+    ///     &b"\nlittle lamb\r\n"[..],
+    ///     &b"\r\nlittle lamb."[..],
+    /// ]);
+    /// # }
+    /// ```
+    fn split_inclusive_both<'r, 't>(&'r self, text: &'t [u8]) -> SplitInclusiveBoth<'r, 't> {
+        SplitInclusiveBoth::new(self, text)
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of the
+    /// regular expression, where `side` decides for each match whether it
+    /// goes at the end of the substring before it, at the start of the one
+    /// after it, in both, or in neither. See [`Side`].
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::{bytes::RegexSplit, Side};
+    /// # fn main() {
+    /// let re = Regex::new(r"(?m);\n|^#").unwrap();
+    /// let text = b"a;\nb;\n#c\nd;\n#e";
+    /// let v: Vec<&[u8]> = re
+    ///     .split_inclusive_by(text, |m| match m.as_bytes() {
+    ///         b"#" => Side::Right,
+    ///         _ => Side::Left,
+    ///     })
+    ///     .collect();
+    /// assert_eq!(v, [
+    ///     &b"a;\n"[..],
+    ///     &b"b;\n"[..],
+    ///     &b""[..],
+    ///     &b"#c\nd;\n"[..],
+    ///     &b""[..],
+    ///     &b"#e"[..],
+    /// ]);
+    /// # }
+    /// ```
+    fn split_inclusive_by<'r, 't, F>(
+        &'r self,
+        text: &'t [u8],
+        side: F,
+    ) -> SplitInclusiveBy<'r, 't, F>
+    where
+        F: FnMut(&Match<'t>) -> Side,
+    {
+        SplitInclusiveBy::new(self, text, side)
+    }
+
+    /// Returns an iterator of substrings of `text` separated by capture group
+    /// `group` of each match of the regular expression, rather than by the
+    /// whole match. The group's text goes at the end of the substring before
+    /// it, like with `split_inclusive`, and whatever else the match covers is
+    /// left where it is. Matches in which the group doesn't participate are
+    /// ignored.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the regular expression has no group numbered `group`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"(,)\s*").unwrap();
+    /// let text = b"one,  two, three";
+    /// let v: Vec<&[u8]> = re.split_on_group(text, 1).collect();
+    /// assert_eq!(v, [
+    ///     &b"one,"[..],
+    ///     &b"  two,"[..],
+    ///     &b" three"[..],
+    /// ]);
+    /// # }
+    /// ```
+    fn split_on_group<'r, 't>(&'r self, text: &'t [u8], group: usize) -> SplitOnGroup<'r, 't> {
+        assert!(
+            group < self.captures_len(),
+            "no capture group numbered {}",
+            group
+        );
+        SplitOnGroup::new(self, text, group)
+    }
+
+    /// Like `split_on_group`, but names the capture group to split on.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the regular expression has no group named `name`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"(?P<sep>,)\s*").unwrap();
+    /// let text = b"one,  two, three";
+    /// let v: Vec<&[u8]> = re.split_on_name(text, "sep").collect();
+    /// assert_eq!(v, [
+    ///     &b"one,"[..],
+    ///     &b"  two,"[..],
+    ///     &b" three"[..],
+    /// ]);
+    /// # }
+    /// ```
+    fn split_on_name<'r, 't>(&'r self, text: &'t [u8], name: &str) -> SplitOnGroup<'r, 't> {
+        let group = self
+            .capture_names()
+            .position(|n| n == Some(name))
+            .unwrap_or_else(|| panic!("no capture group named {:?}", name));
+        self.split_on_group(text, group)
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of the
+    /// regular expression, where the part of each match captured by a group
+    /// named `left` goes at the end of the substring before it and the part
+    /// captured by a group named `right` goes at the start of the substring
+    /// after it. Anything else in the match is dropped, like with
+    /// `Regex::split`. This does the job of lookbehind and lookahead, which
+    /// the regex crate doesn't support.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"(?P<left>[.?!])\s+(?P<right>[A-Z])").unwrap();
+    /// let text = b"It rained. Then it stopped!  Nobody cared.";
+    /// let v: Vec<&[u8]> = re.split_around(text).collect();
+    /// assert_eq!(v, [
+    ///     &b"It rained."[..],
+    ///     &b"Then it stopped!"[..],
+    ///     &b"Nobody cared."[..],
+    /// ]);
+    /// # }
+    /// ```
+    fn split_around<'r, 't>(&'r self, text: &'t [u8]) -> SplitAround<'r, 't> {
+        SplitAround::new(self, text)
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of the
+    /// regular expression, like `split_inclusive`, except that each substring
+    /// is paired with the capture groups of the match that ends it. The last
+    /// substring is paired with `None`.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r" @(?P<hour>\d\d):(?P<minute>\d\d)\n").unwrap();
+    /// let text = &b"started @09:30\nstopped @17:05\nno time"[..];
+    /// let v: Vec<_> = re
+    ///     .split_inclusive_captures(text)
+    ///     .map(|(s, c)| (s, c.map(|c| [&c["hour"], &c["minute"]].join(&b':'))))
+    ///     .collect();
+    /// assert_eq!(v, [
+    ///     (&b"started @09:30\n"[..], Some(b"09:30".to_vec())),
+    ///     (&b"stopped @17:05\n"[..], Some(b"17:05".to_vec())),
+    ///     (&b"no time"[..], None),
+    /// ]);
+    /// # }
+    /// ```
+    fn split_inclusive_captures<'r, 't>(
+        &'r self,
+        text: &'t [u8],
+    ) -> SplitInclusiveCaptures<'r, 't> {
+        SplitInclusiveCaptures::new(self, text)
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of the
+    /// regular expression, like `split_inclusive`, except that each substring
+    /// is paired with the name of the first named group that took part in
+    /// the match that ends it. Naming the branches of an alternation this way
+    /// tells which kind of delimiter each one is. The last substring, and any
+    /// ended by a match without a named group, is paired with `None`.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"(?P<crlf>\r\n)|(?P<lf>\n)|(?P<ff>\x0c)").unwrap();
+    /// let text = b"one\r\ntwo\nthree\x0cfour";
+    /// let v: Vec<_> = re.split_inclusive_tagged(text).collect();
+    /// assert_eq!(v, [
+    ///     (&b"one\r\n"[..], Some("crlf")),
+    ///     (&b"two\n"[..], Some("lf")),
+    ///     (&b"three\x0c"[..], Some("ff")),
+    ///     (&b"four"[..], None),
+    /// ]);
+    /// # }
+    /// ```
+    fn split_inclusive_tagged<'r, 't>(&'r self, text: &'t [u8]) -> SplitInclusiveTagged<'r, 't> {
+        SplitInclusiveTagged::new(self, text)
+    }
+
+    /// Returns an iterator of the sections of `text` that begin with a match
+    /// of the regular expression, each paired with the text captured by the
+    /// group named `name` in that match, or `None` if the group didn't
+    /// participate. The sections are split as by `split_inclusive_left`, so
+    /// each begins with its match. Text before the first match, if there is
+    /// any, is paired with `None`.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the regular expression has no group named `name`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"(?m)^\[(?P<name>\w+)\]\n").unwrap();
+    /// let text = b"debug = true\n[server]\nport = 80\n[client]\nretries = 3\n";
+    /// let v: Vec<_> = re.group_by_capture(text, "name").collect();
+    /// assert_eq!(v, [
+    ///     (None, &b"debug = true\n"[..]),
+    ///     (Some(&b"server"[..]), &b"[server]\nport = 80\n"[..]),
+    ///     (Some(&b"client"[..]), &b"[client]\nretries = 3\n"[..]),
+    /// ]);
+    /// # }
+    /// ```
+    fn group_by_capture<'r, 't>(&'r self, text: &'t [u8], name: &str) -> GroupByCapture<'r, 't> {
+        let group = self
+            .capture_names()
+            .position(|n| n == Some(name))
+            .unwrap_or_else(|| panic!("no capture group named {:?}", name));
+        GroupByCapture::new(self, text, group)
+    }
+
+    /// Returns an iterator of the regions of `text` that begin after a match
+    /// of this regular expression and end before the next match of `end`
+    /// that follows it. Text outside the regions is skipped. A region with no
+    /// closing match runs to the end of the text. Use `include_markers` to
+    /// keep the matches in the regions.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let begin = Regex::new(r"(?m)^BEGIN\n").unwrap();
+    /// let end = Regex::new(r"(?m)^END\n").unwrap();
+    /// let text = b"noise\nBEGIN\none\nEND\nnoise\nBEGIN\ntwo\nEND\n";
+    /// let v: Vec<&[u8]> = begin.split_between(text, &end).collect();
+    /// assert_eq!(v, [
+    ///     &b"one\n"[..],
+    ///     &b"two\n"[..],
+    /// ]);
+    /// # }
+    /// ```
+    fn split_between<'r, 't>(&'r self, text: &'t [u8], end: &'r Regex) -> SplitBetween<'r, 't> {
+        SplitBetween::new(self, end, text)
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of the
+    /// regular expression, like `split_inclusive`, except that the text is
+    /// never split inside a region matched by `mask`: any match overlapping
+    /// such a region is ignored. This expresses "split on this, unless
+    /// inside that", such as splitting on commas outside quoted strings.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r",").unwrap();
+    /// let quoted = Regex::new(r#""[^"]*""#).unwrap();
+    /// let text = &br#"one,"two, three",four"#[..];
+    /// let v: Vec<&[u8]> = re.split_inclusive_outside(text, &quoted).collect();
+    /// assert_eq!(v, [
+    ///     &b"one,"[..],
+    ///     &br#""two, three","#[..],
+    ///     &b"four"[..],
+    /// ]);
+    /// # }
+    /// ```
+    fn split_inclusive_outside<'r, 't>(
+        &'r self,
+        text: &'t [u8],
+        mask: &'r Regex,
+    ) -> SplitInclusiveOutside<'r, 't> {
+        SplitInclusiveOutside::new(self, mask, text)
+    }
+
+    /// Returns an iterator of contiguous slices of `text` that each end with
+    /// every `n`th match of the regular expression, so that each holds `n`
+    /// records split as by `split_inclusive`. Whatever follows the last full
+    /// chunk is yielded as a shorter one, unless it is empty.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text = b"1\n2\n3\n4\n5\n";
+    /// let v: Vec<&[u8]> = re.chunks_by_matches(text, 2).collect();
+    /// assert_eq!(v, [
+    ///     &b"1\n2\n"[..],
+    ///     &b"3\n4\n"[..],
+    ///     &b"5\n"[..],
+    /// ]);
+    /// # }
+    /// ```
+    fn chunks_by_matches<'r, 't>(&'r self, text: &'t [u8], n: usize) -> ChunksByMatches<'r, 't> {
+        assert!(n != 0, "chunk size must be non-zero");
+        ChunksByMatches::new(self, text, n)
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of the
+    /// regular expression, like `Regex::split`, except that each substring is
+    /// paired with the match that separated it from the next one. The last
+    /// substring is paired with `None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = b"Mary had a little lamb\nlittle lamb\r\nlittle lamb.";
+    /// let v: Vec<_> = re
+    ///     .split_with_delimiters(text)
+    ///     .map(|(s, m)| (s, m.map(|m| (m.start(), m.as_bytes()))))
+    ///     .collect();
+    /// assert_eq!(v, [
+    ///     (&b"Mary had a little lamb"[..], Some((22, &b"\n"[..]))),
+    ///     (&b"little lamb"[..], Some((34, &b"\r\n"[..]))),
+    ///     (&b"little lamb."[..], None),
+    /// ]);
+    /// # }
+    /// ```
+    fn split_with_delimiters<'r, 't>(&'r self, text: &'t [u8]) -> SplitWithDelimiters<'r, 't> {
+        SplitWithDelimiters::new(self, text)
+    }
+
+    /// Returns an iterator of the matches of the regular expression in
+    /// `text`, each in a slice of the text with up to `n` bytes of context on
+    /// either side, along with the range of the match within the slice.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\x00+").unwrap();
+    /// let v: Vec<_> = re.matches_with_context(b"head\x00\x00tail", 2).collect();
+    /// assert_eq!(v, [(&b"ad\x00\x00ta"[..], 2..4)]);
+    /// # }
+    /// ```
+    fn matches_with_context<'r, 't>(
+        &'r self,
+        text: &'t [u8],
+        n: usize,
+    ) -> MatchesWithContext<'r, 't> {
+        MatchesWithContext::new(self, text, n)
+    }
+
+    /// Returns an iterator that alternates between the text separating matches
+    /// of the regular expression and the matches themselves. Together, the
+    /// parts cover every byte of `text` exactly once; empty runs of text
+    /// between adjacent matches are not yielded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::{Part, RegexSplit};
+    /// # fn main() {
+    /// let re = Regex::new(r"[+-]").unwrap();
+    /// let text = b"1+2-3";
+    /// let v: Vec<_> = re
+    ///     .split_parts(text)
+    ///     .map(|part| match part {
+    ///         Part::Text(s) => ("text", s),
+    ///         Part::Delim(m) => ("delim", m.as_bytes()),
+    ///     })
+    ///     .collect();
+    /// assert_eq!(v, [
+    ///     ("text", &b"1"[..]),
+    ///     ("delim", &b"+"[..]),
+    ///     ("text", &b"2"[..]),
+    ///     ("delim", &b"-"[..]),
+    ///     ("text", &b"3"[..]),
+    /// ]);
+    /// # }
+    /// ```
+    fn split_parts<'r, 't>(&'r self, text: &'t [u8]) -> SplitParts<'r, 't> {
+        SplitParts::new(self, text)
+    }
+
+    /// Returns an iterator of the same substrings as `split_inclusive`, each
+    /// paired with the byte offset at which it starts in `text`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = b"Mary had a little lamb\nlittle lamb\r\nlittle lamb.";
+    /// let v: Vec<(usize, &[u8])> = re.split_inclusive_indices(text).collect();
+    /// assert_eq!(v, [
+    ///     (0, &b"Mary had a little lamb\n"[..]),
+    ///     (23, &b"little lamb\r\n"[..]),
+    ///     (36, &b"little lamb."[..]),
+    /// ]);
+    /// # }
+    /// ```
+    fn split_inclusive_indices<'r, 't>(&'r self, text: &'t [u8]) -> SplitInclusiveIndices<'r, 't> {
+        SplitInclusiveIndices::new(self, text)
+    }
+
+    /// Returns an iterator of the same substrings as `split_inclusive_left`,
+    /// each paired with the byte offset at which it starts in `text`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = b"Mary had a little lamb\nlittle lamb\r\nlittle lamb.";
+    /// let v: Vec<(usize, &[u8])> = re.split_inclusive_left_indices(text).collect();
+    /// assert_eq!(v, [
+    ///     (0, &b"Mary had a little lamb"[..]),
+    ///     (22, &b"\nlittle lamb"[..]),
+    ///     (34, &b"\r\nlittle lamb."[..]),
+    /// ]);
+    /// # }
+    /// ```
+    fn split_inclusive_left_indices<'r, 't>(
+        &'r self,
+        text: &'t [u8],
+    ) -> SplitInclusiveLeftIndices<'r, 't> {
+        SplitInclusiveLeftIndices::new(self, text)
+    }
+
+    /// Returns an iterator of the byte ranges of the substrings that
+    /// `split_inclusive` would yield. The ranges don't borrow `text`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = b"Mary had a little lamb\nlittle lamb\r\nlittle lamb.";
+    /// let v: Vec<_> = re.split_inclusive_ranges(text).collect();
+    /// assert_eq!(v, [0..23, 23..36, 36..48]);
+    /// # }
+    /// ```
+    fn split_inclusive_ranges<'r, 't>(&'r self, text: &'t [u8]) -> SplitInclusiveRanges<'r, 't> {
+        SplitInclusiveRanges {
+            finder: RangeFinder::Bytes(MatchesAt::new(self, text, 0)),
+            last: Some(0),
+            len: text.len(),
+        }
+    }
+
+    /// Returns an iterator of the byte ranges of the substrings that
+    /// `split_inclusive_left` would yield. The ranges don't borrow `text`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = b"Mary had a little lamb\nlittle lamb\r\nlittle lamb.";
+    /// let v: Vec<_> = re.split_inclusive_left_ranges(text).collect();
+    /// assert_eq!(v, [0..22, 22..34, 34..48]);
+    /// # }
+    /// ```
+    fn split_inclusive_left_ranges<'r, 't>(
+        &'r self,
+        text: &'t [u8],
+    ) -> SplitInclusiveLeftRanges<'r, 't> {
+        SplitInclusiveLeftRanges {
+            finder: RangeFinder::Bytes(MatchesAt::new(self, text, 0)),
+            last: Some(0),
+            len: text.len(),
+        }
+    }
+
+    /// Returns an iterator of the same substrings as `split_inclusive`, as
+    /// [`Segment`]s that can be taken apart into body and delimiter.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = b"Mary had a little lamb\r\nlittle lamb.";
+    /// let v: Vec<_> = re.split_inclusive_segments(text).collect();
+    /// assert_eq!(v[0].full(), &b"Mary had a little lamb\r\n"[..]);
+    /// assert_eq!(v[0].body(), &b"Mary had a little lamb"[..]);
+    /// assert_eq!(v[0].delimiter(), Some(&b"\r\n"[..]));
+    /// assert_eq!(v[0].delimiter_range(), Some(22..24));
+    /// assert_eq!(v[1].body(), &b"little lamb."[..]);
+    /// assert_eq!(v[1].delimiter(), None);
+    /// # }
+    /// ```
+    fn split_inclusive_segments<'r, 't>(
+        &'r self,
+        text: &'t [u8],
+    ) -> SplitInclusiveSegments<'r, 't> {
+        SplitInclusiveSegments::new(self, text)
+    }
+
+    /// Returns an iterator of the same substrings as `split_inclusive_left`,
+    /// as [`Segment`]s that can be taken apart into body and delimiter.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = b"Mary had a little lamb\r\nlittle lamb.";
+    /// let v: Vec<_> = re.split_inclusive_left_segments(text).collect();
+    /// assert_eq!(v[0].body(), &b"Mary had a little lamb"[..]);
+    /// assert_eq!(v[0].delimiter(), None);
+    /// assert_eq!(v[1].full(), &b"\r\nlittle lamb."[..]);
+    /// assert_eq!(v[1].body(), &b"little lamb."[..]);
+    /// assert_eq!(v[1].body_range(), 24..36);
+    /// assert_eq!(v[1].delimiter(), Some(&b"\r\n"[..]));
+    /// # }
+    /// ```
+    fn split_inclusive_left_segments<'r, 't>(
+        &'r self,
+        text: &'t [u8],
+    ) -> SplitInclusiveLeftSegments<'r, 't> {
+        SplitInclusiveLeftSegments::new(self, text)
+    }
+
+    /// Returns an iterator of the sections of `text`, each a header matched
+    /// by the regular expression and the body following it up to the next
+    /// header, split as by `split_inclusive_left`. Text before the first
+    /// header, if there is any, is yielded as a section without one.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"(?m)^#+ .*\n").unwrap();
+    /// let text = b"# Intro\nHello.\n## Usage\nRun it.\n";
+    /// let v: Vec<_> = re
+    ///     .sections(text)
+    ///     .map(|section| (section.header(), section.body()))
+    ///     .collect();
+    /// assert_eq!(v, [
+    ///     (Some(&b"# Intro\n"[..]), &b"Hello.\n"[..]),
+    ///     (Some(&b"## Usage\n"[..]), &b"Run it.\n"[..]),
+    /// ]);
+    /// # }
+    /// ```
+    fn sections<'r, 't>(&'r self, text: &'t [u8]) -> Sections<'r, 't> {
+        Sections::new(self, text)
+    }
+
+    /// Returns the number of substrings `split_inclusive` would yield for
+    /// `text`, without slicing any of them out of the text. Use
+    /// [`Splitter::count_segments`](Splitter#method.count_segments) to count with
+    /// other policies.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// assert_eq!(re.count_segments(&b"one\ntwo\r\nthree"[..]), 3);
+    /// assert_eq!(re.count_segments(&b""[..]), 1);
+    /// # }
+    /// ```
+    fn count_segments(&self, text: &[u8]) -> usize {
+        self.split_inclusive(text).count()
+    }
+
+    /// Returns a [`SegmentStats`] summary of the substrings
+    /// `split_inclusive` would yield for `text`, gathered in a single pass.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r",").unwrap();
+    /// let stats = re.stats(b"a,,bcd");
+    /// assert_eq!(stats.count(), 3);
+    /// assert_eq!((stats.min_len(), stats.max_len()), (1, 3));
+    /// assert_eq!(stats.empty(), 0);
+    /// assert_eq!(stats.delimiter_bytes(), 2);
+    /// # }
+    /// ```
+    fn stats(&self, text: &[u8]) -> SegmentStats {
+        let mut stats = SegmentStats::default();
+        for segment in self.split_inclusive_segments(text) {
+            let delimiter = segment.delimiter_range().map_or(0, |range| range.len());
+            stats.add(segment.range().len(), delimiter);
+        }
+        stats
+    }
+
+    /// Rebuilds `text` with the text between matches replaced by what `f`
+    /// returns for it, leaving the matches themselves as they were. If `f`
+    /// changes nothing, the text is returned borrowed, without copying it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::borrow::Cow;
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = b"  one\r\ntwo  \nthree";
+    /// let trimmed = re.map_segments(text, |body| Cow::Borrowed(body.trim_ascii()));
+    /// assert_eq!(&trimmed[..], b"one\r\ntwo\nthree");
+    ///
+    /// let unchanged = re.map_segments(text, Cow::Borrowed);
+    /// assert!(matches!(unchanged, Cow::Borrowed(_)));
+    /// # }
+    /// ```
+    fn map_segments<'t, F>(&self, text: &'t [u8], mut f: F) -> Cow<'t, [u8]>
+    where
+        F: FnMut(&'t [u8]) -> Cow<'t, [u8]>,
+    {
+        // Nothing is copied until a body comes back changed.
+        let mut out: Option<Vec<u8>> = None;
+        for segment in self.split_inclusive_segments(text) {
+            let body = segment.body();
+            let mapped = f(body);
+            if out.is_none() && mapped != body {
+                out = Some(text[..segment.range().start].to_owned());
+            }
+            if let Some(out) = &mut out {
+                out.extend_from_slice(&mapped);
+                out.extend_from_slice(segment.delimiter().unwrap_or_default());
+            }
+        }
+        out.map_or(Cow::Borrowed(text), Cow::Owned)
+    }
+
+    /// Replaces all matches of `re` in `text` with `rep`, like
+    /// `re.replace_all`, except within the matches of this regular
+    /// expression, which are left as they were. `re` searches the text
+    /// between matches one piece at a time, so its anchors match at the ends
+    /// of each piece. If nothing is replaced, the text is returned borrowed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let records = Regex::new(r"\n---\n").unwrap();
+    /// let token = Regex::new(r"token=\S+|-").unwrap();
+    /// let text = b"user=a token=abc-1\n---\nuser=b token=def-2";
+    /// let redacted = records.replace_within_segments(text, &token, &b"token=***"[..]);
+    /// assert_eq!(redacted, &b"user=a token=***\n---\nuser=b token=***"[..]);
+    /// # }
+    /// ```
+    fn replace_within_segments<'t, R>(
+        &self,
+        text: &'t [u8],
+        re: &Regex,
+        mut rep: R,
+    ) -> Cow<'t, [u8]>
+    where
+        R: Replacer,
+    {
+        self.map_segments(text, |body| re.replace_all(body, rep.by_ref()))
+    }
+
+    /// Returns an iterator like `split_inclusive`, except that it takes
+    /// ownership of `text` and yields owned copies of the substrings. It
+    /// borrows neither the text nor the regular expression, so it can outlive
+    /// both.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::{bytes::SplitInclusiveOwned, bytes::RegexSplit};
+    /// # fn main() {
+    /// fn lines(text: Vec<u8>) -> SplitInclusiveOwned {
+    ///     let re = Regex::new(r"\r?\n").unwrap();
+    ///     re.split_inclusive_owned(text)
+    /// }
+    ///
+    /// let v: Vec<_> = lines(b"one\ntwo\r\nthree".to_vec()).collect();
+    /// assert_eq!(v, [&b"one\n"[..], &b"two\r\n"[..], &b"three"[..]]);
+    /// # }
+    /// ```
+    fn split_inclusive_owned(&self, text: Vec<u8>) -> SplitInclusiveOwned {
+        SplitInclusiveOwned::new(self, text)
+    }
+
+    /// Returns an iterator like `split_inclusive_left`, except that it takes
+    /// ownership of `text` and yields owned copies of the substrings.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"(?m)^-").unwrap();
+    /// let text = b"fruits:\n-apple\n-pear".to_vec();
+    /// let v: Vec<_> = re.split_inclusive_left_owned(text).collect();
+    /// assert_eq!(v, [&b"fruits:\n"[..], &b"-apple\n"[..], &b"-pear"[..]]);
+    /// # }
+    /// ```
+    fn split_inclusive_left_owned(&self, text: Vec<u8>) -> SplitInclusiveLeftOwned {
+        SplitInclusiveLeftOwned::new(self, text)
+    }
+
+    /// Returns an iterator like `split_inclusive` over a shared text, which
+    /// yields [`ArcSegment`]s holding on to the text instead of slices
+    /// borrowing it. The segments can be sent to other threads or stored
+    /// without copying any of the text.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::sync::Arc;
+    /// # use std::thread;
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::{ArcSegment, RegexSplit};
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text: Arc<[u8]> = Arc::from(&b"one\ntwo\nthree"[..]);
+    /// let segments: Vec<ArcSegment> = re.split_inclusive_arc(text).collect();
+    /// let handle = thread::spawn(move || segments[1].to_vec());
+    /// assert_eq!(handle.join().unwrap(), b"two\n");
+    /// # }
+    /// ```
+    fn split_inclusive_arc(&self, text: Arc<[u8]>) -> SplitInclusiveArc {
+        SplitInclusiveArc::new(self, text)
+    }
+
+    /// Returns an iterator like `split_inclusive_left` over a shared text,
+    /// which yields [`ArcSegment`]s holding on to the text.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::sync::Arc;
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"(?m)^-").unwrap();
+    /// let text: Arc<[u8]> = Arc::from(&b"fruits:\n-apple\n-pear"[..]);
+    /// let v: Vec<_> = re.split_inclusive_left_arc(text).collect();
+    /// assert_eq!(&*v[1], b"-apple\n");
+    /// assert_eq!(v[1].range(), 8..15);
+    /// # }
+    /// ```
+    fn split_inclusive_left_arc(&self, text: Arc<[u8]>) -> SplitInclusiveLeftArc {
+        SplitInclusiveLeftArc::new(self, text)
+    }
+
+    /// Returns an iterator like `split_inclusive` over a text that may be
+    /// borrowed or owned, yielding `Cow`s. A borrowed text is split without
+    /// copying anything, so a pipeline that only sometimes rewrites its text
+    /// before splitting it pays for copies only when it does.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::borrow::Cow;
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text: Cow<[u8]> = Cow::Borrowed(b"one\ntwo");
+    /// let v: Vec<_> = re.split_inclusive_cow(text).collect();
+    /// assert!(matches!(v[0], Cow::Borrowed(b"one\n")));
+    ///
+    /// let text: Cow<[u8]> = Cow::Owned(b"one\ntwo".to_vec());
+    /// let v: Vec<_> = re.split_inclusive_cow(text).collect();
+    /// assert!(matches!(&v[1], Cow::Owned(s) if s == b"two"));
+    /// # }
+    /// ```
+    fn split_inclusive_cow<'r, 'a>(&'r self, text: Cow<'a, [u8]>) -> SplitInclusiveCow<'r, 'a> {
+        SplitInclusiveCow::new(self, text)
+    }
+
+    /// Returns an iterator like `split_inclusive_left` over a text that may
+    /// be borrowed or owned, yielding `Cow`s.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::borrow::Cow;
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"(?m)^-").unwrap();
+    /// let text: Cow<[u8]> = Cow::Borrowed(b"a\n-b");
+    /// let v: Vec<_> = re.split_inclusive_left_cow(text).collect();
+    /// assert_eq!(v, [&b"a\n"[..], &b"-b"[..]]);
+    /// # }
+    /// ```
+    fn split_inclusive_left_cow<'r, 'a>(
+        &'r self,
+        text: Cow<'a, [u8]>,
+    ) -> SplitInclusiveLeftCow<'r, 'a> {
+        SplitInclusiveLeftCow::new(self, text)
+    }
+
+    /// Returns an iterator like `split_inclusive` over a [`Bytes`], which
+    /// yields each substring as a `Bytes` sharing the text's buffer, so
+    /// nothing is copied. Only available with the `bytes` feature.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bytes::Bytes;
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r\n").unwrap();
+    /// let frames = Bytes::from_static(b"PING\r\nPONG\r\n");
+    /// let v: Vec<Bytes> = re.split_inclusive_bytes(frames).collect();
+    /// assert_eq!(v, ["PING\r\n", "PONG\r\n", ""]);
+    /// # }
+    /// ```
+    #[cfg(feature = "bytes")]
+    fn split_inclusive_bytes(&self, text: Bytes) -> SplitInclusiveBytes {
+        SplitInclusiveBytes {
+            inner: OwnedSplit::new(self, text),
+        }
+    }
+
+    /// Returns an iterator like `split_inclusive_left` over a [`Bytes`],
+    /// which yields each substring as a `Bytes` sharing the text's buffer.
+    /// Only available with the `bytes` feature.
+    #[cfg(feature = "bytes")]
+    fn split_inclusive_left_bytes(&self, text: Bytes) -> SplitInclusiveLeftBytes {
+        SplitInclusiveLeftBytes {
+            inner: OwnedSplit::new(self, text),
+        }
+    }
+
+    /// Returns an iterator like `split_inclusive` over a [`BStr`], which
+    /// yields `BStr`s, so that the substrings keep `BStr`'s `Debug` output
+    /// and, with `bstr::ByteSlice` in scope, its string methods. Only
+    /// available with the `bstr` feature.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bstr::{BStr, ByteSlice};
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text = b"one\n\xFFtwo".as_bstr();
+    /// let v: Vec<&BStr> = re.split_inclusive_bstr(text).collect();
+    /// assert_eq!(v[0], "one\n");
+    /// assert_eq!(v[1].to_str_lossy(), "\u{FFFD}two");
+    /// # }
+    /// ```
+    #[cfg(feature = "bstr")]
+    fn split_inclusive_bstr<'r, 't>(&'r self, text: &'t BStr) -> SplitInclusiveBStr<'r, 't> {
+        SplitInclusiveBStr {
+            inner: self.split_inclusive(text),
+        }
+    }
+
+    /// Returns an iterator like `split_inclusive_left` over a [`BStr`],
+    /// which yields `BStr`s. Only available with the `bstr` feature.
+    #[cfg(feature = "bstr")]
+    fn split_inclusive_left_bstr<'r, 't>(
+        &'r self,
+        text: &'t BStr,
+    ) -> SplitInclusiveLeftBStr<'r, 't> {
+        SplitInclusiveLeftBStr {
+            inner: self.split_inclusive_left(text),
+        }
+    }
+
+    /// Returns an iterator like `split_inclusive` that yields each substring
+    /// as a `&str`, or the `Utf8Error` from checking it. Substrings are
+    /// checked one at a time as they are yielded, so text mixed with binary
+    /// records can be split without validating the whole of it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let mut iter = re.split_inclusive_utf8(b"one\n\xFF\xFE\nthree");
+    /// assert_eq!(iter.next(), Some(Ok("one\n")));
+    /// assert_eq!(iter.next().unwrap().unwrap_err().valid_up_to(), 0);
+    /// assert_eq!(iter.next(), Some(Ok("three")));
+    /// assert_eq!(iter.next(), None);
+    /// # }
+    /// ```
+    fn split_inclusive_utf8<'r, 't>(&'r self, text: &'t [u8]) -> SplitInclusiveUtf8<'r, 't> {
+        SplitInclusiveUtf8 {
+            inner: self.split_inclusive(text),
+        }
+    }
+
+    /// Returns an iterator like `split_inclusive` that yields each substring
+    /// as text, replacing invalid UTF-8 with U+FFFD as
+    /// `String::from_utf8_lossy` does. Only substrings that needed a
+    /// replacement are copied.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::borrow::Cow;
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let v: Vec<Cow<str>> = re.split_inclusive_lossy(b"ok\nbad\xFF\n").collect();
+    /// assert_eq!(v, ["ok\n", "bad\u{FFFD}\n", ""]);
+    /// assert!(matches!(v[0], Cow::Borrowed(_)));
+    /// # }
+    /// ```
+    fn split_inclusive_lossy<'r, 't>(&'r self, text: &'t [u8]) -> SplitInclusiveLossy<'r, 't> {
+        SplitInclusiveLossy {
+            inner: self.split_inclusive(text),
+        }
+    }
+}
+
+impl ByteSplitExt for [u8] {
+    /// Splits the text on matches of `re`, like `re.split_inclusive(text)`,
+    /// but with the text as the receiver.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::ByteSplitExt;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = b"one\ntwo\r\nthree";
+    /// let v: Vec<&[u8]> = text.split_inclusive_re(&re).collect();
+    /// assert_eq!(v, [&b"one\n"[..], &b"two\r\n"[..], &b"three"[..]]);
+    /// # }
+    /// ```
+    fn split_inclusive_re<'r, 't>(&'t self, re: &'r Regex) -> SplitInclusive<'r, 't> {
+        re.split_inclusive(self)
+    }
+
+    /// Splits the text on matches of `re`, like
+    /// `re.split_inclusive_left(text)`, but with the text as the receiver.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::ByteSplitExt;
+    /// # fn main() {
+    /// let re = Regex::new(r"(?m)^-").unwrap();
+    /// let text = b"fruits:\n-apple\n-pear";
+    /// let v: Vec<&[u8]> = text.split_inclusive_left_re(&re).collect();
+    /// assert_eq!(v, [&b"fruits:\n"[..], &b"-apple\n"[..], &b"-pear"[..]]);
+    /// # }
+    /// ```
+    fn split_inclusive_left_re<'r, 't>(&'t self, re: &'r Regex) -> SplitInclusiveLeft<'r, 't> {
+        re.split_inclusive_left(self)
+    }
+
+    /// Splits the text from the end on matches of `re`, like
+    /// `re.rsplitn_inclusive(text, n)`, but with the text as the receiver.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::ByteSplitExt;
+    /// # fn main() {
+    /// let re = Regex::new(r"/").unwrap();
+    /// let text: Vec<u8> = b"usr/local/bin".to_vec();
+    /// let v: Vec<&[u8]> = text.rsplitn_inclusive_re(&re, 2).collect();
+    /// assert_eq!(v, [&b"bin"[..], &b"usr/local/"[..]]);
+    /// # }
+    /// ```
+    fn rsplitn_inclusive_re<'r, 't>(&'t self, re: &'r Regex, n: usize) -> RSplitNInclusive<'r, 't> {
+        re.rsplitn_inclusive(self, n)
     }
 }
@@ -0,0 +1,180 @@
+//! Searching text a record at a time, the way `grep` searches a file a line
+//! at a time: the text is split into records on one regular expression, and
+//! the records that match a second one are picked out, along with a few of
+//! the records on either side of each for context.
+
+use std::collections::VecDeque;
+use std::iter::FusedIterator;
+use std::ops::Range;
+
+use regex::Regex;
+
+use crate::{RegexSplit, SplitInclusiveSegments};
+
+/// Picks out the records of a text that match a pattern, with records of
+/// context before and after each, like `grep -B` and `grep -A`.
+///
+/// The records are the substrings `split_inclusive` gives for the delimiter,
+/// and the pattern is tested against each record without its delimiter.
+/// An empty record at the end of the text, after its last delimiter, is left
+/// out, as `grep` does with the nothing after the last newline of a file.
+///
+/// # Example
+///
+/// ```rust
+/// # use regex::Regex;
+/// # use crate::regex_split::grep::Grep;
+/// # fn main() {
+/// let lines = Regex::new(r"\n").unwrap();
+/// let error = Regex::new(r"^ERROR").unwrap();
+/// let text = "INFO start\nDEBUG a\nERROR boom\nDEBUG b\nINFO c\nINFO d\n";
+/// let v: Vec<_> = Grep::new(&lines, &error)
+///     .context(1)
+///     .search(text)
+///     .map(|r| (r.number, r.is_match, r.text))
+///     .collect();
+/// assert_eq!(v, [
+///     (2, false, "DEBUG a\n"),
+///     (3, true, "ERROR boom\n"),
+///     (4, false, "DEBUG b\n"),
+/// ]);
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Grep<'r> {
+    delimiter: &'r Regex,
+    pattern: &'r Regex,
+    before: usize,
+    after: usize,
+    invert: bool,
+}
+
+impl<'r> Grep<'r> {
+    /// Creates a search for the records delimited by `delimiter` that match
+    /// `pattern`, without any context.
+    pub fn new(delimiter: &'r Regex, pattern: &'r Regex) -> Self {
+        Self {
+            delimiter,
+            pattern,
+            before: 0,
+            after: 0,
+            invert: false,
+        }
+    }
+
+    /// Sets how many records to yield before each matching record, like
+    /// `grep -B`.
+    pub fn before_context(mut self, n: usize) -> Self {
+        self.before = n;
+        self
+    }
+
+    /// Sets how many records to yield after each matching record, like
+    /// `grep -A`.
+    pub fn after_context(mut self, n: usize) -> Self {
+        self.after = n;
+        self
+    }
+
+    /// Sets how many records to yield on either side of each matching
+    /// record, like `grep -C`.
+    pub fn context(self, n: usize) -> Self {
+        self.before_context(n).after_context(n)
+    }
+
+    /// Sets whether to pick out the records that don't match the pattern
+    /// instead, like `grep -v`. Off by default.
+    pub fn invert(mut self, invert: bool) -> Self {
+        self.invert = invert;
+        self
+    }
+
+    /// Returns an iterator of the matching records of `text` and the records
+    /// of context around them, in order. Each record is yielded once, even
+    /// when it is context for two matches, or both context and a match.
+    pub fn search<'t>(&self, text: &'t str) -> Search<'r, 't> {
+        Search {
+            grep: self.clone(),
+            segments: self.delimiter.split_inclusive_segments(text),
+            number: 0,
+            held: VecDeque::new(),
+            ready: VecDeque::new(),
+            after_left: 0,
+        }
+    }
+}
+
+/// A record yielded by a [`Search`]: a record that matches, or one of the
+/// records of context around one.
+///
+/// The records yielded skip ahead wherever records are left out between one
+/// match's context and the next, which shows up as a jump in their numbers,
+/// where `grep` would print `--`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GrepRecord<'t> {
+    /// The number of the record in the text, counting from one, as `grep -n`
+    /// numbers lines.
+    pub number: usize,
+    /// The byte range of the record in the text.
+    pub range: Range<usize>,
+    /// The whole record, including its delimiter.
+    pub text: &'t str,
+    /// Whether the record matches, rather than being there for context.
+    pub is_match: bool,
+}
+
+/// Yields the records of a text that match a pattern, with records of context
+/// around them. Built by [`Grep::search`].
+///
+/// `'r` is the lifetime of the compiled regular expressions and `'t` is the
+/// lifetime of the string being searched.
+#[derive(Clone, Debug)]
+pub struct Search<'r, 't> {
+    grep: Grep<'r>,
+    segments: SplitInclusiveSegments<'r, 't>,
+    number: usize,
+
+    // The last records passed over, to be yielded as context if the next one
+    // matches, and the records waiting to be yielded.
+    held: VecDeque<GrepRecord<'t>>,
+    ready: VecDeque<GrepRecord<'t>>,
+
+    // How many more records to yield as context after the last match.
+    after_left: usize,
+}
+
+impl<'r, 't> Iterator for Search<'r, 't> {
+    type Item = GrepRecord<'t>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(record) = self.ready.pop_front() {
+                return Some(record);
+            }
+            let segment = self.segments.find(|s| !s.full().is_empty())?;
+            self.number += 1;
+            let record = GrepRecord {
+                number: self.number,
+                range: segment.range(),
+                text: segment.full(),
+                is_match: self.grep.pattern.is_match(segment.body()) != self.grep.invert,
+            };
+
+            if record.is_match {
+                self.ready.extend(self.held.drain(..));
+                self.ready.push_back(record);
+                self.after_left = self.grep.after;
+            } else if self.after_left > 0 {
+                self.after_left -= 1;
+                return Some(record);
+            } else if self.grep.before > 0 {
+                if self.held.len() == self.grep.before {
+                    self.held.pop_front();
+                }
+                self.held.push_back(record);
+            }
+        }
+    }
+}
+
+impl<'r, 't> FusedIterator for Search<'r, 't> {}
@@ -0,0 +1,5259 @@
+//! The generic types behind the splitters and iterators of this crate.
+//!
+//! Splitting is written once over [`Haystack`], which is implemented for
+//! `str` and `[u8]`, so that the two flavors can't drift apart. The crate
+//! root and [`bytes`](crate::bytes) name these types through aliases, which
+//! are documented with examples for their flavor and also hold what only
+//! makes sense for one of them, such as `as_str` or `as_bytes`.
+
+use std::borrow::{Borrow, Cow};
+use std::collections::VecDeque;
+use std::fmt;
+use std::iter::FusedIterator;
+use std::ops::{Deref, Range};
+use std::sync::Arc;
+#[cfg(any(feature = "aho-corasick", feature = "memchr"))]
+use std::sync::OnceLock;
+
+#[cfg(feature = "aho-corasick")]
+use aho_corasick::{AhoCorasick, Input, MatchKind};
+#[cfg(feature = "memchr")]
+use memchr::memmem::Finder;
+
+#[cfg(feature = "aho-corasick")]
+use crate::literal_alternatives;
+use crate::matcher::{FallibleMatcher, Matcher, MatcherRanges};
+#[cfg(feature = "memchr")]
+use crate::single_literal;
+#[cfg(any(feature = "aho-corasick", feature = "memchr"))]
+use crate::swap_case;
+use crate::{
+    EmptyText, Keep, LeadingEmpty, Options, Position, Side, SplitError, TrailingEmpty, ZeroWidth,
+    ZeroWidthError,
+};
+
+/// Text that can be split: `str` or `[u8]`, with the regex crate's types for
+/// searching it. Offsets are in bytes either way; a `str` is only ever sliced
+/// on character boundaries.
+///
+/// This trait is sealed: it is implemented for `str` and `[u8]` and can't be
+/// implemented outside this crate.
+pub trait Haystack:
+    private::Sealed + ToOwned<Owned: Clone + fmt::Debug + Deref<Target = Self>> + fmt::Debug
+{
+    /// What an escape is made of: a `char` in a `str`, a byte otherwise.
+    type Unit: Copy + PartialEq + fmt::Debug + From<u8>;
+
+    /// The regex crate's `Regex` for this text.
+    type Regex: Matcher<Self> + Clone + fmt::Debug;
+
+    /// The regex crate's `RegexSet` for this text.
+    type RegexSet;
+
+    /// A match of a `Regex` in this text.
+    type Match<'t>: Copy + PartialEq + Eq + fmt::Debug
+    where
+        Self: 't;
+
+    /// The capture groups of a match of a `Regex` in this text.
+    type Captures<'t>: fmt::Debug
+    where
+        Self: 't;
+
+    /// The length of the text in bytes.
+    fn len(&self) -> usize;
+
+    /// Whether the text is empty.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The text as bytes.
+    fn as_bytes(&self) -> &[u8];
+
+    /// The part of the text in `range`.
+    fn slice(&self, range: Range<usize>) -> &Self;
+
+    /// The text of a literal, to search for it.
+    fn of_str(s: &str) -> &Self;
+
+    /// Whether the text can be sliced at `at`.
+    fn is_boundary(&self, at: usize) -> bool;
+
+    /// The units of the text, to count lines and columns in.
+    fn units(&self) -> impl Iterator<Item = Self::Unit> + '_;
+
+    /// The number of `escape`s in a row that end right at `end`.
+    fn escapes_before(&self, end: usize, escape: Self::Unit) -> usize;
+
+    /// How far to step from `at` to search past an empty match there.
+    fn step_at(&self, at: usize) -> usize;
+
+    /// The first match of `regex` in the text at or after `at`.
+    fn find_at<'t>(&'t self, regex: &Self::Regex, at: usize) -> Option<Self::Match<'t>>;
+
+    /// The capture groups of the first match of `regex` at or after `at`.
+    fn captures_at<'t>(&'t self, regex: &Self::Regex, at: usize) -> Option<Self::Captures<'t>>;
+
+    /// Whether `regex` matches anywhere in the text.
+    fn is_match(&self, regex: &Self::Regex) -> bool;
+
+    /// The byte range of a match.
+    fn match_range(m: &Self::Match<'_>) -> Range<usize>;
+
+    /// The text of a match.
+    fn match_text<'t>(m: &Self::Match<'t>) -> &'t Self
+    where
+        Self: 't;
+
+    /// The match of group `i`, if it took part in the match.
+    fn group<'t>(captures: &Self::Captures<'t>, i: usize) -> Option<Self::Match<'t>>
+    where
+        Self: 't;
+
+    /// The names of the capture groups of `regex`, in order.
+    fn capture_names(regex: &Self::Regex) -> impl Iterator<Item = Option<&str>>;
+
+    /// The pattern `regex` was compiled from.
+    fn pattern(regex: &Self::Regex) -> &str;
+
+    /// Compiles `pattern` into a `Regex` for this text.
+    fn compile(pattern: &str) -> Result<Self::Regex, regex::Error>;
+
+    /// The patterns `set` was compiled from.
+    fn patterns(set: &Self::RegexSet) -> &[String];
+}
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for str {}
+    impl Sealed for [u8] {}
+}
+
+impl Haystack for str {
+    type Unit = char;
+    type Regex = regex::Regex;
+    type RegexSet = regex::RegexSet;
+    type Match<'t> = regex::Match<'t>;
+    type Captures<'t> = regex::Captures<'t>;
+
+    fn len(&self) -> usize {
+        str::len(self)
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        str::as_bytes(self)
+    }
+
+    fn slice(&self, range: Range<usize>) -> &Self {
+        &self[range]
+    }
+
+    fn of_str(s: &str) -> &Self {
+        s
+    }
+
+    fn is_boundary(&self, at: usize) -> bool {
+        self.is_char_boundary(at)
+    }
+
+    fn units(&self) -> impl Iterator<Item = char> + '_ {
+        self.chars()
+    }
+
+    fn escapes_before(&self, end: usize, escape: char) -> usize {
+        self[..end]
+            .chars()
+            .rev()
+            .take_while(|&c| c == escape)
+            .count()
+    }
+
+    fn step_at(&self, at: usize) -> usize {
+        self[at..].chars().next().map_or(1, char::len_utf8)
+    }
+
+    fn find_at<'t>(&'t self, regex: &regex::Regex, at: usize) -> Option<regex::Match<'t>> {
+        regex.find_at(self, at)
+    }
+
+    fn captures_at<'t>(&'t self, regex: &regex::Regex, at: usize) -> Option<regex::Captures<'t>> {
+        regex.captures_at(self, at)
+    }
+
+    fn is_match(&self, regex: &regex::Regex) -> bool {
+        regex.is_match(self)
+    }
+
+    fn match_range(m: &regex::Match<'_>) -> Range<usize> {
+        m.range()
+    }
+
+    fn match_text<'t>(m: &regex::Match<'t>) -> &'t str
+    where
+        Self: 't,
+    {
+        m.as_str()
+    }
+
+    fn group<'t>(captures: &regex::Captures<'t>, i: usize) -> Option<regex::Match<'t>>
+    where
+        Self: 't,
+    {
+        captures.get(i)
+    }
+
+    fn capture_names(regex: &regex::Regex) -> impl Iterator<Item = Option<&str>> {
+        regex.capture_names()
+    }
+
+    fn pattern(regex: &regex::Regex) -> &str {
+        regex.as_str()
+    }
+
+    fn compile(pattern: &str) -> Result<regex::Regex, regex::Error> {
+        regex::Regex::new(pattern)
+    }
+
+    fn patterns(set: &regex::RegexSet) -> &[String] {
+        set.patterns()
+    }
+}
+
+impl Haystack for [u8] {
+    type Unit = u8;
+    type Regex = regex::bytes::Regex;
+    type RegexSet = regex::bytes::RegexSet;
+    type Match<'t> = regex::bytes::Match<'t>;
+    type Captures<'t> = regex::bytes::Captures<'t>;
+
+    fn len(&self) -> usize {
+        <[u8]>::len(self)
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+
+    fn slice(&self, range: Range<usize>) -> &Self {
+        &self[range]
+    }
+
+    fn of_str(s: &str) -> &Self {
+        s.as_bytes()
+    }
+
+    fn is_boundary(&self, _at: usize) -> bool {
+        true
+    }
+
+    fn units(&self) -> impl Iterator<Item = u8> + '_ {
+        self.iter().copied()
+    }
+
+    fn escapes_before(&self, end: usize, escape: u8) -> usize {
+        self[..end]
+            .iter()
+            .rev()
+            .take_while(|&&b| b == escape)
+            .count()
+    }
+
+    fn step_at(&self, _at: usize) -> usize {
+        1
+    }
+
+    fn find_at<'t>(
+        &'t self,
+        regex: &regex::bytes::Regex,
+        at: usize,
+    ) -> Option<regex::bytes::Match<'t>> {
+        regex.find_at(self, at)
+    }
+
+    fn captures_at<'t>(
+        &'t self,
+        regex: &regex::bytes::Regex,
+        at: usize,
+    ) -> Option<regex::bytes::Captures<'t>> {
+        regex.captures_at(self, at)
+    }
+
+    fn is_match(&self, regex: &regex::bytes::Regex) -> bool {
+        regex.is_match(self)
+    }
+
+    fn match_range(m: &regex::bytes::Match<'_>) -> Range<usize> {
+        m.range()
+    }
+
+    fn match_text<'t>(m: &regex::bytes::Match<'t>) -> &'t [u8]
+    where
+        Self: 't,
+    {
+        m.as_bytes()
+    }
+
+    fn group<'t>(captures: &regex::bytes::Captures<'t>, i: usize) -> Option<regex::bytes::Match<'t>>
+    where
+        Self: 't,
+    {
+        captures.get(i)
+    }
+
+    fn capture_names(regex: &regex::bytes::Regex) -> impl Iterator<Item = Option<&str>> {
+        regex.capture_names()
+    }
+
+    fn pattern(regex: &regex::bytes::Regex) -> &str {
+        regex.as_str()
+    }
+
+    fn compile(pattern: &str) -> Result<regex::bytes::Regex, regex::Error> {
+        regex::bytes::Regex::new(pattern)
+    }
+
+    fn patterns(set: &regex::bytes::RegexSet) -> &[String] {
+        set.patterns()
+    }
+}
+
+// Returns the offset of `s` within `text`, which it must have been sliced from.
+pub(crate) fn offset_in<H: Haystack + ?Sized>(text: &H, s: &H) -> usize {
+    s.as_bytes().as_ptr() as usize - text.as_bytes().as_ptr() as usize
+}
+
+// Pulls delimiters out of the regex's matches, applying the options
+// that decide which matches count and how they combine.
+#[derive(Debug)]
+pub(crate) struct Delimiters<'r, 't, M: ?Sized, H: Haystack + ?Sized> {
+    matches: MatcherRanges<'r, 't, M, H>,
+    pub(crate) zero_width: ZeroWidth,
+    pub(crate) coalesce: bool,
+    pub(crate) skip_escaped: bool,
+    pub(crate) escape: H::Unit,
+
+    // Used instead of the regex when it only matches literals.
+    #[cfg(feature = "aho-corasick")]
+    pub(crate) literals: Option<AhoCorasick>,
+
+    // Used instead of the regex when it only matches a single literal.
+    #[cfg(feature = "memchr")]
+    pub(crate) literal: Option<Finder<'static>>,
+
+    // A match pulled out while looking for the end of a run of adjacent
+    // matches, which belongs to the next delimiter.
+    peeked: Option<Range<usize>>,
+}
+
+// Not derived, which would need `M: Clone`, though only a reference is held.
+impl<'r, 't, M: ?Sized, H: Haystack + ?Sized> Clone for Delimiters<'r, 't, M, H> {
+    fn clone(&self) -> Self {
+        Self {
+            matches: self.matches.clone(),
+            zero_width: self.zero_width,
+            coalesce: self.coalesce,
+            skip_escaped: self.skip_escaped,
+            escape: self.escape,
+            #[cfg(feature = "aho-corasick")]
+            literals: self.literals.clone(),
+            #[cfg(feature = "memchr")]
+            literal: self.literal.clone(),
+            peeked: self.peeked.clone(),
+        }
+    }
+}
+
+impl<'r, 't, M, H> Delimiters<'r, 't, M, H>
+where
+    M: Matcher<H> + ?Sized,
+    H: Haystack + ?Sized,
+{
+    pub(crate) fn new(matches: MatcherRanges<'r, 't, M, H>, escape: H::Unit) -> Self {
+        Self {
+            matches,
+            zero_width: ZeroWidth::Split,
+            coalesce: false,
+            skip_escaped: false,
+            escape,
+            #[cfg(feature = "aho-corasick")]
+            literals: None,
+            #[cfg(feature = "memchr")]
+            literal: None,
+            peeked: None,
+        }
+    }
+
+    pub(crate) fn next_delimiter(&mut self) -> Result<Option<Range<usize>>, ZeroWidthError> {
+        let mut delimiter = match self.peeked.take() {
+            Some(delimiter) => delimiter,
+            None => match self.next_match()? {
+                Some(delimiter) => delimiter,
+                None => return Ok(None),
+            },
+        };
+
+        if self.coalesce {
+            while let Some(next) = self.next_match()? {
+                if next.start != delimiter.end {
+                    self.peeked = Some(next);
+                    break;
+                }
+                delimiter.end = next.end;
+            }
+        }
+        Ok(Some(delimiter))
+    }
+
+    fn next_match(&mut self) -> Result<Option<Range<usize>>, ZeroWidthError> {
+        while let Some(m) = self.next_range() {
+            if self.skip_escaped {
+                let escapes = self.matches.text.escapes_before(m.start, self.escape);
+                if escapes % 2 == 1 {
+                    continue;
+                }
+            }
+            if !m.is_empty() {
+                return Ok(Some(m));
+            }
+            match self.zero_width {
+                ZeroWidth::Split => return Ok(Some(m)),
+                ZeroWidth::Skip => continue,
+                ZeroWidth::Error => return Err(ZeroWidthError { offset: m.start }),
+            }
+        }
+        Ok(None)
+    }
+
+    fn next_range(&mut self) -> Option<Range<usize>> {
+        #[cfg(feature = "memchr")]
+        if let Some(literal) = &self.literal {
+            let matches = &mut self.matches;
+            if matches.at > matches.text.len() {
+                return None;
+            }
+            let start = matches.at + literal.find(&matches.text.as_bytes()[matches.at..])?;
+            let end = start + literal.needle().len();
+            matches.at = end;
+            return Some(start..end);
+        }
+        #[cfg(feature = "aho-corasick")]
+        if let Some(literals) = &self.literals {
+            let matches = &mut self.matches;
+            if matches.at > matches.text.len() {
+                return None;
+            }
+            let input = Input::new(matches.text.as_bytes()).span(matches.at..matches.text.len());
+            let m = literals.find(input)?;
+            matches.at = m.end();
+            return Some(m.range());
+        }
+        self.matches.next()
+    }
+}
+
+// The state of a `Splitter`'s iterator, shared by the str and bytes flavors
+// of `Split`, which only wrap it.
+#[derive(Debug)]
+pub(crate) struct SplitCore<'r, 't, M: ?Sized, H: Haystack + ?Sized> {
+    pub(crate) finder: Delimiters<'r, 't, M, H>,
+    pub(crate) options: Options,
+
+    // Where splitting began; the text before it is never yielded.
+    start: usize,
+
+    // Where the next substring begins, or `None` once the iterator is
+    // finished.
+    last: Option<usize>,
+
+    // Whether the first substring has been produced, yielded or not.
+    started: bool,
+
+    // The number of substrings yielded, checked against the limit.
+    count: usize,
+    error: Option<ZeroWidthError>,
+
+    // Filled by the first call to `next_back`, after which substrings are
+    // taken from either end of it.
+    buffered: Option<VecDeque<&'t H>>,
+    pub(crate) text: &'t H,
+}
+
+impl<'r, 't, M: ?Sized, H: Haystack + ?Sized> Clone for SplitCore<'r, 't, M, H> {
+    fn clone(&self) -> Self {
+        Self {
+            finder: self.finder.clone(),
+            options: self.options,
+            start: self.start,
+            last: self.last,
+            started: self.started,
+            count: self.count,
+            error: self.error,
+            buffered: self.buffered.clone(),
+            text: self.text,
+        }
+    }
+}
+
+impl<'r, 't, M, H> SplitCore<'r, 't, M, H>
+where
+    M: Matcher<H> + ?Sized,
+    H: Haystack + ?Sized,
+{
+    pub(crate) fn new(
+        finder: Delimiters<'r, 't, M, H>,
+        options: Options,
+        text: &'t H,
+        start: usize,
+    ) -> Self {
+        Self {
+            finder,
+            options,
+            start,
+            last: Some(start),
+            started: false,
+            count: 0,
+            error: None,
+            buffered: None,
+            text,
+        }
+    }
+
+    pub(crate) fn error(&self) -> Option<ZeroWidthError> {
+        self.error
+    }
+
+    pub(crate) fn remainder(&self) -> Option<&'t H> {
+        if let Some(buffered) = &self.buffered {
+            let first = buffered.front()?;
+            let last = buffered.back()?;
+            let start = offset_in(self.text, first);
+            let end = offset_in(self.text, last) + last.len();
+            return Some(self.text.slice(start..end));
+        }
+        if self.error.is_some() {
+            return None;
+        }
+        if self.options.limit.is_some_and(|limit| self.count >= limit) {
+            return None;
+        }
+        Some(self.text.slice(self.last?..self.text.len()))
+    }
+
+    pub(crate) fn offset(&self) -> usize {
+        self.last.unwrap_or(self.text.len())
+    }
+
+    // Finds the next substring to be yielded, counting it towards the limit.
+    fn next_yielded(&mut self) -> Option<Range<usize>> {
+        if self.error.is_some() {
+            return None;
+        }
+        if self.options.limit.is_some_and(|limit| self.count >= limit) {
+            return None;
+        }
+
+        loop {
+            let span = self.next_span()?;
+            if !(self.options.skip_empty && span.is_empty()) {
+                self.count += 1;
+                return Some(span);
+            }
+        }
+    }
+
+    // Finds the next substring, yielded or not, as a range of the text.
+    fn next_span(&mut self) -> Option<Range<usize>> {
+        if self.start == self.text.len() && self.options.empty_text == EmptyText::Drop {
+            return None;
+        }
+        let last = self.last?;
+        let first = !self.started;
+        self.started = true;
+
+        // The last substring allowed by the limit takes the rest of the text.
+        let at_limit = self
+            .options
+            .limit
+            .is_some_and(|limit| self.count + 1 >= limit);
+        let delimiter = if at_limit {
+            None
+        } else {
+            match self.finder.next_delimiter() {
+                Ok(delimiter) => delimiter,
+                Err(e) => {
+                    self.error = Some(e);
+                    return None;
+                }
+            }
+        };
+
+        match delimiter {
+            None => {
+                let span = last..self.text.len();
+                self.last = None;
+                if span.is_empty()
+                    && self.start != self.text.len()
+                    && self.options.trailing_empty == TrailingEmpty::Drop
+                {
+                    None
+                } else {
+                    Some(span)
+                }
+            }
+            Some(delimiter) => {
+                let (end, next) = match self.options.keep {
+                    Keep::Right => (delimiter.end, delimiter.end),
+                    Keep::Left => (delimiter.start, delimiter.start),
+                    Keep::Both => (delimiter.end, delimiter.start),
+                    Keep::Neither => (delimiter.start, delimiter.end),
+                };
+                let span = last..end;
+                self.last = Some(next);
+                if first && span.is_empty() && self.options.leading_empty == LeadingEmpty::Drop {
+                    return self.next_span();
+                }
+                Some(span)
+            }
+        }
+    }
+}
+
+impl<'r, 't, M, H> Iterator for SplitCore<'r, 't, M, H>
+where
+    M: Matcher<H> + ?Sized,
+    H: Haystack + ?Sized,
+{
+    type Item = &'t H;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(buffered) = &mut self.buffered {
+            return buffered.pop_front();
+        }
+        self.next_yielded().map(|span| self.text.slice(span))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if let Some(buffered) = &self.buffered {
+            return (buffered.len(), Some(buffered.len()));
+        }
+        let Some(rest) = self.remainder().map(|rest| rest.len()) else {
+            return (0, Some(0));
+        };
+
+        // Some text left over makes at least one more substring, unless
+        // empty substrings can be dropped or zero-width matches can stop
+        // iteration, either of which can leave nothing to yield. There can't
+        // be more substrings than there are places to end one, plus the last.
+        let may_drop = self.options.skip_empty
+            || self.options.leading_empty == LeadingEmpty::Drop
+            || self.options.trailing_empty == TrailingEmpty::Drop
+            || self.options.zero_width != ZeroWidth::Split;
+        let lower = usize::from(rest > 0 && !may_drop);
+        let mut upper = rest + 2;
+        if let Some(limit) = self.options.limit {
+            upper = upper.min(limit - self.count);
+        }
+        (lower, Some(upper))
+    }
+
+    fn count(mut self) -> usize {
+        if let Some(buffered) = &self.buffered {
+            return buffered.len();
+        }
+        let mut count = 0;
+        while self.next_yielded().is_some() {
+            count += 1;
+        }
+        count
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if let Some(buffered) = &mut self.buffered {
+            buffered.drain(..n.min(buffered.len()));
+            return buffered.pop_front();
+        }
+        for _ in 0..n {
+            self.next_yielded()?;
+        }
+        self.next()
+    }
+
+    fn last(mut self) -> Option<Self::Item> {
+        if let Some(buffered) = &mut self.buffered {
+            return buffered.pop_back();
+        }
+        let mut last = None;
+        while let Some(span) = self.next_yielded() {
+            last = Some(span);
+        }
+        last.map(|span| self.text.slice(span))
+    }
+
+    fn fold<B, F>(mut self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        if let Some(buffered) = self.buffered {
+            return buffered.into_iter().fold(init, f);
+        }
+        let mut acc = init;
+        while let Some(span) = self.next_yielded() {
+            acc = f(acc, self.text.slice(span));
+        }
+        acc
+    }
+}
+
+impl<'r, 't, M, H> DoubleEndedIterator for SplitCore<'r, 't, M, H>
+where
+    M: Matcher<H> + ?Sized,
+    H: Haystack + ?Sized,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.buffered.is_none() {
+            let mut buffered: VecDeque<_> = self.by_ref().collect();
+            if self.error.is_some() {
+                buffered.clear();
+            }
+            self.buffered = Some(buffered);
+        }
+        self.buffered.as_mut()?.pop_back()
+    }
+}
+
+// The state behind `rsplitn_inclusive`, shared by the str and bytes flavors
+// of `RSplitNInclusive`, which only wrap it.
+#[derive(Debug)]
+pub(crate) struct RSplitNCore<'r, 't, M: ?Sized, H: Haystack + ?Sized> {
+    pub(crate) finder: Delimiters<'r, 't, M, H>,
+    pub(crate) empty_text: EmptyText,
+    pub(crate) skip_empty: bool,
+
+    // The ends of the last `n - 1` delimiters, filled by the first call to
+    // `next`, which scans the whole text.
+    ends: VecDeque<usize>,
+    scanned: bool,
+    n: usize,
+    last: usize,
+    pub(crate) error: Option<ZeroWidthError>,
+    text: &'t H,
+}
+
+impl<'r, 't, M: ?Sized, H: Haystack + ?Sized> Clone for RSplitNCore<'r, 't, M, H> {
+    fn clone(&self) -> Self {
+        Self {
+            finder: self.finder.clone(),
+            empty_text: self.empty_text,
+            skip_empty: self.skip_empty,
+            ends: self.ends.clone(),
+            scanned: self.scanned,
+            n: self.n,
+            last: self.last,
+            error: self.error,
+            text: self.text,
+        }
+    }
+}
+
+impl<'r, 't, M, H> RSplitNCore<'r, 't, M, H>
+where
+    M: Matcher<H> + ?Sized,
+    H: Haystack + ?Sized,
+{
+    pub(crate) fn new(finder: Delimiters<'r, 't, M, H>, text: &'t H, n: usize) -> Self {
+        Self {
+            finder,
+            empty_text: EmptyText::Keep,
+            skip_empty: false,
+            ends: VecDeque::new(),
+            scanned: false,
+            n,
+            last: text.len(),
+            error: None,
+            text,
+        }
+    }
+}
+
+impl<'r, 't, M, H> Iterator for RSplitNCore<'r, 't, M, H>
+where
+    M: Matcher<H> + ?Sized,
+    H: Haystack + ?Sized,
+{
+    type Item = &'t H;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.error.is_some() {
+            return None;
+        }
+        if self.text.is_empty() && self.empty_text == EmptyText::Drop {
+            return None;
+        }
+
+        if self.n == 0 {
+            return None;
+        }
+
+        if !self.scanned {
+            self.scanned = true;
+            let keep = self.n - 1;
+            if keep > 0 {
+                loop {
+                    match self.finder.next_delimiter() {
+                        Err(e) => {
+                            self.error = Some(e);
+                            return None;
+                        }
+                        Ok(None) => break,
+                        Ok(Some(delimiter)) => {
+                            // Skipping an empty substring is the same as not
+                            // splitting at one of its ends.
+                            if self.skip_empty
+                                && (delimiter.end == 0
+                                    || delimiter.end == self.text.len()
+                                    || self.ends.back() == Some(&delimiter.end))
+                            {
+                                continue;
+                            }
+                            if self.ends.len() == keep {
+                                self.ends.pop_front();
+                            }
+                            self.ends.push_back(delimiter.end);
+                        }
+                    }
+                }
+            }
+        }
+
+        self.n -= 1;
+        match self.ends.pop_back() {
+            Some(end) => {
+                let s = self.text.slice(end..self.last);
+                self.last = end;
+                Some(s)
+            }
+            None => {
+                self.n = 0; // Next call will return None
+                let s = self.text.slice(0..self.last);
+                if self.skip_empty && s.is_empty() {
+                    None
+                } else {
+                    Some(s)
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.error.is_some() || self.n == 0 {
+            return (0, Some(0));
+        }
+        if self.scanned {
+            // Every remembered match ends a substring, and the text before
+            // the earliest of them makes one more.
+            (self.ends.len(), Some(self.ends.len() + 1))
+        } else {
+            (0, Some(self.n))
+        }
+    }
+
+    fn fold<B, F>(mut self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        // The first call scans the text, after which the substrings come
+        // straight from the remembered match ends.
+        let mut acc = match self.next() {
+            Some(s) => f(init, s),
+            None => return init,
+        };
+        if self.n == 0 {
+            return acc;
+        }
+        for end in self.ends.drain(..).rev() {
+            acc = f(acc, self.text.slice(end..self.last));
+            self.last = end;
+        }
+        let s = self.text.slice(0..self.last);
+        if !(self.skip_empty && s.is_empty()) {
+            acc = f(acc, s);
+        }
+        acc
+    }
+}
+
+// The state behind `split_inclusive_rev` and `split_inclusive_left_rev`,
+// which split at the end or the start of each match, shared by their str
+// and bytes flavors. The first call to `next` scans the whole text.
+#[derive(Debug)]
+pub(crate) struct SplitRevCore<'r, 't, M: ?Sized, H: ?Sized> {
+    matches: MatcherRanges<'r, 't, M, H>,
+    left: bool,
+    bounds: Vec<usize>,
+    scanned: bool,
+    finished: bool,
+    last: usize,
+    text: &'t H,
+}
+
+impl<'r, 't, M: ?Sized, H: ?Sized> Clone for SplitRevCore<'r, 't, M, H> {
+    fn clone(&self) -> Self {
+        Self {
+            matches: self.matches.clone(),
+            left: self.left,
+            bounds: self.bounds.clone(),
+            scanned: self.scanned,
+            finished: self.finished,
+            last: self.last,
+            text: self.text,
+        }
+    }
+}
+
+impl<'r, 't, M, H> SplitRevCore<'r, 't, M, H>
+where
+    M: Matcher<H> + ?Sized,
+    H: Haystack + ?Sized,
+{
+    pub(crate) fn new(matcher: &'r M, text: &'t H, left: bool) -> Self {
+        Self {
+            matches: MatcherRanges::new(matcher, text, 0),
+            left,
+            bounds: Vec::new(),
+            scanned: false,
+            finished: false,
+            last: text.len(),
+            text,
+        }
+    }
+}
+
+impl<'r, 't, M, H> Iterator for SplitRevCore<'r, 't, M, H>
+where
+    M: Matcher<H> + ?Sized,
+    H: Haystack + ?Sized,
+{
+    type Item = &'t H;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        if !self.scanned {
+            self.scanned = true;
+            let left = self.left;
+            self.bounds
+                .extend((&mut self.matches).map(|m| if left { m.start } else { m.end }));
+        }
+
+        match self.bounds.pop() {
+            Some(bound) => {
+                let s = self.text.slice(bound..self.last);
+                self.last = bound;
+                Some(s)
+            }
+            None => {
+                self.finished = true;
+                Some(self.text.slice(0..self.last))
+            }
+        }
+    }
+}
+
+// The state behind `split_inclusive_both`, shared by its str and bytes
+// flavors.
+#[derive(Debug)]
+pub(crate) struct SplitBothCore<'r, 't, M: ?Sized, H: ?Sized> {
+    matches: MatcherRanges<'r, 't, M, H>,
+    last: Option<usize>,
+    text: &'t H,
+}
+
+impl<'r, 't, M: ?Sized, H: ?Sized> Clone for SplitBothCore<'r, 't, M, H> {
+    fn clone(&self) -> Self {
+        Self {
+            matches: self.matches.clone(),
+            last: self.last,
+            text: self.text,
+        }
+    }
+}
+
+impl<'r, 't, M, H> SplitBothCore<'r, 't, M, H>
+where
+    M: Matcher<H> + ?Sized,
+    H: Haystack + ?Sized,
+{
+    pub(crate) fn new(matcher: &'r M, text: &'t H) -> Self {
+        Self {
+            matches: MatcherRanges::new(matcher, text, 0),
+            last: Some(0),
+            text,
+        }
+    }
+}
+
+impl<'r, 't, M, H> Iterator for SplitBothCore<'r, 't, M, H>
+where
+    M: Matcher<H> + ?Sized,
+    H: Haystack + ?Sized,
+{
+    type Item = &'t H;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let last = self.last?;
+        match self.matches.next() {
+            None => {
+                self.last = None;
+                Some(self.text.slice(last..self.text.len()))
+            }
+            Some(m) => {
+                self.last = Some(m.start);
+                Some(self.text.slice(last..m.end))
+            }
+        }
+    }
+}
+
+/// The type behind [`crate::Splitter`] and [`crate::bytes::Splitter`].
+#[derive(Debug)]
+pub struct Splitter<'r, H: Haystack + ?Sized, M: ?Sized = <H as Haystack>::Regex> {
+    matcher: &'r M,
+    options: Options,
+    escape: H::Unit,
+
+    // The regex a splitter made with `new` was given, to look for literals
+    // in.
+    #[cfg(any(feature = "aho-corasick", feature = "memchr"))]
+    regex: Option<&'r H::Regex>,
+
+    // Finds the matches instead of the regex, when it only matches literals.
+    // Built the first time the splitter splits, so that one used just once
+    // doesn't pay for it.
+    #[cfg(feature = "aho-corasick")]
+    literals: OnceLock<Option<AhoCorasick>>,
+
+    // Finds the matches instead of the regex, when it only matches a single
+    // literal. Worked out the first time the splitter splits, like
+    // `literals`.
+    #[cfg(feature = "memchr")]
+    literal: OnceLock<Option<Finder<'static>>>,
+}
+
+// Not derived, which would need `M: Clone`, though only a reference is held.
+impl<'r, H: Haystack + ?Sized, M: ?Sized> Clone for Splitter<'r, H, M> {
+    fn clone(&self) -> Self {
+        Self {
+            matcher: self.matcher,
+            options: self.options,
+            escape: self.escape,
+            #[cfg(any(feature = "aho-corasick", feature = "memchr"))]
+            regex: self.regex,
+            #[cfg(feature = "aho-corasick")]
+            literals: self.literals.clone(),
+            #[cfg(feature = "memchr")]
+            literal: self.literal.clone(),
+        }
+    }
+}
+
+impl<'r, H: Haystack + ?Sized> Splitter<'r, H> {
+    /// Creates a splitter with the default options, which split like
+    /// `split_inclusive`.
+    ///
+    /// With the `aho-corasick` feature enabled, a regex that is just an
+    /// alternation of plain literals, such as `foo|bar`, is swapped for an
+    /// Aho-Corasick automaton, which finds the same matches faster. Building
+    /// the automaton takes a moment, so it's built the first time the
+    /// splitter splits and kept for every split after: reuse the splitter
+    /// for big jobs. The methods of `RegexSplit` never build one.
+    ///
+    /// With the `memchr` feature enabled, a regex that matches a single
+    /// fixed string, such as `\n---\n`, is swapped for a `memmem` searcher
+    /// instead. Finding out whether it does means parsing the pattern, so
+    /// that too is done on the first split and kept.
+    pub fn new(regex: &'r H::Regex) -> Self {
+        Self {
+            matcher: regex,
+            options: Options::default(),
+            escape: <H::Unit>::from(b'\\'),
+            #[cfg(any(feature = "aho-corasick", feature = "memchr"))]
+            regex: Some(regex),
+            #[cfg(feature = "aho-corasick")]
+            literals: OnceLock::new(),
+            #[cfg(feature = "memchr")]
+            literal: OnceLock::new(),
+        }
+    }
+}
+
+impl<'r, H: Haystack + ?Sized, M: Matcher<H> + ?Sized> Splitter<'r, H, M> {
+    /// Creates a splitter with the default options that finds delimiters
+    /// with `matcher` rather than a regex. See [`Matcher`].
+    pub fn with_matcher(matcher: &'r M) -> Self {
+        Self {
+            matcher,
+            options: Options::default(),
+            escape: <H::Unit>::from(b'\\'),
+            #[cfg(any(feature = "aho-corasick", feature = "memchr"))]
+            regex: None,
+            #[cfg(feature = "aho-corasick")]
+            literals: OnceLock::new(),
+            #[cfg(feature = "memchr")]
+            literal: OnceLock::new(),
+        }
+    }
+
+    /// Sets where substrings keep their delimiting matches. See [`Keep`].
+    pub fn keep(mut self, keep: Keep) -> Self {
+        self.options.keep = keep;
+        self
+    }
+
+    /// Sets the maximum number of substrings to yield. The last one holds the
+    /// rest of the text, unsplit, like with `splitn` in std. Skipped empty
+    /// substrings don't count.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.options.limit = Some(limit);
+        self
+    }
+
+    /// Sets whether empty substrings are skipped, wherever they come from.
+    /// Off by default.
+    pub fn skip_empty(mut self, skip_empty: bool) -> Self {
+        self.options.skip_empty = skip_empty;
+        self
+    }
+
+    /// Sets what to do with the empty substring left at the end of the text
+    /// when the text ends with a match. See [`TrailingEmpty`].
+    pub fn trailing_empty(mut self, policy: TrailingEmpty) -> Self {
+        self.options.trailing_empty = policy;
+        self
+    }
+
+    /// Sets what to do with the empty substring yielded first when the text
+    /// begins with a match. See [`LeadingEmpty`].
+    pub fn leading_empty(mut self, policy: LeadingEmpty) -> Self {
+        self.options.leading_empty = policy;
+        self
+    }
+
+    /// Sets what to do when the text being split is empty. See
+    /// [`EmptyText`].
+    pub fn empty_text(mut self, policy: EmptyText) -> Self {
+        self.options.empty_text = policy;
+        self
+    }
+
+    /// Sets what to do with zero-width matches. See [`ZeroWidth`].
+    pub fn zero_width(mut self, policy: ZeroWidth) -> Self {
+        self.options.zero_width = policy;
+        self
+    }
+
+    /// Sets whether a run of adjacent matches counts as a single delimiter.
+    /// Off by default.
+    pub fn coalesce_delimiters(mut self, coalesce: bool) -> Self {
+        self.options.coalesce = coalesce;
+        self
+    }
+
+    /// Sets whether matches preceded by an odd number of escape characters
+    /// are skipped, so that an escaped delimiter doesn't split. The escape
+    /// character is a backslash unless set with `escape_char`. Off by
+    /// default.
+    pub fn skip_escaped(mut self, skip_escaped: bool) -> Self {
+        self.options.skip_escaped = skip_escaped;
+        self
+    }
+
+    /// Sets the escape character used by `skip_escaped`.
+    pub fn escape_char(mut self, escape: H::Unit) -> Self {
+        self.escape = escape;
+        self
+    }
+
+    /// Returns an iterator of the substrings of `text`, split as configured.
+    ///
+    /// This method will *not* copy the text given.
+    pub fn split<'t>(&self, text: &'t H) -> Split<'r, 't, H, M> {
+        self.split_at(text, 0)
+    }
+
+    /// Returns an iterator of the substrings of `text[start..]`, split as
+    /// configured. The regular expression still sees all of `text`, so
+    /// anchors and word boundaries at `start` behave as they would if
+    /// splitting had begun at the start of `text`.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start` is greater than the length of `text`.
+    pub fn split_at<'t>(&self, text: &'t H, start: usize) -> Split<'r, 't, H, M> {
+        assert!(
+            start <= text.len(),
+            "start {} is out of bounds of text of length {}",
+            start,
+            text.len()
+        );
+        let mut finder =
+            Delimiters::new(MatcherRanges::new(self.matcher, text, start), self.escape);
+        #[cfg(feature = "aho-corasick")]
+        {
+            finder.literals = self
+                .literals
+                .get_or_init(|| self.regex.and_then(literal_finder::<H>))
+                .clone();
+        }
+        #[cfg(feature = "memchr")]
+        {
+            finder.literal = self
+                .literal
+                .get_or_init(|| self.regex.and_then(memmem_finder::<H>))
+                .clone();
+        }
+        finder.zero_width = self.options.zero_width;
+        finder.coalesce = self.options.coalesce;
+        finder.skip_escaped = self.options.skip_escaped;
+        Split {
+            core: SplitCore::new(finder, self.options, text, start),
+        }
+    }
+
+    /// Returns the number of substrings splitting `text` as configured would
+    /// produce, without slicing any of them out of the text.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::{Splitter, TrailingEmpty};
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text = "one\ntwo\nthree\n";
+    /// assert_eq!(Splitter::new(&re).count_segments(text), 4);
+    /// let splitter = Splitter::new(&re).trailing_empty(TrailingEmpty::Drop);
+    /// assert_eq!(splitter.count_segments(text), 3);
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::{bytes::Splitter, TrailingEmpty};
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text = b"one\ntwo\nthree\n";
+    /// assert_eq!(Splitter::new(&re).count_segments(text), 4);
+    /// let splitter = Splitter::new(&re).trailing_empty(TrailingEmpty::Drop);
+    /// assert_eq!(splitter.count_segments(text), 3);
+    /// # }
+    /// ```
+    pub fn count_segments(&self, text: &H) -> usize {
+        self.split(text).count()
+    }
+}
+
+impl<'r, H: Haystack + ?Sized, M: FallibleMatcher<H> + ?Sized> Splitter<'r, H, M> {
+    /// Returns an iterator of the substrings of `text`, split as configured,
+    /// that yields an error as its last item rather than stopping quietly
+    /// when a search fails or, with [`ZeroWidth::Error`], a zero-width match
+    /// is found. See [`TrySplit`].
+    ///
+    /// Any error the matcher kept from an earlier search is cleared first.
+    pub fn try_split<'t>(&self, text: &'t H) -> TrySplit<'r, 't, H, M> {
+        self.matcher.take_error();
+        TrySplit {
+            inner: self.split(text),
+            matcher: self.matcher,
+            done: false,
+        }
+    }
+}
+
+/// The type behind [`crate::Split`] and [`crate::bytes::Split`].
+#[derive(Debug)]
+pub struct Split<'r, 't, H: Haystack + ?Sized, M: ?Sized = <H as Haystack>::Regex> {
+    core: SplitCore<'r, 't, M, H>,
+}
+
+// Not derived, which would need `M: Clone`, though only a reference is held.
+impl<'r, 't, H: Haystack + ?Sized, M: ?Sized> Clone for Split<'r, 't, H, M> {
+    fn clone(&self) -> Self {
+        Self {
+            core: self.core.clone(),
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized, M: Matcher<H> + ?Sized> Split<'r, 't, H, M> {
+    /// Returns the error that stopped iteration, if any. Only an iterator
+    /// configured with [`ZeroWidth::Error`] can stop early.
+    pub fn error(&self) -> Option<ZeroWidthError> {
+        self.core.error()
+    }
+
+    /// Returns the part of the text not yet yielded, or `None` once the
+    /// iterator is exhausted.
+    pub fn remainder(&self) -> Option<&'t H> {
+        self.core.remainder()
+    }
+
+    /// Returns the offset into the text up to which the iterator has
+    /// consumed it. Every substring yielded so far ends at or before it.
+    pub fn offset(&self) -> usize {
+        self.core.offset()
+    }
+
+    /// Adapts the iterator to yield slices of the text covering `size`
+    /// consecutive substrings at a time, where each slice shares its last
+    /// `overlap` substrings with the next one. The last slice may cover
+    /// fewer substrings. The slices are views into the text, not copies.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero or `overlap` is not less than `size`.
+    pub fn windows(self, size: usize, overlap: usize) -> Windows<'t, H, Self> {
+        let text = self.core.text;
+        Windows::new(self, text, size, overlap)
+    }
+
+    /// Adapts the iterator to pair each substring with the [`Position`]
+    /// where it begins, counting lines as it goes.
+    pub fn located(self) -> Located<'t, H, Self> {
+        let text = self.core.text;
+        Located::new(self, text)
+    }
+
+    /// Collects the substrings into a vector allocated up front to hold all
+    /// of them, which takes a counting pass over the text first. That doubles
+    /// the searching but saves growing the vector over and over, which wins
+    /// out when there are very many substrings.
+    pub fn to_vec(self) -> Vec<&'t H> {
+        let mut v = Vec::with_capacity(self.clone().count());
+        v.extend(self);
+        v
+    }
+
+    /// Clears `buf` and fills it with the substrings, reusing its allocation.
+    /// Splitting many texts into the same buffer in turn saves allocating a
+    /// fresh vector for each.
+    pub fn collect_into(self, buf: &mut Vec<&'t H>) {
+        buf.clear();
+        buf.extend(self);
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized, M: Matcher<H> + ?Sized> Iterator for Split<'r, 't, H, M> {
+    type Item = &'t H;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.core.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.core.size_hint()
+    }
+
+    fn count(self) -> usize {
+        self.core.count()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.core.nth(n)
+    }
+
+    fn last(self) -> Option<Self::Item> {
+        self.core.last()
+    }
+
+    fn fold<B, F>(self, init: B, f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        self.core.fold(init, f)
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized, M: Matcher<H> + ?Sized> DoubleEndedIterator
+    for Split<'r, 't, H, M>
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.core.next_back()
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized, M: Matcher<H> + ?Sized> FusedIterator for Split<'r, 't, H, M> {}
+
+/// The type behind [`crate::TrySplit`] and [`crate::bytes::TrySplit`].
+#[derive(Debug)]
+pub struct TrySplit<'r, 't, H: Haystack + ?Sized, M: ?Sized = <H as Haystack>::Regex> {
+    inner: Split<'r, 't, H, M>,
+    matcher: &'r M,
+    done: bool,
+}
+
+impl<'r, 't, H: Haystack + ?Sized, M: FallibleMatcher<H> + ?Sized> Iterator
+    for TrySplit<'r, 't, H, M>
+{
+    type Item = Result<&'t H, SplitError<M::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let next = self.inner.next();
+
+        // A failed search looks like the end of the text to `Split`, so the
+        // substring it made of the rest is dropped.
+        if let Some(e) = self.matcher.take_error() {
+            self.done = true;
+            return Some(Err(SplitError::Search(e)));
+        }
+        if let Some(e) = self.inner.error() {
+            self.done = true;
+            return Some(Err(SplitError::ZeroWidth(e)));
+        }
+        self.done = next.is_none();
+        next.map(Ok)
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized, M: FallibleMatcher<H> + ?Sized> FusedIterator
+    for TrySplit<'r, 't, H, M>
+{
+}
+
+/// The type behind [`crate::SplitInclusive`] and
+/// [`crate::bytes::SplitInclusive`].
+#[derive(Debug)]
+pub struct SplitInclusive<'r, 't, H: Haystack + ?Sized> {
+    inner: Split<'r, 't, H>,
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Clone for SplitInclusive<'r, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> SplitInclusive<'r, 't, H> {
+    pub(crate) fn new(regex: &'r H::Regex, text: &'t H) -> Self {
+        Self::new_at(regex, text, 0)
+    }
+
+    pub(crate) fn new_at(regex: &'r H::Regex, text: &'t H, start: usize) -> Self {
+        Self {
+            inner: Splitter::with_matcher(regex).split_at(text, start),
+        }
+    }
+
+    /// Sets what to do with the empty substring left at the end of the text
+    /// when the text ends with a match. See [`TrailingEmpty`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::{RegexSplit, TrailingEmpty};
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = "one\ntwo\n";
+    /// let v: Vec<&str> = re
+    ///     .split_inclusive(text)
+    ///     .trailing_empty(TrailingEmpty::Drop)
+    ///     .collect();
+    /// assert_eq!(v, [
+    ///     "one\n",
+    ///     "two\n",
+    /// ]);
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::{bytes::RegexSplit, TrailingEmpty};
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = b"one\ntwo\n";
+    /// let v: Vec<&[u8]> = re
+    ///     .split_inclusive(text)
+    ///     .trailing_empty(TrailingEmpty::Drop)
+    ///     .collect();
+    /// assert_eq!(v, [
+    ///     &b"one\n"[..],
+    ///     &b"two\n"[..],
+    /// ]);
+    /// # }
+    /// ```
+    pub fn trailing_empty(mut self, policy: TrailingEmpty) -> Self {
+        self.inner.core.options.trailing_empty = policy;
+        self
+    }
+
+    /// Sets what to do when the text being split is empty. See
+    /// [`EmptyText`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::{RegexSplit, EmptyText};
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = "";
+    /// assert_eq!(re.split_inclusive(text).count(), 1);
+    /// assert_eq!(re.split_inclusive(text).empty_text(EmptyText::Drop).count(), 0);
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::{bytes::RegexSplit, EmptyText};
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = b"";
+    /// assert_eq!(re.split_inclusive(text).count(), 1);
+    /// assert_eq!(re.split_inclusive(text).empty_text(EmptyText::Drop).count(), 0);
+    /// # }
+    /// ```
+    pub fn empty_text(mut self, policy: EmptyText) -> Self {
+        self.inner.core.options.empty_text = policy;
+        self
+    }
+
+    /// Sets what to do with zero-width matches. See [`ZeroWidth`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::{RegexSplit, ZeroWidth};
+    /// # fn main() {
+    /// let re = Regex::new(r",*").unwrap();
+    /// let text = "a,b";
+    ///
+    /// let v: Vec<&str> = re.split_inclusive(text).zero_width(ZeroWidth::Skip).collect();
+    /// assert_eq!(v, ["a,", "b"]);
+    ///
+    /// let mut it = re.split_inclusive(text).zero_width(ZeroWidth::Error);
+    /// assert_eq!(it.next(), None);
+    /// assert_eq!(it.error().map(|e| e.offset()), Some(0));
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::{bytes::RegexSplit, ZeroWidth};
+    /// # fn main() {
+    /// let re = Regex::new(r",*").unwrap();
+    /// let text = b"a,b";
+    ///
+    /// let v: Vec<&[u8]> = re.split_inclusive(text).zero_width(ZeroWidth::Skip).collect();
+    /// assert_eq!(v, [&b"a,"[..], &b"b"[..]]);
+    ///
+    /// let mut it = re.split_inclusive(text).zero_width(ZeroWidth::Error);
+    /// assert_eq!(it.next(), None);
+    /// assert_eq!(it.error().map(|e| e.offset()), Some(0));
+    /// # }
+    /// ```
+    pub fn zero_width(mut self, policy: ZeroWidth) -> Self {
+        self.inner.core.options.zero_width = policy;
+        self.inner.core.finder.zero_width = policy;
+        self
+    }
+
+    /// Returns the error that stopped iteration, if any. Only an iterator
+    /// configured with [`ZeroWidth::Error`] can stop early.
+    pub fn error(&self) -> Option<ZeroWidthError> {
+        self.inner.error()
+    }
+
+    /// Returns the part of the text not yet yielded, or `None` once the
+    /// iterator is exhausted. Like the unstable `remainder` of std's
+    /// `SplitInclusive`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let mut it = re.split_inclusive("one\ntwo\nthree");
+    /// assert_eq!(it.remainder(), Some("one\ntwo\nthree"));
+    /// it.next();
+    /// assert_eq!(it.remainder(), Some("two\nthree"));
+    /// it.next();
+    /// it.next();
+    /// assert_eq!(it.remainder(), None);
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let mut it = re.split_inclusive(&b"one\ntwo\nthree"[..]);
+    /// assert_eq!(it.remainder(), Some(&b"one\ntwo\nthree"[..]));
+    /// it.next();
+    /// assert_eq!(it.remainder(), Some(&b"two\nthree"[..]));
+    /// it.next();
+    /// it.next();
+    /// assert_eq!(it.remainder(), None);
+    /// # }
+    /// ```
+    pub fn remainder(&self) -> Option<&'t H> {
+        self.inner.remainder()
+    }
+
+    /// Returns the offset into the text up to which the iterator has
+    /// consumed it. Every substring yielded so far ends at or before it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let mut it = re.split_inclusive("one\ntwo\nthree");
+    /// assert_eq!(it.offset(), 0);
+    /// it.next();
+    /// assert_eq!(it.offset(), 4);
+    /// it.next();
+    /// it.next();
+    /// assert_eq!(it.offset(), 13);
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let mut it = re.split_inclusive(&b"one\ntwo\nthree"[..]);
+    /// assert_eq!(it.offset(), 0);
+    /// it.next();
+    /// assert_eq!(it.offset(), 4);
+    /// it.next();
+    /// it.next();
+    /// assert_eq!(it.offset(), 13);
+    /// # }
+    /// ```
+    pub fn offset(&self) -> usize {
+        self.inner.offset()
+    }
+
+    /// Adapts the iterator to yield slices of the text covering `size`
+    /// consecutive substrings at a time, where each slice shares its last
+    /// `overlap` substrings with the next one. The last slice may cover
+    /// fewer substrings. The slices are views into the text, not copies.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero or `overlap` is not less than `size`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\. ").unwrap();
+    /// let text = "A. B. C. D. E. F.";
+    /// let v: Vec<&str> = re.split_inclusive(text).windows(3, 1).collect();
+    /// assert_eq!(v, [
+    ///     "A. B. C. ",
+    ///     "C. D. E. ",
+    ///     "E. F.",
+    /// ]);
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\. ").unwrap();
+    /// let text = b"A. B. C. D. E. F.";
+    /// let v: Vec<&[u8]> = re.split_inclusive(text).windows(3, 1).collect();
+    /// assert_eq!(v, [
+    ///     &b"A. B. C. "[..],
+    ///     &b"C. D. E. "[..],
+    ///     &b"E. F."[..],
+    /// ]);
+    /// # }
+    /// ```
+    pub fn windows(self, size: usize, overlap: usize) -> Windows<'t, H, Self> {
+        let text = self.inner.core.text;
+        Windows::new(self, text, size, overlap)
+    }
+
+    /// Adapts the iterator to pair each substring with the [`Position`]
+    /// where it begins, counting lines as it goes rather than from the start
+    /// of the text for each substring.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r";").unwrap();
+    /// let text = "a = 1;\nb = 2; c = 3;\n";
+    /// let v: Vec<_> = re
+    ///     .split_inclusive(text)
+    ///     .located()
+    ///     .map(|(p, s)| (p.line(), p.column(), s))
+    ///     .collect();
+    /// assert_eq!(v, [
+    ///     (1, 1, "a = 1;"),
+    ///     (1, 7, "\nb = 2;"),
+    ///     (2, 7, " c = 3;"),
+    ///     (2, 14, "\n"),
+    /// ]);
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r";").unwrap();
+    /// let text = b"a = 1;\nb = 2; c = 3;\n";
+    /// let v: Vec<_> = re
+    ///     .split_inclusive(text)
+    ///     .located()
+    ///     .map(|(p, s)| (p.line(), p.column(), s))
+    ///     .collect();
+    /// assert_eq!(v, [
+    ///     (1, 1, &b"a = 1;"[..]),
+    ///     (1, 7, &b"\nb = 2;"[..]),
+    ///     (2, 7, &b" c = 3;"[..]),
+    ///     (2, 14, &b"\n"[..]),
+    /// ]);
+    /// # }
+    /// ```
+    pub fn located(self) -> Located<'t, H, Self> {
+        let text = self.inner.core.text;
+        Located::new(self, text)
+    }
+
+    /// Collects the substrings into a vector allocated up front to hold all
+    /// of them. See [`Split::to_vec`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let v = re.split_inclusive("one\ntwo\nthree").to_vec();
+    /// assert_eq!(v, ["one\n", "two\n", "three"]);
+    /// assert!(v.capacity() >= v.len());
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let v = re.split_inclusive(b"one\ntwo\nthree").to_vec();
+    /// assert_eq!(v, [&b"one\n"[..], &b"two\n"[..], &b"three"[..]]);
+    /// assert!(v.capacity() >= v.len());
+    /// # }
+    /// ```
+    pub fn to_vec(self) -> Vec<&'t H> {
+        self.inner.to_vec()
+    }
+
+    /// Clears `buf` and fills it with the substrings, reusing its allocation.
+    /// See [`Split::collect_into`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let documents = ["one\ntwo", "three"];
+    /// let mut buf = Vec::new();
+    /// let mut count = 0;
+    /// for text in documents {
+    ///     re.split_inclusive(text).collect_into(&mut buf);
+    ///     count += buf.len();
+    /// }
+    /// assert_eq!(count, 3);
+    /// assert_eq!(buf, ["three"]);
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let documents = [&b"one\ntwo"[..], &b"three"[..]];
+    /// let mut buf = Vec::new();
+    /// let mut count = 0;
+    /// for text in documents {
+    ///     re.split_inclusive(text).collect_into(&mut buf);
+    ///     count += buf.len();
+    /// }
+    /// assert_eq!(count, 3);
+    /// assert_eq!(buf, [&b"three"[..]]);
+    /// # }
+    /// ```
+    pub fn collect_into(self, buf: &mut Vec<&'t H>) {
+        self.inner.collect_into(buf)
+    }
+
+    /// Sets whether a run of adjacent matches counts as a single delimiter,
+    /// rather than producing empty substrings between them. Off by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text = "one\n\n\ntwo\nthree";
+    /// let v: Vec<&str> = re.split_inclusive(text).coalesce_delimiters(true).collect();
+    /// assert_eq!(v, [
+    ///     "one\n\n\n",
+    ///     "two\n",
+    ///     "three",
+    /// ]);
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text = b"one\n\n\ntwo\nthree";
+    /// let v: Vec<&[u8]> = re.split_inclusive(text).coalesce_delimiters(true).collect();
+    /// assert_eq!(v, [
+    ///     &b"one\n\n\n"[..],
+    ///     &b"two\n"[..],
+    ///     &b"three"[..],
+    /// ]);
+    /// # }
+    /// ```
+    pub fn coalesce_delimiters(mut self, coalesce: bool) -> Self {
+        self.inner.core.options.coalesce = coalesce;
+        self.inner.core.finder.coalesce = coalesce;
+        self
+    }
+
+    /// Sets whether matches preceded by an odd number of escape characters
+    /// are skipped, so that an escaped delimiter doesn't split. The escape
+    /// character is a backslash unless set with `escape_char`. Off by
+    /// default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r",").unwrap();
+    /// let text = r"a\,b,c\\,d";
+    /// let v: Vec<&str> = re.split_inclusive(text).skip_escaped(true).collect();
+    /// assert_eq!(v, [
+    ///     r"a\,b,",
+    ///     r"c\\,",
+    ///     "d",
+    /// ]);
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r",").unwrap();
+    /// let text = &br"a\,b,c\\,d"[..];
+    /// let v: Vec<&[u8]> = re.split_inclusive(text).skip_escaped(true).collect();
+    /// assert_eq!(v, [
+    ///     &br"a\,b,"[..],
+    ///     &br"c\\,"[..],
+    ///     &b"d"[..],
+    /// ]);
+    /// # }
+    /// ```
+    pub fn skip_escaped(mut self, skip_escaped: bool) -> Self {
+        self.inner.core.options.skip_escaped = skip_escaped;
+        self.inner.core.finder.skip_escaped = skip_escaped;
+        self
+    }
+
+    /// Sets the escape character used by `skip_escaped`.
+    pub fn escape_char(mut self, escape: H::Unit) -> Self {
+        self.inner.core.finder.escape = escape;
+        self
+    }
+
+    /// Sets whether empty substrings are skipped, wherever they come from.
+    /// Off by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text = "one\ntwo\n";
+    /// let v: Vec<&str> = re.split_inclusive(text).skip_empty(true).collect();
+    /// assert_eq!(v, [
+    ///     "one\n",
+    ///     "two\n",
+    /// ]);
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text = b"one\ntwo\n";
+    /// let v: Vec<&[u8]> = re.split_inclusive(text).skip_empty(true).collect();
+    /// assert_eq!(v, [
+    ///     &b"one\n"[..],
+    ///     &b"two\n"[..],
+    /// ]);
+    /// # }
+    /// ```
+    pub fn skip_empty(mut self, skip_empty: bool) -> Self {
+        self.inner.core.options.skip_empty = skip_empty;
+        self
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Iterator for SplitInclusive<'r, 't, H> {
+    type Item = &'t H;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+
+    fn count(self) -> usize {
+        self.inner.count()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.inner.nth(n)
+    }
+
+    fn last(self) -> Option<Self::Item> {
+        self.inner.last()
+    }
+
+    fn fold<B, F>(self, init: B, f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        self.inner.fold(init, f)
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> DoubleEndedIterator for SplitInclusive<'r, 't, H> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> FusedIterator for SplitInclusive<'r, 't, H> {}
+
+/// The type behind [`crate::SplitInclusiveLeft`] and
+/// [`crate::bytes::SplitInclusiveLeft`].
+#[derive(Debug)]
+pub struct SplitInclusiveLeft<'r, 't, H: Haystack + ?Sized> {
+    inner: Split<'r, 't, H>,
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Clone for SplitInclusiveLeft<'r, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> SplitInclusiveLeft<'r, 't, H> {
+    pub(crate) fn new(regex: &'r H::Regex, text: &'t H) -> Self {
+        Self {
+            inner: Splitter::with_matcher(regex).keep(Keep::Left).split(text),
+        }
+    }
+
+    /// Sets what to do with the empty substring left at the end of the text
+    /// when the text ends with a match. See [`TrailingEmpty`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::{RegexSplit, TrailingEmpty};
+    /// # fn main() {
+    /// let re = Regex::new(r"(?m)$").unwrap();
+    /// let text = "one\ntwo";
+    /// let v: Vec<&str> = re
+    ///     .split_inclusive_left(text)
+    ///     .trailing_empty(TrailingEmpty::Drop)
+    ///     .collect();
+    /// assert_eq!(v, [
+    ///     "one",
+    ///     "\ntwo",
+    /// ]);
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::{bytes::RegexSplit, TrailingEmpty};
+    /// # fn main() {
+    /// let re = Regex::new(r"(?m)$").unwrap();
+    /// let text = b"one\ntwo";
+    /// let v: Vec<&[u8]> = re
+    ///     .split_inclusive_left(text)
+    ///     .trailing_empty(TrailingEmpty::Drop)
+    ///     .collect();
+    /// assert_eq!(v, [
+    ///     &b"one"[..],
+    ///     &b"\ntwo"[..],
+    /// ]);
+    /// # }
+    /// ```
+    pub fn trailing_empty(mut self, policy: TrailingEmpty) -> Self {
+        self.inner.core.options.trailing_empty = policy;
+        self
+    }
+
+    /// Sets what to do with the empty substring yielded first when the text
+    /// begins with a match. See [`LeadingEmpty`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::{RegexSplit, LeadingEmpty};
+    /// # fn main() {
+    /// let re = Regex::new(r"(?m)^#").unwrap();
+    /// let text = "# One\ntext\n# Two\nmore text";
+    /// let v: Vec<&str> = re
+    ///     .split_inclusive_left(text)
+    ///     .leading_empty(LeadingEmpty::Drop)
+    ///     .collect();
+    /// assert_eq!(v, [
+    ///     "# One\ntext\n",
+    ///     "# Two\nmore text",
+    /// ]);
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::{bytes::RegexSplit, LeadingEmpty};
+    /// # fn main() {
+    /// let re = Regex::new(r"(?m)^#").unwrap();
+    /// let text = b"# One\ntext\n# Two\nmore text";
+    /// let v: Vec<&[u8]> = re
+    ///     .split_inclusive_left(text)
+    ///     .leading_empty(LeadingEmpty::Drop)
+    ///     .collect();
+    /// assert_eq!(v, [
+    ///     &b"# One\ntext\n"[..],
+    ///     &b"# Two\nmore text"[..],
+    /// ]);
+    /// # }
+    /// ```
+    pub fn leading_empty(mut self, policy: LeadingEmpty) -> Self {
+        self.inner.core.options.leading_empty = policy;
+        self
+    }
+
+    /// Sets what to do when the text being split is empty. See
+    /// [`EmptyText`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::{RegexSplit, EmptyText};
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = "";
+    /// assert_eq!(re.split_inclusive_left(text).count(), 1);
+    /// assert_eq!(re.split_inclusive_left(text).empty_text(EmptyText::Drop).count(), 0);
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::{bytes::RegexSplit, EmptyText};
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = b"";
+    /// assert_eq!(re.split_inclusive_left(text).count(), 1);
+    /// assert_eq!(re.split_inclusive_left(text).empty_text(EmptyText::Drop).count(), 0);
+    /// # }
+    /// ```
+    pub fn empty_text(mut self, policy: EmptyText) -> Self {
+        self.inner.core.options.empty_text = policy;
+        self
+    }
+
+    /// Sets what to do with zero-width matches. See [`ZeroWidth`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::{RegexSplit, ZeroWidth};
+    /// # fn main() {
+    /// let re = Regex::new(r",*").unwrap();
+    /// let text = "a,b";
+    ///
+    /// let v: Vec<&str> = re.split_inclusive_left(text).zero_width(ZeroWidth::Skip).collect();
+    /// assert_eq!(v, ["a", ",b"]);
+    ///
+    /// let mut it = re.split_inclusive_left(text).zero_width(ZeroWidth::Error);
+    /// assert_eq!(it.next(), None);
+    /// assert_eq!(it.error().map(|e| e.offset()), Some(0));
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::{bytes::RegexSplit, ZeroWidth};
+    /// # fn main() {
+    /// let re = Regex::new(r",*").unwrap();
+    /// let text = b"a,b";
+    ///
+    /// let v: Vec<&[u8]> = re.split_inclusive_left(text).zero_width(ZeroWidth::Skip).collect();
+    /// assert_eq!(v, [&b"a"[..], &b",b"[..]]);
+    ///
+    /// let mut it = re.split_inclusive_left(text).zero_width(ZeroWidth::Error);
+    /// assert_eq!(it.next(), None);
+    /// assert_eq!(it.error().map(|e| e.offset()), Some(0));
+    /// # }
+    /// ```
+    pub fn zero_width(mut self, policy: ZeroWidth) -> Self {
+        self.inner.core.options.zero_width = policy;
+        self.inner.core.finder.zero_width = policy;
+        self
+    }
+
+    /// Returns the error that stopped iteration, if any. Only an iterator
+    /// configured with [`ZeroWidth::Error`] can stop early.
+    pub fn error(&self) -> Option<ZeroWidthError> {
+        self.inner.error()
+    }
+
+    /// Returns the part of the text not yet yielded, or `None` once the
+    /// iterator is exhausted. Like the unstable `remainder` of std's
+    /// `SplitInclusive`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let mut it = re.split_inclusive_left("one\ntwo\nthree");
+    /// assert_eq!(it.remainder(), Some("one\ntwo\nthree"));
+    /// it.next();
+    /// assert_eq!(it.remainder(), Some("\ntwo\nthree"));
+    /// it.next();
+    /// it.next();
+    /// assert_eq!(it.remainder(), None);
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let mut it = re.split_inclusive_left(&b"one\ntwo\nthree"[..]);
+    /// assert_eq!(it.remainder(), Some(&b"one\ntwo\nthree"[..]));
+    /// it.next();
+    /// assert_eq!(it.remainder(), Some(&b"\ntwo\nthree"[..]));
+    /// it.next();
+    /// it.next();
+    /// assert_eq!(it.remainder(), None);
+    /// # }
+    /// ```
+    pub fn remainder(&self) -> Option<&'t H> {
+        self.inner.remainder()
+    }
+
+    /// Returns the offset into the text up to which the iterator has
+    /// consumed it. Every substring yielded so far ends at or before it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let mut it = re.split_inclusive_left("one\ntwo\nthree");
+    /// assert_eq!(it.offset(), 0);
+    /// it.next();
+    /// assert_eq!(it.offset(), 3);
+    /// it.next();
+    /// it.next();
+    /// assert_eq!(it.offset(), 13);
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let mut it = re.split_inclusive_left(&b"one\ntwo\nthree"[..]);
+    /// assert_eq!(it.offset(), 0);
+    /// it.next();
+    /// assert_eq!(it.offset(), 3);
+    /// it.next();
+    /// it.next();
+    /// assert_eq!(it.offset(), 13);
+    /// # }
+    /// ```
+    pub fn offset(&self) -> usize {
+        self.inner.offset()
+    }
+
+    /// Adapts the iterator to yield slices of the text covering `size`
+    /// consecutive substrings at a time, where each slice shares its last
+    /// `overlap` substrings with the next one. The last slice may cover
+    /// fewer substrings. The slices are views into the text, not copies.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero or `overlap` is not less than `size`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text = "a\nb\nc\nd\ne";
+    /// let v: Vec<&str> = re.split_inclusive_left(text).windows(3, 1).collect();
+    /// assert_eq!(v, [
+    ///     "a\nb\nc",
+    ///     "\nc\nd\ne",
+    /// ]);
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text = b"a\nb\nc\nd\ne";
+    /// let v: Vec<&[u8]> = re.split_inclusive_left(text).windows(3, 1).collect();
+    /// assert_eq!(v, [
+    ///     &b"a\nb\nc"[..],
+    ///     &b"\nc\nd\ne"[..],
+    /// ]);
+    /// # }
+    /// ```
+    pub fn windows(self, size: usize, overlap: usize) -> Windows<'t, H, Self> {
+        let text = self.inner.core.text;
+        Windows::new(self, text, size, overlap)
+    }
+
+    /// Adapts the iterator to pair each substring with the [`Position`]
+    /// where it begins, counting lines as it goes rather than from the start
+    /// of the text for each substring.
+    pub fn located(self) -> Located<'t, H, Self> {
+        let text = self.inner.core.text;
+        Located::new(self, text)
+    }
+
+    /// Collects the substrings into a vector allocated up front to hold all
+    /// of them. See [`Split::to_vec`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let v = re.split_inclusive_left("one\ntwo\nthree").to_vec();
+    /// assert_eq!(v, ["one", "\ntwo", "\nthree"]);
+    /// assert!(v.capacity() >= v.len());
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let v = re.split_inclusive_left(b"one\ntwo\nthree").to_vec();
+    /// assert_eq!(v, [&b"one"[..], &b"\ntwo"[..], &b"\nthree"[..]]);
+    /// assert!(v.capacity() >= v.len());
+    /// # }
+    /// ```
+    pub fn to_vec(self) -> Vec<&'t H> {
+        self.inner.to_vec()
+    }
+
+    /// Clears `buf` and fills it with the substrings, reusing its allocation.
+    /// See [`Split::collect_into`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let documents = ["one\ntwo", "three"];
+    /// let mut buf = Vec::new();
+    /// let mut count = 0;
+    /// for text in documents {
+    ///     re.split_inclusive_left(text).collect_into(&mut buf);
+    ///     count += buf.len();
+    /// }
+    /// assert_eq!(count, 3);
+    /// assert_eq!(buf, ["three"]);
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let documents = [&b"one\ntwo"[..], &b"three"[..]];
+    /// let mut buf = Vec::new();
+    /// let mut count = 0;
+    /// for text in documents {
+    ///     re.split_inclusive_left(text).collect_into(&mut buf);
+    ///     count += buf.len();
+    /// }
+    /// assert_eq!(count, 3);
+    /// assert_eq!(buf, [&b"three"[..]]);
+    /// # }
+    /// ```
+    pub fn collect_into(self, buf: &mut Vec<&'t H>) {
+        self.inner.collect_into(buf)
+    }
+
+    /// Sets whether a run of adjacent matches counts as a single delimiter,
+    /// rather than producing empty substrings between them. Off by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text = "one\n\n\ntwo\nthree";
+    /// let v: Vec<&str> = re.split_inclusive_left(text).coalesce_delimiters(true).collect();
+    /// assert_eq!(v, [
+    ///     "one",
+    ///     "\n\n\ntwo",
+    ///     "\nthree",
+    /// ]);
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text = b"one\n\n\ntwo\nthree";
+    /// let v: Vec<&[u8]> = re.split_inclusive_left(text).coalesce_delimiters(true).collect();
+    /// assert_eq!(v, [
+    ///     &b"one"[..],
+    ///     &b"\n\n\ntwo"[..],
+    ///     &b"\nthree"[..],
+    /// ]);
+    /// # }
+    /// ```
+    pub fn coalesce_delimiters(mut self, coalesce: bool) -> Self {
+        self.inner.core.options.coalesce = coalesce;
+        self.inner.core.finder.coalesce = coalesce;
+        self
+    }
+
+    /// Sets whether matches preceded by an odd number of escape characters
+    /// are skipped, so that an escaped delimiter doesn't split. The escape
+    /// character is a backslash unless set with `escape_char`. Off by
+    /// default.
+    pub fn skip_escaped(mut self, skip_escaped: bool) -> Self {
+        self.inner.core.options.skip_escaped = skip_escaped;
+        self.inner.core.finder.skip_escaped = skip_escaped;
+        self
+    }
+
+    /// Sets the escape character used by `skip_escaped`.
+    pub fn escape_char(mut self, escape: H::Unit) -> Self {
+        self.inner.core.finder.escape = escape;
+        self
+    }
+
+    /// Sets whether empty substrings are skipped, wherever they come from.
+    /// Off by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text = "\none\ntwo";
+    /// let v: Vec<&str> = re.split_inclusive_left(text).skip_empty(true).collect();
+    /// assert_eq!(v, [
+    ///     "\none",
+    ///     "\ntwo",
+    /// ]);
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text = b"\none\ntwo";
+    /// let v: Vec<&[u8]> = re.split_inclusive_left(text).skip_empty(true).collect();
+    /// assert_eq!(v, [
+    ///     &b"\none"[..],
+    ///     &b"\ntwo"[..],
+    /// ]);
+    /// # }
+    /// ```
+    pub fn skip_empty(mut self, skip_empty: bool) -> Self {
+        self.inner.core.options.skip_empty = skip_empty;
+        self
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Iterator for SplitInclusiveLeft<'r, 't, H> {
+    type Item = &'t H;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+
+    fn count(self) -> usize {
+        self.inner.count()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.inner.nth(n)
+    }
+
+    fn last(self) -> Option<Self::Item> {
+        self.inner.last()
+    }
+
+    fn fold<B, F>(self, init: B, f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        self.inner.fold(init, f)
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> DoubleEndedIterator for SplitInclusiveLeft<'r, 't, H> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> FusedIterator for SplitInclusiveLeft<'r, 't, H> {}
+
+/// The type behind [`crate::RSplitNInclusive`] and
+/// [`crate::bytes::RSplitNInclusive`].
+#[derive(Debug)]
+pub struct RSplitNInclusive<'r, 't, H: Haystack + ?Sized> {
+    inner: RSplitNCore<'r, 't, H::Regex, H>,
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Clone for RSplitNInclusive<'r, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> RSplitNInclusive<'r, 't, H> {
+    pub(crate) fn new(regex: &'r H::Regex, text: &'t H, n: usize) -> Self {
+        let finder = Delimiters::new(MatcherRanges::new(regex, text, 0), <H::Unit>::from(b'\\'));
+        Self {
+            inner: RSplitNCore::new(finder, text, n),
+        }
+    }
+
+    /// Sets what to do when the text being split is empty. See
+    /// [`EmptyText`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::{RegexSplit, EmptyText};
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = "";
+    /// assert_eq!(re.rsplitn_inclusive(text, 2).count(), 1);
+    /// assert_eq!(re.rsplitn_inclusive(text, 2).empty_text(EmptyText::Drop).count(), 0);
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::{bytes::RegexSplit, EmptyText};
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = b"";
+    /// assert_eq!(re.rsplitn_inclusive(text, 2).count(), 1);
+    /// assert_eq!(re.rsplitn_inclusive(text, 2).empty_text(EmptyText::Drop).count(), 0);
+    /// # }
+    /// ```
+    pub fn empty_text(mut self, policy: EmptyText) -> Self {
+        self.inner.empty_text = policy;
+        self
+    }
+
+    /// Sets what to do with zero-width matches. See [`ZeroWidth`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::{RegexSplit, ZeroWidth};
+    /// # fn main() {
+    /// let re = Regex::new(r",*").unwrap();
+    /// let text = "a,b";
+    ///
+    /// let v: Vec<&str> = re.rsplitn_inclusive(text, 2).zero_width(ZeroWidth::Skip).collect();
+    /// assert_eq!(v, ["b", "a,"]);
+    ///
+    /// let mut it = re.rsplitn_inclusive(text, 2).zero_width(ZeroWidth::Error);
+    /// assert_eq!(it.next(), None);
+    /// assert_eq!(it.error().map(|e| e.offset()), Some(0));
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::{bytes::RegexSplit, ZeroWidth};
+    /// # fn main() {
+    /// let re = Regex::new(r",*").unwrap();
+    /// let text = b"a,b";
+    ///
+    /// let v: Vec<&[u8]> = re.rsplitn_inclusive(text, 2).zero_width(ZeroWidth::Skip).collect();
+    /// assert_eq!(v, [&b"b"[..], &b"a,"[..]]);
+    ///
+    /// let mut it = re.rsplitn_inclusive(text, 2).zero_width(ZeroWidth::Error);
+    /// assert_eq!(it.next(), None);
+    /// assert_eq!(it.error().map(|e| e.offset()), Some(0));
+    /// # }
+    /// ```
+    pub fn zero_width(mut self, policy: ZeroWidth) -> Self {
+        self.inner.finder.zero_width = policy;
+        self
+    }
+
+    /// Returns the error that stopped iteration, if any. Only an iterator
+    /// configured with [`ZeroWidth::Error`] can stop early.
+    pub fn error(&self) -> Option<ZeroWidthError> {
+        self.inner.error
+    }
+
+    /// Sets whether a run of adjacent matches counts as a single delimiter,
+    /// rather than producing empty substrings between them. Off by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text = "one\n\n\ntwo\nthree";
+    /// let v: Vec<&str> = re.rsplitn_inclusive(text, 2).coalesce_delimiters(true).collect();
+    /// assert_eq!(v, [
+    ///     "three",
+    ///     "one\n\n\ntwo\n",
+    /// ]);
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text = b"one\n\n\ntwo\nthree";
+    /// let v: Vec<&[u8]> = re.rsplitn_inclusive(text, 2).coalesce_delimiters(true).collect();
+    /// assert_eq!(v, [
+    ///     &b"three"[..],
+    ///     &b"one\n\n\ntwo\n"[..],
+    /// ]);
+    /// # }
+    /// ```
+    pub fn coalesce_delimiters(mut self, coalesce: bool) -> Self {
+        self.inner.finder.coalesce = coalesce;
+        self
+    }
+
+    /// Sets whether matches preceded by an odd number of escape characters
+    /// are skipped, so that an escaped delimiter doesn't split. The escape
+    /// character is a backslash unless set with `escape_char`. Off by
+    /// default.
+    pub fn skip_escaped(mut self, skip_escaped: bool) -> Self {
+        self.inner.finder.skip_escaped = skip_escaped;
+        self
+    }
+
+    /// Sets the escape character used by `skip_escaped`.
+    pub fn escape_char(mut self, escape: H::Unit) -> Self {
+        self.inner.finder.escape = escape;
+        self
+    }
+
+    /// Sets whether empty substrings are skipped, wherever they come from.
+    /// Skipped substrings don't count towards the limit. Off by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text = "one\ntwo\nthree\n";
+    /// let v: Vec<&str> = re.rsplitn_inclusive(text, 2).skip_empty(true).collect();
+    /// assert_eq!(v, ["three\n", "one\ntwo\n"]);
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text = b"one\ntwo\nthree\n";
+    /// let v: Vec<&[u8]> = re.rsplitn_inclusive(text, 2).skip_empty(true).collect();
+    /// assert_eq!(v, [&b"three\n"[..], &b"one\ntwo\n"[..]]);
+    /// # }
+    /// ```
+    pub fn skip_empty(mut self, skip_empty: bool) -> Self {
+        self.inner.skip_empty = skip_empty;
+        self
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Iterator for RSplitNInclusive<'r, 't, H> {
+    type Item = &'t H;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+
+    fn fold<B, F>(self, init: B, f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        self.inner.fold(init, f)
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> FusedIterator for RSplitNInclusive<'r, 't, H> {}
+
+/// The type behind [`crate::SplitInclusiveRev`] and
+/// [`crate::bytes::SplitInclusiveRev`].
+#[derive(Debug)]
+pub struct SplitInclusiveRev<'r, 't, H: Haystack + ?Sized> {
+    inner: SplitRevCore<'r, 't, H::Regex, H>,
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Clone for SplitInclusiveRev<'r, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> SplitInclusiveRev<'r, 't, H> {
+    pub(crate) fn new(regex: &'r H::Regex, text: &'t H) -> Self {
+        Self {
+            inner: SplitRevCore::new(regex, text, false),
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Iterator for SplitInclusiveRev<'r, 't, H> {
+    type Item = &'t H;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> FusedIterator for SplitInclusiveRev<'r, 't, H> {}
+
+/// The type behind [`crate::SplitInclusiveLeftRev`] and
+/// [`crate::bytes::SplitInclusiveLeftRev`].
+#[derive(Debug)]
+pub struct SplitInclusiveLeftRev<'r, 't, H: Haystack + ?Sized> {
+    inner: SplitRevCore<'r, 't, H::Regex, H>,
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Clone for SplitInclusiveLeftRev<'r, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> SplitInclusiveLeftRev<'r, 't, H> {
+    pub(crate) fn new(regex: &'r H::Regex, text: &'t H) -> Self {
+        Self {
+            inner: SplitRevCore::new(regex, text, true),
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Iterator for SplitInclusiveLeftRev<'r, 't, H> {
+    type Item = &'t H;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> FusedIterator for SplitInclusiveLeftRev<'r, 't, H> {}
+
+/// The type behind [`crate::SplitInclusiveBoth`] and
+/// [`crate::bytes::SplitInclusiveBoth`].
+#[derive(Debug)]
+pub struct SplitInclusiveBoth<'r, 't, H: Haystack + ?Sized> {
+    inner: SplitBothCore<'r, 't, H::Regex, H>,
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Clone for SplitInclusiveBoth<'r, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> SplitInclusiveBoth<'r, 't, H> {
+    pub(crate) fn new(regex: &'r H::Regex, text: &'t H) -> Self {
+        Self {
+            inner: SplitBothCore::new(regex, text),
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Iterator for SplitInclusiveBoth<'r, 't, H> {
+    type Item = &'t H;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> FusedIterator for SplitInclusiveBoth<'r, 't, H> {}
+
+/// The type behind [`crate::SplitInclusiveOwned`] and
+/// [`crate::bytes::SplitInclusiveOwned`].
+#[derive(Debug)]
+pub struct SplitInclusiveOwned<H: Haystack + ?Sized> {
+    inner: OwnedSplit<H::Owned>,
+}
+
+impl<H: Haystack + ?Sized> Clone for SplitInclusiveOwned<H> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<H: Haystack + ?Sized> SplitInclusiveOwned<H> {
+    pub(crate) fn new(regex: &H::Regex, text: H::Owned) -> Self {
+        Self {
+            inner: OwnedSplit::new(regex, text),
+        }
+    }
+}
+
+impl<H: Haystack + ?Sized> Iterator for SplitInclusiveOwned<H> {
+    type Item = H::Owned;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let range = self.inner.next_range(false)?;
+        Some(self.inner.text.slice(range).to_owned())
+    }
+}
+
+impl<H: Haystack + ?Sized> FusedIterator for SplitInclusiveOwned<H> {}
+
+/// The type behind [`crate::SplitInclusiveLeftOwned`] and
+/// [`crate::bytes::SplitInclusiveLeftOwned`].
+#[derive(Debug)]
+pub struct SplitInclusiveLeftOwned<H: Haystack + ?Sized> {
+    inner: OwnedSplit<H::Owned>,
+}
+
+impl<H: Haystack + ?Sized> Clone for SplitInclusiveLeftOwned<H> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<H: Haystack + ?Sized> SplitInclusiveLeftOwned<H> {
+    pub(crate) fn new(regex: &H::Regex, text: H::Owned) -> Self {
+        Self {
+            inner: OwnedSplit::new(regex, text),
+        }
+    }
+}
+
+impl<H: Haystack + ?Sized> Iterator for SplitInclusiveLeftOwned<H> {
+    type Item = H::Owned;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let range = self.inner.next_range(true)?;
+        Some(self.inner.text.slice(range).to_owned())
+    }
+}
+
+impl<H: Haystack + ?Sized> FusedIterator for SplitInclusiveLeftOwned<H> {}
+
+/// The type behind [`crate::ArcSegment`] and [`crate::bytes::ArcSegment`].
+#[derive(Debug)]
+pub struct ArcSegment<H: Haystack + ?Sized> {
+    text: Arc<H>,
+    range: Range<usize>,
+}
+
+impl<H: Haystack + ?Sized> Clone for ArcSegment<H> {
+    fn clone(&self) -> Self {
+        Self {
+            text: Arc::clone(&self.text),
+            range: self.range.clone(),
+        }
+    }
+}
+
+impl<H: Haystack + ?Sized> ArcSegment<H> {
+    /// Returns the byte range of the substring within the text.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Returns the whole text the substring belongs to.
+    pub fn text(&self) -> &Arc<H> {
+        &self.text
+    }
+}
+
+impl<H: Haystack + ?Sized> Deref for ArcSegment<H> {
+    type Target = H;
+
+    fn deref(&self) -> &H {
+        self.text.slice(self.range.clone())
+    }
+}
+
+impl<H: Haystack + ?Sized> AsRef<H> for ArcSegment<H> {
+    fn as_ref(&self) -> &H {
+        self
+    }
+}
+
+/// The type behind [`crate::SplitInclusiveArc`] and
+/// [`crate::bytes::SplitInclusiveArc`].
+#[derive(Debug)]
+pub struct SplitInclusiveArc<H: Haystack + ?Sized> {
+    inner: OwnedSplit<Arc<H>>,
+}
+
+impl<H: Haystack + ?Sized> Clone for SplitInclusiveArc<H> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<H: Haystack + ?Sized> SplitInclusiveArc<H> {
+    pub(crate) fn new(regex: &H::Regex, text: Arc<H>) -> Self {
+        Self {
+            inner: OwnedSplit::new(regex, text),
+        }
+    }
+}
+
+impl<H: Haystack + ?Sized> Iterator for SplitInclusiveArc<H> {
+    type Item = ArcSegment<H>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let range = self.inner.next_range(false)?;
+        Some(ArcSegment {
+            text: Arc::clone(&self.inner.text),
+            range,
+        })
+    }
+}
+
+impl<H: Haystack + ?Sized> FusedIterator for SplitInclusiveArc<H> {}
+
+/// The type behind [`crate::SplitInclusiveLeftArc`] and
+/// [`crate::bytes::SplitInclusiveLeftArc`].
+#[derive(Debug)]
+pub struct SplitInclusiveLeftArc<H: Haystack + ?Sized> {
+    inner: OwnedSplit<Arc<H>>,
+}
+
+impl<H: Haystack + ?Sized> Clone for SplitInclusiveLeftArc<H> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<H: Haystack + ?Sized> SplitInclusiveLeftArc<H> {
+    pub(crate) fn new(regex: &H::Regex, text: Arc<H>) -> Self {
+        Self {
+            inner: OwnedSplit::new(regex, text),
+        }
+    }
+}
+
+impl<H: Haystack + ?Sized> Iterator for SplitInclusiveLeftArc<H> {
+    type Item = ArcSegment<H>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let range = self.inner.next_range(true)?;
+        Some(ArcSegment {
+            text: Arc::clone(&self.inner.text),
+            range,
+        })
+    }
+}
+
+impl<H: Haystack + ?Sized> FusedIterator for SplitInclusiveLeftArc<H> {}
+
+/// The type behind [`crate::SplitInclusiveCow`] and
+/// [`crate::bytes::SplitInclusiveCow`].
+#[derive(Debug)]
+pub struct SplitInclusiveCow<'r, 'a, H: Haystack + ?Sized> {
+    inner: CowSplit<SplitInclusive<'r, 'a, H>, SplitInclusiveOwned<H>>,
+}
+
+impl<'r, 'a, H: Haystack + ?Sized> Clone for SplitInclusiveCow<'r, 'a, H> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'r, 'a, H: Haystack + ?Sized> SplitInclusiveCow<'r, 'a, H> {
+    pub(crate) fn new(regex: &'r H::Regex, text: Cow<'a, H>) -> Self {
+        let inner = match text {
+            Cow::Borrowed(text) => CowSplit::Borrowed(SplitInclusive::new(regex, text)),
+            Cow::Owned(text) => CowSplit::Owned(SplitInclusiveOwned::new(regex, text)),
+        };
+        Self { inner }
+    }
+}
+
+impl<'r, 'a, H: Haystack + ?Sized> Iterator for SplitInclusiveCow<'r, 'a, H> {
+    type Item = Cow<'a, H>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+impl<'r, 'a, H: Haystack + ?Sized> FusedIterator for SplitInclusiveCow<'r, 'a, H> {}
+
+/// The type behind [`crate::SplitInclusiveLeftCow`] and
+/// [`crate::bytes::SplitInclusiveLeftCow`].
+#[derive(Debug)]
+pub struct SplitInclusiveLeftCow<'r, 'a, H: Haystack + ?Sized> {
+    inner: CowSplit<SplitInclusiveLeft<'r, 'a, H>, SplitInclusiveLeftOwned<H>>,
+}
+
+impl<'r, 'a, H: Haystack + ?Sized> Clone for SplitInclusiveLeftCow<'r, 'a, H> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'r, 'a, H: Haystack + ?Sized> SplitInclusiveLeftCow<'r, 'a, H> {
+    pub(crate) fn new(regex: &'r H::Regex, text: Cow<'a, H>) -> Self {
+        let inner = match text {
+            Cow::Borrowed(text) => CowSplit::Borrowed(SplitInclusiveLeft::new(regex, text)),
+            Cow::Owned(text) => CowSplit::Owned(SplitInclusiveLeftOwned::new(regex, text)),
+        };
+        Self { inner }
+    }
+}
+
+impl<'r, 'a, H: Haystack + ?Sized> Iterator for SplitInclusiveLeftCow<'r, 'a, H> {
+    type Item = Cow<'a, H>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+impl<'r, 'a, H: Haystack + ?Sized> FusedIterator for SplitInclusiveLeftCow<'r, 'a, H> {}
+
+// Either of two iterators, one over a borrowed text and one over an owned
+// text, yielding `Cow`s.
+#[derive(Clone, Debug)]
+enum CowSplit<B, O> {
+    Borrowed(B),
+    Owned(O),
+}
+
+impl<'a, H, B, O> Iterator for CowSplit<B, O>
+where
+    H: Haystack + ?Sized + 'a,
+    B: Iterator<Item = &'a H>,
+    O: Iterator<Item = H::Owned>,
+{
+    type Item = Cow<'a, H>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            CowSplit::Borrowed(iter) => iter.next().map(Cow::Borrowed),
+            CowSplit::Owned(iter) => iter.next().map(Cow::Owned),
+        }
+    }
+}
+
+// Splits a text it owns, in whatever form, with a regular expression it owns.
+#[derive(Debug)]
+pub(crate) struct OwnedSplit<T: Deref<Target: Haystack>> {
+    regex: <T::Target as Haystack>::Regex,
+    pub(crate) text: T,
+
+    // The state of a `MatchesAt` over the text, which can't be kept as one
+    // because it would borrow from this struct.
+    at: usize,
+    last_end: Option<usize>,
+    last: Option<usize>,
+}
+
+impl<T: Deref<Target: Haystack> + Clone> Clone for OwnedSplit<T> {
+    fn clone(&self) -> Self {
+        Self {
+            regex: self.regex.clone(),
+            text: self.text.clone(),
+            at: self.at,
+            last_end: self.last_end,
+            last: self.last,
+        }
+    }
+}
+
+impl<T: Deref<Target: Haystack>> OwnedSplit<T> {
+    pub(crate) fn new(regex: &<T::Target as Haystack>::Regex, text: T) -> Self {
+        Self {
+            regex: regex.clone(),
+            text,
+            at: 0,
+            last_end: None,
+            last: Some(0),
+        }
+    }
+
+    // Returns the range of the next substring, with the match at its end, or
+    // at the start of the next substring if `left` is set.
+    pub(crate) fn next_range(&mut self, left: bool) -> Option<Range<usize>> {
+        let last = self.last?;
+        let mut matches = MatchesAt {
+            regex: &self.regex,
+            text: &*self.text,
+            at: self.at,
+            last_end: self.last_end,
+        };
+        let m = matches.next();
+        self.at = matches.at;
+        self.last_end = matches.last_end;
+        match m.map(|m| <T::Target as Haystack>::match_range(&m)) {
+            None => {
+                self.last = None;
+                Some(last..self.text.len())
+            }
+            Some(m) => {
+                let end = if left { m.start } else { m.end };
+                self.last = Some(end);
+                Some(last..end)
+            }
+        }
+    }
+}
+
+/// The type behind [`crate::SplitInclusiveBy`] and
+/// [`crate::bytes::SplitInclusiveBy`].
+pub struct SplitInclusiveBy<'r, 't, H: Haystack + ?Sized, F> {
+    finder: MatchesAt<'r, 't, H>,
+    side: F,
+    last: Option<usize>,
+    text: &'t H,
+}
+
+impl<'r, 't, H: Haystack + ?Sized, F: Clone> Clone for SplitInclusiveBy<'r, 't, H, F> {
+    fn clone(&self) -> Self {
+        Self {
+            finder: self.finder.clone(),
+            side: self.side.clone(),
+            last: self.last,
+            text: self.text,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized, F> fmt::Debug for SplitInclusiveBy<'r, 't, H, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SplitInclusiveBy")
+            .field("finder", &self.finder)
+            .field("last", &self.last)
+            .field("text", &self.text)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized, F> SplitInclusiveBy<'r, 't, H, F> {
+    pub(crate) fn new(regex: &'r H::Regex, text: &'t H, side: F) -> Self {
+        Self {
+            finder: MatchesAt::new(regex, text, 0),
+            side,
+            last: Some(0),
+            text,
+        }
+    }
+}
+
+impl<'r, 't, H, F> Iterator for SplitInclusiveBy<'r, 't, H, F>
+where
+    H: Haystack + ?Sized,
+    F: FnMut(&H::Match<'t>) -> Side,
+{
+    type Item = &'t H;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let last = self.last?;
+        match self.finder.next() {
+            None => {
+                let s = self.text.slice(last..self.text.len());
+                self.last = None;
+                Some(s)
+            }
+            Some(m) => {
+                let side = (self.side)(&m);
+                let m = H::match_range(&m);
+                let (end, next) = match side {
+                    Side::Left => (m.end, m.end),
+                    Side::Right => (m.start, m.start),
+                    Side::Both => (m.end, m.start),
+                    Side::Discard => (m.start, m.end),
+                };
+                let s = self.text.slice(last..end);
+                self.last = Some(next);
+                Some(s)
+            }
+        }
+    }
+}
+
+impl<'r, 't, H, F> FusedIterator for SplitInclusiveBy<'r, 't, H, F>
+where
+    H: Haystack + ?Sized,
+    F: FnMut(&H::Match<'t>) -> Side,
+{
+}
+
+/// The type behind [`crate::SplitOnGroup`] and [`crate::bytes::SplitOnGroup`].
+#[derive(Debug)]
+pub struct SplitOnGroup<'r, 't, H: Haystack + ?Sized> {
+    finder: CapturesAt<'r, 't, H>,
+    group: usize,
+    last: Option<usize>,
+    text: &'t H,
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Clone for SplitOnGroup<'r, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            finder: self.finder.clone(),
+            group: self.group,
+            last: self.last,
+            text: self.text,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> SplitOnGroup<'r, 't, H> {
+    pub(crate) fn new(regex: &'r H::Regex, text: &'t H, group: usize) -> Self {
+        Self {
+            finder: CapturesAt::new(regex, text),
+            group,
+            last: Some(0),
+            text,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Iterator for SplitOnGroup<'r, 't, H> {
+    type Item = &'t H;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let last = self.last?;
+        // Matches in which the group took no part don't split the text.
+        let group = self
+            .finder
+            .by_ref()
+            .find_map(|captures| H::group(&captures, self.group));
+        match group.map(|m| H::match_range(&m)) {
+            None => {
+                let s = self.text.slice(last..self.text.len());
+                self.last = None;
+                Some(s)
+            }
+            Some(m) => {
+                let matched = self.text.slice(last..m.end);
+                self.last = Some(m.end);
+                Some(matched)
+            }
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> FusedIterator for SplitOnGroup<'r, 't, H> {}
+
+/// The type behind [`crate::SplitAround`] and [`crate::bytes::SplitAround`].
+#[derive(Debug)]
+pub struct SplitAround<'r, 't, H: Haystack + ?Sized> {
+    finder: CapturesAt<'r, 't, H>,
+    left: Option<usize>,
+    right: Option<usize>,
+    last: Option<usize>,
+    text: &'t H,
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Clone for SplitAround<'r, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            finder: self.finder.clone(),
+            left: self.left,
+            right: self.right,
+            last: self.last,
+            text: self.text,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> SplitAround<'r, 't, H> {
+    pub(crate) fn new(regex: &'r H::Regex, text: &'t H) -> Self {
+        let group = |name| H::capture_names(regex).position(|n| n == Some(name));
+        Self {
+            finder: CapturesAt::new(regex, text),
+            left: group("left"),
+            right: group("right"),
+            last: Some(0),
+            text,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Iterator for SplitAround<'r, 't, H> {
+    type Item = &'t H;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let last = self.last?;
+        match self.finder.next() {
+            None => {
+                let s = self.text.slice(last..self.text.len());
+                self.last = None;
+                Some(s)
+            }
+            Some(captures) => {
+                let m = H::match_range(&H::group(&captures, 0).unwrap());
+                let end = match self.left.and_then(|i| H::group(&captures, i)) {
+                    Some(left) => H::match_range(&left).end,
+                    None => m.start,
+                };
+                let next = match self.right.and_then(|i| H::group(&captures, i)) {
+                    Some(right) => H::match_range(&right).start,
+                    None => m.end,
+                };
+                let s = self.text.slice(last..end);
+                self.last = Some(next);
+                Some(s)
+            }
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> FusedIterator for SplitAround<'r, 't, H> {}
+
+/// The type behind [`crate::SplitInclusiveCaptures`] and
+/// [`crate::bytes::SplitInclusiveCaptures`].
+#[derive(Debug)]
+pub struct SplitInclusiveCaptures<'r, 't, H: Haystack + ?Sized> {
+    finder: CapturesAt<'r, 't, H>,
+    last: Option<usize>,
+    text: &'t H,
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Clone for SplitInclusiveCaptures<'r, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            finder: self.finder.clone(),
+            last: self.last,
+            text: self.text,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> SplitInclusiveCaptures<'r, 't, H> {
+    pub(crate) fn new(regex: &'r H::Regex, text: &'t H) -> Self {
+        Self {
+            finder: CapturesAt::new(regex, text),
+            last: Some(0),
+            text,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Iterator for SplitInclusiveCaptures<'r, 't, H> {
+    type Item = (&'t H, Option<H::Captures<'t>>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let last = self.last?;
+        match self.finder.next() {
+            None => {
+                let s = self.text.slice(last..self.text.len());
+                self.last = None;
+                Some((s, None))
+            }
+            Some(captures) => {
+                let end = H::match_range(&H::group(&captures, 0).unwrap()).end;
+                let s = self.text.slice(last..end);
+                self.last = Some(end);
+                Some((s, Some(captures)))
+            }
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> FusedIterator for SplitInclusiveCaptures<'r, 't, H> {}
+
+/// The type behind [`crate::SplitInclusiveTagged`] and
+/// [`crate::bytes::SplitInclusiveTagged`].
+#[derive(Debug)]
+pub struct SplitInclusiveTagged<'r, 't, H: Haystack + ?Sized> {
+    regex: &'r H::Regex,
+    finder: CapturesAt<'r, 't, H>,
+    last: Option<usize>,
+    text: &'t H,
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Clone for SplitInclusiveTagged<'r, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            regex: self.regex,
+            finder: self.finder.clone(),
+            last: self.last,
+            text: self.text,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> SplitInclusiveTagged<'r, 't, H> {
+    pub(crate) fn new(regex: &'r H::Regex, text: &'t H) -> Self {
+        Self {
+            regex,
+            finder: CapturesAt::new(regex, text),
+            last: Some(0),
+            text,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Iterator for SplitInclusiveTagged<'r, 't, H> {
+    type Item = (&'t H, Option<&'r str>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let last = self.last?;
+        match self.finder.next() {
+            None => {
+                let s = self.text.slice(last..self.text.len());
+                self.last = None;
+                Some((s, None))
+            }
+            Some(captures) => {
+                let end = H::match_range(&H::group(&captures, 0).unwrap()).end;
+                let tag = H::capture_names(self.regex)
+                    .enumerate()
+                    .find_map(|(i, name)| name.filter(|_| H::group(&captures, i).is_some()));
+                let s = self.text.slice(last..end);
+                self.last = Some(end);
+                Some((s, tag))
+            }
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> FusedIterator for SplitInclusiveTagged<'r, 't, H> {}
+
+/// The type behind [`crate::GroupByCapture`] and
+/// [`crate::bytes::GroupByCapture`].
+#[derive(Debug)]
+pub struct GroupByCapture<'r, 't, H: Haystack + ?Sized> {
+    finder: CapturesAt<'r, 't, H>,
+    group: usize,
+
+    // The key captured by the match that began the current section.
+    key: Option<&'t H>,
+    started: bool,
+    last: Option<usize>,
+    text: &'t H,
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Clone for GroupByCapture<'r, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            finder: self.finder.clone(),
+            group: self.group,
+            key: self.key,
+            started: self.started,
+            last: self.last,
+            text: self.text,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> GroupByCapture<'r, 't, H> {
+    pub(crate) fn new(regex: &'r H::Regex, text: &'t H, group: usize) -> Self {
+        Self {
+            finder: CapturesAt::new(regex, text),
+            group,
+            key: None,
+            started: false,
+            last: Some(0),
+            text,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Iterator for GroupByCapture<'r, 't, H> {
+    type Item = (Option<&'t H>, &'t H);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let last = self.last?;
+            match self.finder.next() {
+                None => {
+                    let s = self.text.slice(last..self.text.len());
+                    self.last = None;
+                    return Some((self.key, s));
+                }
+                Some(captures) => {
+                    let m = H::match_range(&H::group(&captures, 0).unwrap());
+                    let s = self.text.slice(last..m.start);
+                    let key = self.key;
+                    self.key = H::group(&captures, self.group).map(|k| H::match_text(&k));
+                    self.last = Some(m.start);
+
+                    // Text before the first match isn't a section if there
+                    // isn't any.
+                    let first = !self.started;
+                    self.started = true;
+                    if !(first && s.is_empty()) {
+                        return Some((key, s));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> FusedIterator for GroupByCapture<'r, 't, H> {}
+
+/// The type behind [`crate::SplitBetween`] and [`crate::bytes::SplitBetween`].
+#[derive(Debug)]
+pub struct SplitBetween<'r, 't, H: Haystack + ?Sized> {
+    start: &'r H::Regex,
+    end: &'r H::Regex,
+    include_markers: bool,
+    last: Option<usize>,
+    text: &'t H,
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Clone for SplitBetween<'r, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            start: self.start,
+            end: self.end,
+            include_markers: self.include_markers,
+            last: self.last,
+            text: self.text,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> SplitBetween<'r, 't, H> {
+    pub(crate) fn new(start: &'r H::Regex, end: &'r H::Regex, text: &'t H) -> Self {
+        Self {
+            start,
+            end,
+            include_markers: false,
+            last: Some(0),
+            text,
+        }
+    }
+
+    /// Sets whether each region includes the matches that open and close it.
+    /// Off by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let begin = Regex::new(r"BEGIN\n").unwrap();
+    /// let end = Regex::new(r"END\n").unwrap();
+    /// let text = "noise\nBEGIN\none\nEND\nnoise\nBEGIN\ntwo\nEND\n";
+    /// let v: Vec<&str> = begin.split_between(text, &end).include_markers(true).collect();
+    /// assert_eq!(v, [
+    ///     "BEGIN\none\nEND\n",
+    ///     "BEGIN\ntwo\nEND\n",
+    /// ]);
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let begin = Regex::new(r"BEGIN\n").unwrap();
+    /// let end = Regex::new(r"END\n").unwrap();
+    /// let text = b"noise\nBEGIN\none\nEND\nnoise\nBEGIN\ntwo\nEND\n";
+    /// let v: Vec<&[u8]> = begin.split_between(text, &end).include_markers(true).collect();
+    /// assert_eq!(v, [
+    ///     &b"BEGIN\none\nEND\n"[..],
+    ///     &b"BEGIN\ntwo\nEND\n"[..],
+    /// ]);
+    /// # }
+    /// ```
+    pub fn include_markers(self, include_markers: bool) -> Self {
+        Self {
+            include_markers,
+            ..self
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Iterator for SplitBetween<'r, 't, H> {
+    type Item = &'t H;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let last = self.last?;
+        let Some(open) = self.text.find_at(self.start, last) else {
+            self.last = None;
+            return None;
+        };
+        let open = H::match_range(&open);
+
+        // A region left open runs to the end of the text.
+        let close = self
+            .text
+            .find_at(self.end, open.end)
+            .map(|m| H::match_range(&m));
+        let (region, next) = match close {
+            Some(close) if self.include_markers => (open.start..close.end, Some(close.end)),
+            Some(close) => (open.end..close.start, Some(close.end)),
+            None if self.include_markers => (open.start..self.text.len(), None),
+            None => (open.end..self.text.len(), None),
+        };
+
+        // Empty markers in the same place would find the same region forever.
+        self.last = match next {
+            Some(next) if next > last => Some(next),
+            Some(_) if last < self.text.len() => Some(last + self.text.step_at(last)),
+            _ => None,
+        };
+
+        Some(self.text.slice(region))
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> FusedIterator for SplitBetween<'r, 't, H> {}
+
+/// The type behind [`crate::SplitInclusiveOutside`] and
+/// [`crate::bytes::SplitInclusiveOutside`].
+#[derive(Debug)]
+pub struct SplitInclusiveOutside<'r, 't, H: Haystack + ?Sized> {
+    finder: MatchesAt<'r, 't, H>,
+    masks: MatchesAt<'r, 't, H>,
+
+    // The first masked region that doesn't end before the last match found.
+    mask: Option<Range<usize>>,
+    last: Option<usize>,
+    text: &'t H,
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Clone for SplitInclusiveOutside<'r, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            finder: self.finder.clone(),
+            masks: self.masks.clone(),
+            mask: self.mask.clone(),
+            last: self.last,
+            text: self.text,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> SplitInclusiveOutside<'r, 't, H> {
+    pub(crate) fn new(regex: &'r H::Regex, mask: &'r H::Regex, text: &'t H) -> Self {
+        let mut masks = MatchesAt::new(mask, text, 0);
+        Self {
+            finder: MatchesAt::new(regex, text, 0),
+            mask: masks.next().map(|m| H::match_range(&m)),
+            masks,
+            last: Some(0),
+            text,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Iterator for SplitInclusiveOutside<'r, 't, H> {
+    type Item = &'t H;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let last = self.last?;
+            let Some(m) = self.finder.next() else {
+                let s = self.text.slice(last..self.text.len());
+                self.last = None;
+                return Some(s);
+            };
+            let m = H::match_range(&m);
+
+            while let Some(mask) = &self.mask {
+                if mask.end > m.start {
+                    break;
+                }
+                self.mask = self.masks.next().map(|mask| H::match_range(&mask));
+            }
+
+            // A match that overlaps a masked region doesn't count. Look again
+            // from the end of the region, where a match may begin that this
+            // one would have hidden.
+            if let Some(mask) = &self.mask {
+                if mask.start < m.end && m.start < mask.end {
+                    self.finder.at = mask.end;
+                    self.finder.last_end = None;
+                    continue;
+                }
+            }
+
+            let s = self.text.slice(last..m.end);
+            self.last = Some(m.end);
+            return Some(s);
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> FusedIterator for SplitInclusiveOutside<'r, 't, H> {}
+
+/// The type behind [`crate::ChunksByMatches`] and
+/// [`crate::bytes::ChunksByMatches`].
+#[derive(Debug)]
+pub struct ChunksByMatches<'r, 't, H: Haystack + ?Sized> {
+    finder: MatchesAt<'r, 't, H>,
+    n: usize,
+    last: usize,
+    text: &'t H,
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Clone for ChunksByMatches<'r, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            finder: self.finder.clone(),
+            n: self.n,
+            last: self.last,
+            text: self.text,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> ChunksByMatches<'r, 't, H> {
+    pub(crate) fn new(regex: &'r H::Regex, text: &'t H, n: usize) -> Self {
+        Self {
+            finder: MatchesAt::new(regex, text, 0),
+            n,
+            last: 0,
+            text,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Iterator for ChunksByMatches<'r, 't, H> {
+    type Item = &'t H;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.finder.nth(self.n - 1) {
+            None => {
+                if self.last >= self.text.len() {
+                    None
+                } else {
+                    let s = self.text.slice(self.last..self.text.len());
+                    self.last = self.text.len(); // Next call will return None
+                    Some(s)
+                }
+            }
+            Some(m) => {
+                let end = H::match_range(&m).end;
+                let chunk = self.text.slice(self.last..end);
+                self.last = end;
+                Some(chunk)
+            }
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> FusedIterator for ChunksByMatches<'r, 't, H> {}
+
+/// The type behind [`crate::Windows`] and [`crate::bytes::Windows`].
+#[derive(Debug)]
+pub struct Windows<'t, H: Haystack + ?Sized, I> {
+    iter: I,
+    size: usize,
+    overlap: usize,
+
+    // The spans of the substrings in the current window, and how many of them
+    // have yet to be yielded as part of any window.
+    spans: VecDeque<Range<usize>>,
+    fresh: usize,
+    text: &'t H,
+}
+
+impl<'t, H: Haystack + ?Sized, I: Clone> Clone for Windows<'t, H, I> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            size: self.size,
+            overlap: self.overlap,
+            spans: self.spans.clone(),
+            fresh: self.fresh,
+            text: self.text,
+        }
+    }
+}
+
+impl<'t, H: Haystack + ?Sized, I> Windows<'t, H, I> {
+    pub(crate) fn new(iter: I, text: &'t H, size: usize, overlap: usize) -> Self {
+        assert!(size != 0, "window size must be non-zero");
+        assert!(
+            overlap < size,
+            "overlap {} must be less than window size {}",
+            overlap,
+            size
+        );
+        Self {
+            iter,
+            size,
+            overlap,
+            spans: VecDeque::with_capacity(size),
+            fresh: 0,
+            text,
+        }
+    }
+}
+
+impl<'t, H, I> Iterator for Windows<'t, H, I>
+where
+    H: Haystack + ?Sized,
+    I: Iterator<Item = &'t H>,
+{
+    type Item = &'t H;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.spans.len() < self.size {
+            match self.iter.next() {
+                Some(s) => {
+                    let start = offset_in(self.text, s);
+                    self.spans.push_back(start..start + s.len());
+                    self.fresh += 1;
+                }
+                None => break,
+            }
+        }
+
+        // A short window at the end is only worth yielding if it holds
+        // something the last one didn't.
+        if self.fresh == 0 {
+            return None;
+        }
+        let window = self
+            .text
+            .slice(self.spans.front()?.start..self.spans.back()?.end);
+        self.fresh = 0;
+        let step = self.size - self.overlap;
+        self.spans.drain(..step.min(self.spans.len()));
+        Some(window)
+    }
+}
+
+impl<'t, H, I> FusedIterator for Windows<'t, H, I>
+where
+    H: Haystack + ?Sized,
+    I: FusedIterator<Item = &'t H>,
+{
+}
+
+/// The type behind [`crate::Located`] and [`crate::bytes::Located`].
+#[derive(Debug)]
+pub struct Located<'t, H: Haystack + ?Sized, I> {
+    iter: I,
+
+    // The position of `scanned`, up to which lines have been counted.
+    position: Position,
+    text: &'t H,
+}
+
+impl<'t, H: Haystack + ?Sized, I: Clone> Clone for Located<'t, H, I> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            position: self.position,
+            text: self.text,
+        }
+    }
+}
+
+impl<'t, H: Haystack + ?Sized, I> Located<'t, H, I> {
+    pub(crate) fn new(iter: I, text: &'t H) -> Self {
+        Self {
+            iter,
+            position: Position {
+                offset: 0,
+                line: 1,
+                column: 1,
+            },
+            text,
+        }
+    }
+}
+
+impl<'t, H, I> Iterator for Located<'t, H, I>
+where
+    H: Haystack + ?Sized,
+    I: Iterator<Item = &'t H>,
+{
+    type Item = (Position, &'t H);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let s = self.iter.next()?;
+        let start = offset_in(self.text, s);
+        let newline = <H::Unit>::from(b'\n');
+        for c in self.text.slice(self.position.offset..start).units() {
+            if c == newline {
+                self.position.line += 1;
+                self.position.column = 1;
+            } else {
+                self.position.column += 1;
+            }
+        }
+        self.position.offset = start;
+        Some((self.position, s))
+    }
+}
+
+impl<'t, H, I> FusedIterator for Located<'t, H, I>
+where
+    H: Haystack + ?Sized,
+    I: FusedIterator<Item = &'t H>,
+{
+}
+
+/// The type behind [`crate::SplitWithDelimiters`] and
+/// [`crate::bytes::SplitWithDelimiters`].
+#[derive(Debug)]
+pub struct SplitWithDelimiters<'r, 't, H: Haystack + ?Sized> {
+    finder: MatchesAt<'r, 't, H>,
+    last: Option<usize>,
+    text: &'t H,
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Clone for SplitWithDelimiters<'r, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            finder: self.finder.clone(),
+            last: self.last,
+            text: self.text,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> SplitWithDelimiters<'r, 't, H> {
+    pub(crate) fn new(regex: &'r H::Regex, text: &'t H) -> Self {
+        Self {
+            finder: MatchesAt::new(regex, text, 0),
+            last: Some(0),
+            text,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Iterator for SplitWithDelimiters<'r, 't, H> {
+    type Item = (&'t H, Option<H::Match<'t>>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let last = self.last?;
+        match self.finder.next() {
+            None => {
+                let s = self.text.slice(last..self.text.len());
+                self.last = None;
+                Some((s, None))
+            }
+            Some(m) => {
+                let range = H::match_range(&m);
+                let s = self.text.slice(last..range.start);
+                self.last = Some(range.end);
+                Some((s, Some(m)))
+            }
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> FusedIterator for SplitWithDelimiters<'r, 't, H> {}
+
+/// The type behind [`crate::MatchesWithContext`] and
+/// [`crate::bytes::MatchesWithContext`].
+#[derive(Debug)]
+pub struct MatchesWithContext<'r, 't, H: Haystack + ?Sized> {
+    finder: MatchesAt<'r, 't, H>,
+    n: usize,
+    text: &'t H,
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Clone for MatchesWithContext<'r, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            finder: self.finder.clone(),
+            n: self.n,
+            text: self.text,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> MatchesWithContext<'r, 't, H> {
+    pub(crate) fn new(regex: &'r H::Regex, text: &'t H, n: usize) -> Self {
+        Self {
+            finder: MatchesAt::new(regex, text, 0),
+            n,
+            text,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Iterator for MatchesWithContext<'r, 't, H> {
+    type Item = (&'t H, Range<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let m = H::match_range(&self.finder.next()?);
+        let text = self.text;
+
+        // Back off from `n` bytes to the nearest place the text can be
+        // sliced, short of splitting a character.
+        let mut start = m.start.saturating_sub(self.n);
+        while !text.is_boundary(start) {
+            start += 1;
+        }
+        let mut end = m.end.saturating_add(self.n).min(text.len());
+        while !text.is_boundary(end) {
+            end -= 1;
+        }
+        Some((text.slice(start..end), m.start - start..m.end - start))
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> FusedIterator for MatchesWithContext<'r, 't, H> {}
+
+/// The type behind [`crate::Part`] and [`crate::bytes::Part`].
+#[derive(Debug)]
+pub enum Part<'t, H: Haystack + ?Sized> {
+    /// Text that is not part of any match. Never empty.
+    Text(&'t H),
+    /// A match of the regular expression.
+    Delim(H::Match<'t>),
+}
+
+impl<'t, H: Haystack + ?Sized> Clone for Part<'t, H> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'t, H: Haystack + ?Sized> Copy for Part<'t, H> {}
+
+impl<'t, H: Haystack + ?Sized + PartialEq> PartialEq for Part<'t, H> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Part::Text(a), Part::Text(b)) => a == b,
+            (Part::Delim(a), Part::Delim(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl<'t, H: Haystack + ?Sized + Eq> Eq for Part<'t, H> {}
+
+impl<'t, H: Haystack + ?Sized> Part<'t, H> {
+    // The text this part covers, whichever kind of part it is.
+    pub(crate) fn text(&self) -> &'t H {
+        match self {
+            Part::Text(s) => s,
+            Part::Delim(m) => H::match_text(m),
+        }
+    }
+}
+
+/// The type behind [`crate::SplitParts`] and [`crate::bytes::SplitParts`].
+#[derive(Debug)]
+pub struct SplitParts<'r, 't, H: Haystack + ?Sized> {
+    finder: MatchesAt<'r, 't, H>,
+    last: usize,
+
+    // A match found while looking for the end of a text part, which is
+    // yielded on the following call.
+    pending: Option<H::Match<'t>>,
+    text: &'t H,
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Clone for SplitParts<'r, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            finder: self.finder.clone(),
+            last: self.last,
+            pending: self.pending,
+            text: self.text,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> SplitParts<'r, 't, H> {
+    pub(crate) fn new(regex: &'r H::Regex, text: &'t H) -> Self {
+        Self {
+            finder: MatchesAt::new(regex, text, 0),
+            last: 0,
+            pending: None,
+            text,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Iterator for SplitParts<'r, 't, H> {
+    type Item = Part<'t, H>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(m) = self.pending.take() {
+            return Some(Part::Delim(m));
+        }
+
+        let Some(m) = self.finder.next() else {
+            if self.last >= self.text.len() {
+                return None;
+            }
+            let s = self.text.slice(self.last..self.text.len());
+            self.last = self.text.len();
+            return Some(Part::Text(s));
+        };
+        let range = H::match_range(&m);
+        if range.start > self.last {
+            let s = self.text.slice(self.last..range.start);
+            self.last = range.end;
+            self.pending = Some(m);
+            Some(Part::Text(s))
+        } else {
+            self.last = range.end;
+            Some(Part::Delim(m))
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> FusedIterator for SplitParts<'r, 't, H> {}
+
+/// The type behind [`crate::SplitInclusiveIndices`] and
+/// [`crate::bytes::SplitInclusiveIndices`].
+#[derive(Debug)]
+pub struct SplitInclusiveIndices<'r, 't, H: Haystack + ?Sized> {
+    inner: SplitInclusive<'r, 't, H>,
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Clone for SplitInclusiveIndices<'r, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> SplitInclusiveIndices<'r, 't, H> {
+    pub(crate) fn new(regex: &'r H::Regex, text: &'t H) -> Self {
+        Self {
+            inner: SplitInclusive::new(regex, text),
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Iterator for SplitInclusiveIndices<'r, 't, H> {
+    type Item = (usize, &'t H);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let s = self.inner.next()?;
+        Some((offset_in(self.inner.inner.core.text, s), s))
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> DoubleEndedIterator for SplitInclusiveIndices<'r, 't, H> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let s = self.inner.next_back()?;
+        Some((offset_in(self.inner.inner.core.text, s), s))
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> FusedIterator for SplitInclusiveIndices<'r, 't, H> {}
+
+/// The type behind [`crate::SplitInclusiveLeftIndices`] and
+/// [`crate::bytes::SplitInclusiveLeftIndices`].
+#[derive(Debug)]
+pub struct SplitInclusiveLeftIndices<'r, 't, H: Haystack + ?Sized> {
+    inner: SplitInclusiveLeft<'r, 't, H>,
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Clone for SplitInclusiveLeftIndices<'r, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> SplitInclusiveLeftIndices<'r, 't, H> {
+    pub(crate) fn new(regex: &'r H::Regex, text: &'t H) -> Self {
+        Self {
+            inner: SplitInclusiveLeft::new(regex, text),
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Iterator for SplitInclusiveLeftIndices<'r, 't, H> {
+    type Item = (usize, &'t H);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let s = self.inner.next()?;
+        Some((offset_in(self.inner.inner.core.text, s), s))
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> DoubleEndedIterator for SplitInclusiveLeftIndices<'r, 't, H> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let s = self.inner.next_back()?;
+        Some((offset_in(self.inner.inner.core.text, s), s))
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> FusedIterator for SplitInclusiveLeftIndices<'r, 't, H> {}
+
+/// The type behind [`crate::Segment`] and [`crate::bytes::Segment`].
+#[derive(Debug)]
+pub struct Segment<'t, H: Haystack + ?Sized> {
+    text: &'t H,
+    range: Range<usize>,
+    delimiter: Option<Range<usize>>,
+}
+
+impl<'t, H: Haystack + ?Sized> Clone for Segment<'t, H> {
+    fn clone(&self) -> Self {
+        Self {
+            text: self.text,
+            range: self.range.clone(),
+            delimiter: self.delimiter.clone(),
+        }
+    }
+}
+
+impl<'t, H: Haystack + ?Sized + PartialEq> PartialEq for Segment<'t, H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.text == other.text && self.range == other.range && self.delimiter == other.delimiter
+    }
+}
+
+impl<'t, H: Haystack + ?Sized + Eq> Eq for Segment<'t, H> {}
+
+impl<'t, H: Haystack + ?Sized> Segment<'t, H> {
+    /// The whole substring, including its delimiter.
+    pub fn full(&self) -> &'t H {
+        self.text.slice(self.range())
+    }
+
+    /// The substring without its delimiter.
+    pub fn body(&self) -> &'t H {
+        self.text.slice(self.body_range())
+    }
+
+    /// The match delimiting the substring. Only the substring at the end of
+    /// the text (or at the start, when splitting with the match at the front)
+    /// has none.
+    pub fn delimiter(&self) -> Option<&'t H> {
+        self.delimiter_range().map(|range| self.text.slice(range))
+    }
+
+    /// The byte range of the whole substring in the text being split.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// The byte range of the substring without its delimiter.
+    pub fn body_range(&self) -> Range<usize> {
+        match &self.delimiter {
+            None => self.range(),
+            Some(delimiter) if delimiter.start == self.range.start => delimiter.end..self.range.end,
+            Some(delimiter) => self.range.start..delimiter.start,
+        }
+    }
+
+    /// The byte range of the delimiting match, if there is one.
+    pub fn delimiter_range(&self) -> Option<Range<usize>> {
+        self.delimiter.clone()
+    }
+}
+
+/// The type behind [`crate::SplitInclusiveSegments`] and
+/// [`crate::bytes::SplitInclusiveSegments`].
+#[derive(Debug)]
+pub struct SplitInclusiveSegments<'r, 't, H: Haystack + ?Sized> {
+    finder: MatchesAt<'r, 't, H>,
+    last: Option<usize>,
+    text: &'t H,
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Clone for SplitInclusiveSegments<'r, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            finder: self.finder.clone(),
+            last: self.last,
+            text: self.text,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> SplitInclusiveSegments<'r, 't, H> {
+    pub(crate) fn new(regex: &'r H::Regex, text: &'t H) -> Self {
+        Self {
+            finder: MatchesAt::new(regex, text, 0),
+            last: Some(0),
+            text,
+        }
+    }
+
+    /// Adapts the iterator to yield each substring with its index, counting
+    /// from zero, and the match delimiting it, if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = "one\ntwo\r\nthree";
+    /// let v: Vec<_> = re.split_inclusive_segments(text).enumerated().collect();
+    /// assert_eq!(v, [
+    ///     (0, Some("\n"), "one\n"),
+    ///     (1, Some("\r\n"), "two\r\n"),
+    ///     (2, None, "three"),
+    /// ]);
+    /// # }
+    /// ```
+    ///
+    /// With a byte string:
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::bytes::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = b"one\ntwo\r\nthree";
+    /// let v: Vec<_> = re.split_inclusive_segments(text).enumerated().collect();
+    /// assert_eq!(v, [
+    ///     (0, Some(&b"\n"[..]), &b"one\n"[..]),
+    ///     (1, Some(&b"\r\n"[..]), &b"two\r\n"[..]),
+    ///     (2, None, &b"three"[..]),
+    /// ]);
+    /// # }
+    /// ```
+    pub fn enumerated(self) -> Enumerated<Self> {
+        Enumerated {
+            iter: self,
+            index: 0,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Iterator for SplitInclusiveSegments<'r, 't, H> {
+    type Item = Segment<'t, H>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let last = self.last?;
+        match self.finder.next() {
+            None => {
+                let segment = Segment {
+                    text: self.text,
+                    range: last..self.text.len(),
+                    delimiter: None,
+                };
+                self.last = None;
+                Some(segment)
+            }
+            Some(m) => {
+                let m = H::match_range(&m);
+                let segment = Segment {
+                    text: self.text,
+                    range: last..m.end,
+                    delimiter: Some(m.clone()),
+                };
+                self.last = Some(m.end);
+                Some(segment)
+            }
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> FusedIterator for SplitInclusiveSegments<'r, 't, H> {}
+
+/// The type behind [`crate::SplitInclusiveLeftSegments`] and
+/// [`crate::bytes::SplitInclusiveLeftSegments`].
+#[derive(Debug)]
+pub struct SplitInclusiveLeftSegments<'r, 't, H: Haystack + ?Sized> {
+    finder: MatchesAt<'r, 't, H>,
+    last: Option<usize>,
+
+    // The match at the front of the next substring.
+    delimiter: Option<Range<usize>>,
+    text: &'t H,
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Clone for SplitInclusiveLeftSegments<'r, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            finder: self.finder.clone(),
+            last: self.last,
+            delimiter: self.delimiter.clone(),
+            text: self.text,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> SplitInclusiveLeftSegments<'r, 't, H> {
+    pub(crate) fn new(regex: &'r H::Regex, text: &'t H) -> Self {
+        Self {
+            finder: MatchesAt::new(regex, text, 0),
+            last: Some(0),
+            delimiter: None,
+            text,
+        }
+    }
+
+    /// Adapts the iterator to yield each substring with its index, counting
+    /// from zero, and the match delimiting it, if any.
+    pub fn enumerated(self) -> Enumerated<Self> {
+        Enumerated {
+            iter: self,
+            index: 0,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Iterator for SplitInclusiveLeftSegments<'r, 't, H> {
+    type Item = Segment<'t, H>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let last = self.last?;
+        match self.finder.next() {
+            None => {
+                let segment = Segment {
+                    text: self.text,
+                    range: last..self.text.len(),
+                    delimiter: self.delimiter.take(),
+                };
+                self.last = None;
+                Some(segment)
+            }
+            Some(m) => {
+                let m = H::match_range(&m);
+                let segment = Segment {
+                    text: self.text,
+                    range: last..m.start,
+                    delimiter: self.delimiter.replace(m.clone()),
+                };
+                self.last = Some(m.start);
+                Some(segment)
+            }
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> FusedIterator for SplitInclusiveLeftSegments<'r, 't, H> {}
+
+/// The type behind [`crate::Enumerated`] and [`crate::bytes::Enumerated`].
+#[derive(Clone, Debug)]
+pub struct Enumerated<I> {
+    iter: I,
+    index: usize,
+}
+
+impl<'t, H, I> Iterator for Enumerated<I>
+where
+    H: Haystack + ?Sized + 't,
+    I: Iterator<Item = Segment<'t, H>>,
+{
+    type Item = (usize, Option<&'t H>, &'t H);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let segment = self.iter.next()?;
+        let index = self.index;
+        self.index += 1;
+        Some((index, segment.delimiter(), segment.full()))
+    }
+}
+
+impl<'t, H, I> FusedIterator for Enumerated<I>
+where
+    H: Haystack + ?Sized + 't,
+    I: FusedIterator<Item = Segment<'t, H>>,
+{
+}
+
+/// The type behind [`crate::Section`] and [`crate::bytes::Section`].
+#[derive(Debug)]
+pub struct Section<'t, H: Haystack + ?Sized> {
+    header: Option<&'t H>,
+    body: &'t H,
+}
+
+impl<'t, H: Haystack + ?Sized> Clone for Section<'t, H> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'t, H: Haystack + ?Sized> Copy for Section<'t, H> {}
+
+impl<'t, H: Haystack + ?Sized + PartialEq> PartialEq for Section<'t, H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.header == other.header && self.body == other.body
+    }
+}
+
+impl<'t, H: Haystack + ?Sized + Eq> Eq for Section<'t, H> {}
+
+impl<'t, H: Haystack + ?Sized> Section<'t, H> {
+    /// The header that begins the section. Only text before the first header
+    /// has none.
+    pub fn header(&self) -> Option<&'t H> {
+        self.header
+    }
+
+    /// The text following the header, up to the next header or the end of
+    /// the text.
+    pub fn body(&self) -> &'t H {
+        self.body
+    }
+}
+
+/// The type behind [`crate::Sections`] and [`crate::bytes::Sections`].
+#[derive(Debug)]
+pub struct Sections<'r, 't, H: Haystack + ?Sized> {
+    inner: SplitInclusiveLeftSegments<'r, 't, H>,
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Clone for Sections<'r, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Sections<'r, 't, H> {
+    pub(crate) fn new(regex: &'r H::Regex, text: &'t H) -> Self {
+        Self {
+            inner: SplitInclusiveLeftSegments::new(regex, text),
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Iterator for Sections<'r, 't, H> {
+    type Item = Section<'t, H>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let segment = self.inner.next()?;
+            let header = segment.delimiter();
+
+            // Text before the first header isn't a section if there isn't
+            // any.
+            if header.is_none() && segment.full().is_empty() && !self.inner.text.is_empty() {
+                continue;
+            }
+            return Some(Section {
+                header,
+                body: segment.body(),
+            });
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> FusedIterator for Sections<'r, 't, H> {}
+
+/// The type behind [`crate::SegmentTree`] and [`crate::bytes::SegmentTree`].
+#[derive(Debug)]
+pub struct SegmentTree<'t, H: Haystack + ?Sized> {
+    text: &'t H,
+    range: Range<usize>,
+    children: Vec<SegmentTree<'t, H>>,
+}
+
+impl<'t, H: Haystack + ?Sized> Clone for SegmentTree<'t, H> {
+    fn clone(&self) -> Self {
+        Self {
+            text: self.text,
+            range: self.range.clone(),
+            children: self.children.clone(),
+        }
+    }
+}
+
+impl<'t, H: Haystack + ?Sized + PartialEq> PartialEq for SegmentTree<'t, H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.text == other.text && self.range == other.range && self.children == other.children
+    }
+}
+
+impl<'t, H: Haystack + ?Sized + Eq> Eq for SegmentTree<'t, H> {}
+
+impl<'t, H: Haystack + ?Sized> SegmentTree<'t, H> {
+    /// The whole substring, including its delimiter.
+    pub fn full(&self) -> &'t H {
+        self.text.slice(self.range())
+    }
+
+    /// The byte range of the substring in the text that was split.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// The substrings this one was split into by the next regular
+    /// expression, which cover all of it. Empty past the last level.
+    pub fn children(&self) -> &[SegmentTree<'t, H>] {
+        &self.children
+    }
+
+    /// Whether this substring is at the last level, so wasn't split further.
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    fn build<R>(text: &'t H, range: Range<usize>, levels: &[R]) -> Self
+    where
+        R: Borrow<H::Regex>,
+    {
+        let children = match levels.split_first() {
+            None => Vec::new(),
+            Some((regex, levels)) => {
+                let segment = text.slice(range.clone());
+                SplitInclusive::new(regex.borrow(), segment)
+                    .map(|s| {
+                        let start = range.start + offset_in(segment, s);
+                        Self::build(text, start..start + s.len(), levels)
+                    })
+                    .collect()
+            }
+        };
+        Self {
+            text,
+            range,
+            children,
+        }
+    }
+}
+
+// The body of `split_tree`, for either flavor.
+pub(crate) fn split_tree<'t, H, R>(text: &'t H, levels: &[R]) -> SegmentTree<'t, H>
+where
+    H: Haystack + ?Sized,
+    R: Borrow<H::Regex>,
+{
+    SegmentTree::build(text, 0..text.len(), levels)
+}
+
+/// The type behind [`crate::SplitRecursive`] and
+/// [`crate::bytes::SplitRecursive`].
+#[derive(Debug)]
+pub struct SplitRecursive<'r, 't, H: Haystack + ?Sized, R> {
+    patterns: &'r [R],
+    max_len: usize,
+
+    // Regions still to be yielded or split, last first, each with the index
+    // of the first pattern that may split it and of the pattern whose match
+    // ends it.
+    stack: Vec<(Range<usize>, usize, Option<usize>)>,
+    text: &'t H,
+}
+
+impl<'r, 't, H: Haystack + ?Sized, R> Clone for SplitRecursive<'r, 't, H, R> {
+    fn clone(&self) -> Self {
+        Self {
+            patterns: self.patterns,
+            max_len: self.max_len,
+            stack: self.stack.clone(),
+            text: self.text,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized, R> SplitRecursive<'r, 't, H, R> {
+    pub(crate) fn new(text: &'t H, patterns: &'r [R], max_len: usize) -> Self {
+        Self {
+            patterns,
+            max_len,
+            stack: vec![(0..text.len(), 0, None)],
+            text,
+        }
+    }
+}
+
+impl<'r, 't, H, R> Iterator for SplitRecursive<'r, 't, H, R>
+where
+    H: Haystack + ?Sized,
+    R: Borrow<H::Regex>,
+{
+    type Item = (&'t H, Option<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (range, level, boundary) = self.stack.pop()?;
+            let region = self.text.slice(range.clone());
+            if region.len() <= self.max_len {
+                return Some((region, boundary));
+            }
+
+            let Some(index) =
+                (level..self.patterns.len()).find(|&i| region.is_match(self.patterns[i].borrow()))
+            else {
+                return Some((region, boundary));
+            };
+
+            // A match at the end of the region leaves an empty piece after
+            // it, which isn't worth yielding.
+            let mut pieces: Vec<_> = SplitInclusive::new(self.patterns[index].borrow(), region)
+                .filter(|s| !s.is_empty())
+                .map(|s| {
+                    let start = range.start + offset_in(region, s);
+                    (start..start + s.len(), index + 1, Some(index))
+                })
+                .collect();
+            if let Some(last) = pieces.last_mut() {
+                last.2 = boundary;
+            }
+            self.stack.extend(pieces.into_iter().rev());
+        }
+    }
+}
+
+impl<'r, 't, H, R> FusedIterator for SplitRecursive<'r, 't, H, R>
+where
+    H: Haystack + ?Sized,
+    R: Borrow<H::Regex>,
+{
+}
+
+/// The type behind [`crate::SetSplitter`] and [`crate::bytes::SetSplitter`].
+#[derive(Debug)]
+pub struct SetSplitter<H: Haystack + ?Sized> {
+    regexes: Vec<H::Regex>,
+}
+
+impl<H: Haystack + ?Sized> Clone for SetSplitter<H> {
+    fn clone(&self) -> Self {
+        Self {
+            regexes: self.regexes.clone(),
+        }
+    }
+}
+
+impl<H: Haystack + ?Sized> SetSplitter<H> {
+    /// Compiles the patterns of `set` one by one. Options the set was built
+    /// with aren't carried over; use inline flags such as `(?i)` in the
+    /// patterns instead.
+    pub fn new(set: &H::RegexSet) -> Result<Self, regex::Error> {
+        let regexes = H::patterns(set)
+            .iter()
+            .map(|pattern| H::compile(pattern))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { regexes })
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of
+    /// any of the patterns, like `split_inclusive`. Each substring is paired
+    /// with the index of the pattern whose match ends it, or `None` for the
+    /// last one. Where matches of several patterns begin at the same place,
+    /// the pattern listed first wins.
+    ///
+    /// This method will *not* copy the text given.
+    pub fn split_inclusive<'s, 't>(&'s self, text: &'t H) -> SetSplitInclusive<'s, 't, H> {
+        SetSplitInclusive {
+            finder: SetMatches::new(&self.regexes, text),
+            last: Some(0),
+            text,
+        }
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of
+    /// any of the patterns, like `split_inclusive_left`. Each substring is
+    /// paired with the index of the pattern whose match begins it, or `None`
+    /// for the first one. Where matches of several patterns begin at the same
+    /// place, the pattern listed first wins.
+    ///
+    /// This method will *not* copy the text given.
+    pub fn split_inclusive_left<'s, 't>(&'s self, text: &'t H) -> SetSplitInclusiveLeft<'s, 't, H> {
+        SetSplitInclusiveLeft {
+            finder: SetMatches::new(&self.regexes, text),
+            last: Some(0),
+            pattern: None,
+            text,
+        }
+    }
+}
+
+/// The type behind [`crate::MultiSplitter`] and
+/// [`crate::bytes::MultiSplitter`].
+#[derive(Debug)]
+pub struct MultiSplitter<H: Haystack + ?Sized> {
+    regexes: Vec<H::Regex>,
+}
+
+impl<H: Haystack + ?Sized> Clone for MultiSplitter<H> {
+    fn clone(&self) -> Self {
+        Self {
+            regexes: self.regexes.clone(),
+        }
+    }
+}
+
+impl<H: Haystack + ?Sized> MultiSplitter<H> {
+    /// Creates a splitter from the regular expressions given, in order of
+    /// precedence. They are cheap to clone, so the splitter keeps its own.
+    pub fn new<R>(regexes: &[R]) -> Self
+    where
+        R: Borrow<H::Regex>,
+    {
+        Self {
+            regexes: regexes.iter().map(|re| re.borrow().clone()).collect(),
+        }
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of
+    /// any of the regular expressions, like `split_inclusive`. Each substring
+    /// is paired with the index of the regular expression whose match ends
+    /// it, or `None` for the last one.
+    ///
+    /// This method will *not* copy the text given.
+    pub fn split_inclusive<'s, 't>(&'s self, text: &'t H) -> SetSplitInclusive<'s, 't, H> {
+        SetSplitInclusive {
+            finder: SetMatches::new(&self.regexes, text).by_precedence(),
+            last: Some(0),
+            text,
+        }
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of
+    /// any of the regular expressions, like `split_inclusive_left`. Each
+    /// substring is paired with the index of the regular expression whose
+    /// match begins it, or `None` for the first one.
+    ///
+    /// This method will *not* copy the text given.
+    pub fn split_inclusive_left<'s, 't>(&'s self, text: &'t H) -> SetSplitInclusiveLeft<'s, 't, H> {
+        SetSplitInclusiveLeft {
+            finder: SetMatches::new(&self.regexes, text).by_precedence(),
+            last: Some(0),
+            pattern: None,
+            text,
+        }
+    }
+}
+
+/// The type behind [`crate::SetSplitInclusive`] and
+/// [`crate::bytes::SetSplitInclusive`].
+#[derive(Debug)]
+pub struct SetSplitInclusive<'s, 't, H: Haystack + ?Sized> {
+    finder: SetMatches<'s, 't, H>,
+    last: Option<usize>,
+    text: &'t H,
+}
+
+impl<'s, 't, H: Haystack + ?Sized> Clone for SetSplitInclusive<'s, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            finder: self.finder.clone(),
+            last: self.last,
+            text: self.text,
+        }
+    }
+}
+
+impl<'s, 't, H: Haystack + ?Sized> Iterator for SetSplitInclusive<'s, 't, H> {
+    type Item = (&'t H, Option<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let last = self.last?;
+        match self.finder.next() {
+            None => {
+                let s = self.text.slice(last..self.text.len());
+                self.last = None;
+                Some((s, None))
+            }
+            Some((index, m)) => {
+                let matched = self.text.slice(last..m.end);
+                self.last = Some(m.end);
+                Some((matched, Some(index)))
+            }
+        }
+    }
+}
+
+impl<'s, 't, H: Haystack + ?Sized> FusedIterator for SetSplitInclusive<'s, 't, H> {}
+
+/// The type behind [`crate::SetSplitInclusiveLeft`] and
+/// [`crate::bytes::SetSplitInclusiveLeft`].
+#[derive(Debug)]
+pub struct SetSplitInclusiveLeft<'s, 't, H: Haystack + ?Sized> {
+    finder: SetMatches<'s, 't, H>,
+    last: Option<usize>,
+
+    // The pattern whose match begins the next substring.
+    pattern: Option<usize>,
+    text: &'t H,
+}
+
+impl<'s, 't, H: Haystack + ?Sized> Clone for SetSplitInclusiveLeft<'s, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            finder: self.finder.clone(),
+            last: self.last,
+            pattern: self.pattern,
+            text: self.text,
+        }
+    }
+}
+
+impl<'s, 't, H: Haystack + ?Sized> Iterator for SetSplitInclusiveLeft<'s, 't, H> {
+    type Item = (&'t H, Option<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let last = self.last?;
+        match self.finder.next() {
+            None => {
+                let s = self.text.slice(last..self.text.len());
+                self.last = None;
+                Some((s, self.pattern))
+            }
+            Some((index, m)) => {
+                let matched = self.text.slice(last..m.start);
+                self.last = Some(m.start);
+                Some((matched, self.pattern.replace(index)))
+            }
+        }
+    }
+}
+
+impl<'s, 't, H: Haystack + ?Sized> FusedIterator for SetSplitInclusiveLeft<'s, 't, H> {}
+
+// Iterates over successive non-overlapping matches of any of several regular
+// expressions, like `find_iter` would for their alternation, tagging each with
+// the index of the one that matched.
+#[derive(Debug)]
+struct SetMatches<'s, 't, H: Haystack + ?Sized> {
+    regexes: &'s [H::Regex],
+
+    // The next match of each regular expression at or after `at`, if any.
+    next: Vec<Option<Range<usize>>>,
+    at: usize,
+    last_end: Option<usize>,
+
+    // Whether a match overlapping one found by an earlier regular expression
+    // gives way to it, rather than the leftmost match winning.
+    by_precedence: bool,
+    text: &'t H,
+}
+
+impl<'s, 't, H: Haystack + ?Sized> Clone for SetMatches<'s, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            regexes: self.regexes,
+            next: self.next.clone(),
+            at: self.at,
+            last_end: self.last_end,
+            by_precedence: self.by_precedence,
+            text: self.text,
+        }
+    }
+}
+
+impl<'s, 't, H: Haystack + ?Sized> SetMatches<'s, 't, H> {
+    fn new(regexes: &'s [H::Regex], text: &'t H) -> Self {
+        let next = regexes
+            .iter()
+            .map(|re| text.find_at(re, 0).map(|m| H::match_range(&m)))
+            .collect();
+        Self {
+            regexes,
+            next,
+            at: 0,
+            last_end: None,
+            by_precedence: false,
+            text,
+        }
+    }
+
+    fn by_precedence(self) -> Self {
+        Self {
+            by_precedence: true,
+            ..self
+        }
+    }
+
+    // Finds the next match of `regex` that `find_iter` would report.
+    fn find_next(&self, regex: &H::Regex) -> Option<Range<usize>> {
+        let mut at = self.at;
+        loop {
+            if at > self.text.len() {
+                return None;
+            }
+            let m = H::match_range(&self.text.find_at(regex, at)?);
+            if m.is_empty() && Some(m.end) == self.last_end {
+                at += self.text.step_at(at);
+                continue;
+            }
+            return Some(m);
+        }
+    }
+}
+
+impl<'s, 't, H: Haystack + ?Sized> Iterator for SetMatches<'s, 't, H> {
+    type Item = (usize, Range<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for i in 0..self.regexes.len() {
+            let stale = self.next[i].as_ref().is_some_and(|m| {
+                m.start < self.at || (m.is_empty() && Some(m.start) == self.last_end)
+            });
+            if stale {
+                self.next[i] = self.find_next(&self.regexes[i]);
+            }
+        }
+
+        let (mut index, mut m) = self
+            .next
+            .iter()
+            .enumerate()
+            .filter_map(|(i, m)| Some((i, m.clone()?)))
+            .min_by_key(|(_, m)| m.start)?;
+        if self.by_precedence {
+            while let Some((i, earlier)) = self.next[..index]
+                .iter()
+                .enumerate()
+                .filter_map(|(i, other)| Some((i, other.clone()?)))
+                .find(|(_, other)| other.start < m.end && m.start < other.end)
+            {
+                index = i;
+                m = earlier;
+            }
+        }
+        self.at = m.end;
+        self.last_end = Some(m.end);
+        Some((index, m))
+    }
+}
+
+// Iterates over successive non-overlapping matches like `find_iter`, but
+// beginning at an offset into the text. Every search runs against the whole
+// text, so anchors and word boundaries see what comes before the offset.
+#[derive(Debug)]
+pub(crate) struct MatchesAt<'r, 't, H: Haystack + ?Sized> {
+    regex: &'r H::Regex,
+    text: &'t H,
+    at: usize,
+    last_end: Option<usize>,
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Clone for MatchesAt<'r, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            regex: self.regex,
+            text: self.text,
+            at: self.at,
+            last_end: self.last_end,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> MatchesAt<'r, 't, H> {
+    pub(crate) fn new(regex: &'r H::Regex, text: &'t H, at: usize) -> Self {
+        Self {
+            regex,
+            text,
+            at,
+            last_end: None,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Iterator for MatchesAt<'r, 't, H> {
+    type Item = H::Match<'t>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.at > self.text.len() {
+                return None;
+            }
+            let m = self.text.find_at(self.regex, self.at)?;
+            let range = H::match_range(&m);
+
+            // An empty match right where the last match ended isn't reported,
+            // same as with `find_iter`; look again one character further on.
+            if range.is_empty() && Some(range.end) == self.last_end {
+                self.at += self.text.step_at(self.at);
+                continue;
+            }
+            self.at = range.end;
+            self.last_end = Some(range.end);
+            return Some(m);
+        }
+    }
+}
+
+// Builds an Aho-Corasick automaton to stand in for `regex` if it is an
+// alternation of plain literals. Builder options such as case insensitivity
+// don't show in the pattern, so this first makes sure the regex matches each
+// literal as written, and nothing but.
+#[cfg(feature = "aho-corasick")]
+fn literal_finder<H: Haystack + ?Sized>(regex: &H::Regex) -> Option<AhoCorasick> {
+    let literals = literal_alternatives(H::pattern(regex))?;
+    for literal in &literals {
+        let m = H::of_str(literal).find_at(regex, 0)?;
+        if H::match_range(&m) != (0..literal.len()) {
+            return None;
+        }
+        let swapped = swap_case(literal);
+        if !literals.contains(&swapped.as_str()) && H::of_str(&swapped).is_match(regex) {
+            return None;
+        }
+    }
+    AhoCorasick::builder()
+        .match_kind(MatchKind::LeftmostFirst)
+        .build(&literals)
+        .ok()
+}
+
+// Builds a memmem searcher to stand in for `regex` if it matches a single
+// literal, checking that builder options don't change what it matches the
+// same way `literal_finder` does.
+#[cfg(feature = "memchr")]
+fn memmem_finder<H: Haystack + ?Sized>(regex: &H::Regex) -> Option<Finder<'static>> {
+    let literal = single_literal(H::pattern(regex))?;
+    let m = H::of_str(&literal).find_at(regex, 0)?;
+    if H::match_range(&m) != (0..literal.len()) {
+        return None;
+    }
+    let swapped = swap_case(&literal);
+    if swapped != literal && H::of_str(&swapped).is_match(regex) {
+        return None;
+    }
+    Some(Finder::new(literal.as_bytes()).into_owned())
+}
+
+// Iterates over the capture groups of successive non-overlapping matches like
+// `captures_iter`, but keeps only a position, so that it can be cloned.
+#[derive(Debug)]
+struct CapturesAt<'r, 't, H: Haystack + ?Sized> {
+    regex: &'r H::Regex,
+    text: &'t H,
+    at: usize,
+    last_end: Option<usize>,
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Clone for CapturesAt<'r, 't, H> {
+    fn clone(&self) -> Self {
+        Self {
+            regex: self.regex,
+            text: self.text,
+            at: self.at,
+            last_end: self.last_end,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> CapturesAt<'r, 't, H> {
+    fn new(regex: &'r H::Regex, text: &'t H) -> Self {
+        Self {
+            regex,
+            text,
+            at: 0,
+            last_end: None,
+        }
+    }
+}
+
+impl<'r, 't, H: Haystack + ?Sized> Iterator for CapturesAt<'r, 't, H> {
+    type Item = H::Captures<'t>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.at > self.text.len() {
+                return None;
+            }
+            let captures = self.text.captures_at(self.regex, self.at)?;
+            let m = H::match_range(&H::group(&captures, 0).unwrap());
+
+            // Same as with `MatchesAt`.
+            if m.is_empty() && Some(m.end) == self.last_end {
+                self.at += self.text.step_at(self.at);
+                continue;
+            }
+            self.at = m.end;
+            self.last_end = Some(m.end);
+            return Some(captures);
+        }
+    }
+}
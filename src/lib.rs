@@ -43,162 +43,2590 @@
 //! ]);
 //! # }
 //! ```
+//! 
+//! ## `Splitter`
+//! 
+//! For anything past the defaults, `Splitter` sets every option in one place: where the
+//! delimiter goes, a limit on the number of substrings, skipping empty ones, and so on.
+//! 
+//! ```rust
+//! # use regex::Regex;
+//! # use crate::regex_split::{Keep, Splitter};
+//! # fn main() {
+//! let re = Regex::new(r"\s*,\s*").unwrap();
+//! let text = "a, b , c , d";
+//! let v: Vec<&str> = Splitter::new(&re).keep(Keep::Neither).limit(3).split(text).collect();
+//! assert_eq!(v, ["a", "b", "c , d"]);
+//! # }
+//! ```
 //!  
-//! Use `regex_split::bytes::RegexSplit` for `regex::bytes::Regex`.
+//! Use `regex_split::bytes::RegexSplit` for `regex::bytes::Regex`,
+//! `regex_split::os_str::RegexSplit` to split an `OsStr` with one, and
+//! `regex_split::stream::RegexSplitRead` to split text as it is read. Bound generic code on
+//! `regex_split::splittable::Splittable` to take either kind of regex.
+
+pub mod bytes;
+pub mod grep;
+pub mod haystack;
+pub mod matcher;
+#[cfg(feature = "memmap2")]
+pub mod mmap;
+pub mod os_str;
+pub mod parallel;
+pub mod splittable;
+pub mod stream;
+#[cfg(feature = "utf16")]
+pub mod utf16;
+
+use std::borrow::{Borrow, Cow};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::iter::FusedIterator;
+use std::ops::Range;
+use std::sync::Arc;
+use std::{error, fmt};
+
+use regex::{Match, Regex, Replacer};
+#[cfg(feature = "memchr")]
+use regex_syntax::hir::{HirKind, Literal};
+
+use crate::haystack::MatchesAt;
+
+// Lets the macros name the regex crate whatever the caller depends on.
+#[doc(hidden)]
+pub use regex as __regex;
+
+/// What to do with the empty substring left at the end of the text when the
+/// text ends with a match.
+///
+/// An empty text is not affected by this policy: splitting it still yields a
+/// single empty substring, so that an empty input remains distinguishable
+/// from one that merely ends with a delimiter.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TrailingEmpty {
+    /// Yield the empty substring. This is the default.
+    #[default]
+    Keep,
+    /// Skip the empty substring, like `split_terminator` in std.
+    Drop,
+}
+
+/// What to do when the text being split is empty.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum EmptyText {
+    /// Yield a single empty substring. This is the default.
+    #[default]
+    Keep,
+    /// Yield nothing at all.
+    Drop,
+}
+
+/// What to do with zero-width matches, such as those produced by `\b` or by a
+/// pattern like `,*` that may match nothing at all.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ZeroWidth {
+    /// Split at zero-width matches like any other match. This is the
+    /// default.
+    #[default]
+    Split,
+    /// Ignore zero-width matches entirely.
+    Skip,
+    /// Stop at the first zero-width match. The iterator's `error` method
+    /// then reports where it was found.
+    Error,
+}
+
+/// The error reported by a split iterator configured with
+/// [`ZeroWidth::Error`] when it finds a zero-width match.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ZeroWidthError {
+    offset: usize,
+}
+
+impl ZeroWidthError {
+    /// The byte offset of the zero-width match.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl fmt::Display for ZeroWidthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "zero-width match at offset {}", self.offset)
+    }
+}
+
+impl error::Error for ZeroWidthError {}
+
+/// The error that ends a [`TrySplit`] or a
+/// [`bytes::TrySplit`](crate::bytes::TrySplit).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SplitError<E> {
+    /// A zero-width match, found by a splitter configured with
+    /// [`ZeroWidth::Error`].
+    ZeroWidth(ZeroWidthError),
+    /// The search for the next delimiter failed.
+    Search(E),
+}
+
+impl<E: fmt::Display> fmt::Display for SplitError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SplitError::ZeroWidth(e) => e.fmt(f),
+            SplitError::Search(e) => write!(f, "search failed: {}", e),
+        }
+    }
+}
+
+impl<E: error::Error + 'static> error::Error for SplitError<E> {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            SplitError::ZeroWidth(e) => Some(e),
+            SplitError::Search(e) => Some(e),
+        }
+    }
+}
+
+/// What to do with the empty substring `split_inclusive_left` yields first
+/// when the text begins with a match.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LeadingEmpty {
+    /// Yield the empty substring. This is the default.
+    #[default]
+    Keep,
+    /// Skip the empty substring, so that the first substring yielded begins
+    /// with the match.
+    Drop,
+}
+
+/// Where the substrings yielded by a [`Splitter`] keep the matches that
+/// delimit them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Keep {
+    /// At the right end of the substring before the match, like
+    /// `split_inclusive`. This is the default.
+    #[default]
+    Right,
+    /// At the left end of the substring after the match, like
+    /// `split_inclusive_left`.
+    Left,
+    /// At both ends, like `split_inclusive_both`.
+    Both,
+    /// Nowhere, like `Regex::split`.
+    Neither,
+}
+
+/// Which of the substrings on either side of a match receives it, as decided
+/// per match by the closure given to `split_inclusive_by`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Side {
+    /// The substring before the match, which ends with it.
+    Left,
+    /// The substring after the match, which begins with it.
+    Right,
+    /// Both substrings, which then overlap.
+    Both,
+    /// Neither substring; the match is dropped.
+    Discard,
+}
+
+/// Where a substring begins in the text being split, as a byte offset and as
+/// a line and column, both counted from one. Lines end at `\n`. Columns count
+/// characters when splitting a `str` and bytes when splitting a `[u8]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Position {
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Position {
+    /// The byte offset.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The line number, counted from one.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The column number, counted from one.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+}
+
+/// A summary of the substrings a split yields, gathered in one pass by
+/// `stats` without keeping any of them: how many there are, their lengths in
+/// bytes and how many bytes of the text went to delimiters.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SegmentStats {
+    count: usize,
+    min_len: usize,
+    max_len: usize,
+    total_len: usize,
+    empty: usize,
+    delimiter_bytes: usize,
+}
+
+impl SegmentStats {
+    /// The number of substrings.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The length of the shortest substring, including its delimiter.
+    pub fn min_len(&self) -> usize {
+        self.min_len
+    }
+
+    /// The length of the longest substring, including its delimiter.
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// The mean length of the substrings, or zero if there are none.
+    pub fn mean_len(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.total_len as f64 / self.count as f64
+        }
+    }
+
+    /// The number of empty substrings.
+    pub fn empty(&self) -> usize {
+        self.empty
+    }
+
+    /// The number of bytes taken up by delimiting matches.
+    pub fn delimiter_bytes(&self) -> usize {
+        self.delimiter_bytes
+    }
+
+    // Counts a substring of `len` bytes, `delimiter` of which belong to its
+    // delimiting match.
+    fn add(&mut self, len: usize, delimiter: usize) {
+        if self.count == 0 || len < self.min_len {
+            self.min_len = len;
+        }
+        self.max_len = self.max_len.max(len);
+        self.total_len += len;
+        self.count += 1;
+        self.empty += usize::from(len == 0);
+        self.delimiter_bytes += delimiter;
+    }
+}
+
+// The options set on a `Splitter`, shared by the str and bytes flavors.
+#[derive(Clone, Copy, Debug, Default)]
+struct Options {
+    keep: Keep,
+    limit: Option<usize>,
+    skip_empty: bool,
+    trailing_empty: TrailingEmpty,
+    leading_empty: LeadingEmpty,
+    empty_text: EmptyText,
+    zero_width: ZeroWidth,
+    coalesce: bool,
+    skip_escaped: bool,
+}
+
+// Returns the alternatives of `pattern` if it is nothing but an alternation of
+// non-empty plain literals, which Aho-Corasick can find faster than a regex.
+#[cfg(feature = "aho-corasick")]
+fn literal_alternatives(pattern: &str) -> Option<Vec<&str>> {
+    let literals: Vec<&str> = pattern.split('|').collect();
+    let plain = literals
+        .iter()
+        .all(|literal| !literal.is_empty() && regex::escape(literal) == *literal);
+    plain.then_some(literals)
+}
+
+// Returns the string `pattern` matches if it matches nothing but that one
+// non-empty literal, such as `\n---\n`, which memmem can find faster than a
+// regex.
+#[cfg(feature = "memchr")]
+fn single_literal(pattern: &str) -> Option<String> {
+    let hir = regex_syntax::parse(pattern).ok()?;
+    match hir.kind() {
+        HirKind::Literal(Literal(bytes)) if !bytes.is_empty() => {
+            String::from_utf8(bytes.to_vec()).ok()
+        }
+        _ => None,
+    }
+}
+
+#[cfg(any(feature = "aho-corasick", feature = "memchr"))]
+fn swap_case(s: &str) -> String {
+    s.chars()
+        .flat_map(|c| {
+            if c.is_lowercase() {
+                c.to_uppercase().collect::<Vec<_>>()
+            } else {
+                c.to_lowercase().collect()
+            }
+        })
+        .collect()
+}
+
+/// Split methods on a regex. These search with the regex itself: the
+/// `aho-corasick` and `memchr` fast paths are only taken by a [`Splitter`]
+/// made with `Splitter::new`, which works out whether the pattern allows
+/// them once and keeps the answer for every split after.
+pub trait RegexSplit {
+    fn split_inclusive<'r, 't>(&'r self, text: &'t str) -> SplitInclusive<'r, 't>;
+    fn split_inclusive_left<'r, 't>(&'r self, text: &'t str) -> SplitInclusiveLeft<'r, 't>;
+    fn rsplitn_inclusive<'r, 't>(&'r self, text: &'t str, n: usize) -> RSplitNInclusive<'r, 't>;
+    fn split_inclusive_terminator<'r, 't>(&'r self, text: &'t str) -> SplitInclusive<'r, 't>;
+    fn split_inclusive_left_terminator<'r, 't>(
+        &'r self,
+        text: &'t str,
+    ) -> SplitInclusiveLeft<'r, 't>;
+    fn split_inclusive_at<'r, 't>(&'r self, text: &'t str, start: usize) -> SplitInclusive<'r, 't>;
+    fn split_once_inclusive<'t>(&self, text: &'t str) -> Option<(&'t str, &'t str)>;
+    fn split_once_inclusive_left<'t>(&self, text: &'t str) -> Option<(&'t str, &'t str)>;
+    fn rsplit_once_inclusive<'t>(&self, text: &'t str) -> Option<(&'t str, &'t str)>;
+    fn rsplit_once_inclusive_left<'t>(&self, text: &'t str) -> Option<(&'t str, &'t str)>;
+    fn partition_at_nth_match<'t>(&self, text: &'t str, n: usize) -> Option<(&'t str, &'t str)>;
+    fn partition_at_nth_match_left<'t>(
+        &self,
+        text: &'t str,
+        n: usize,
+    ) -> Option<(&'t str, &'t str)>;
+    fn split_inclusive_rev<'r, 't>(&'r self, text: &'t str) -> SplitInclusiveRev<'r, 't>;
+    fn split_inclusive_left_rev<'r, 't>(&'r self, text: &'t str) -> SplitInclusiveLeftRev<'r, 't>;
+    fn split_inclusive_both<'r, 't>(&'r self, text: &'t str) -> SplitInclusiveBoth<'r, 't>;
+    fn split_inclusive_by<'r, 't, F>(
+        &'r self,
+        text: &'t str,
+        side: F,
+    ) -> SplitInclusiveBy<'r, 't, F>
+    where
+        F: FnMut(&Match<'t>) -> Side;
+    fn split_on_group<'r, 't>(&'r self, text: &'t str, group: usize) -> SplitOnGroup<'r, 't>;
+    fn split_on_name<'r, 't>(&'r self, text: &'t str, name: &str) -> SplitOnGroup<'r, 't>;
+    fn split_around<'r, 't>(&'r self, text: &'t str) -> SplitAround<'r, 't>;
+    fn split_inclusive_captures<'r, 't>(&'r self, text: &'t str) -> SplitInclusiveCaptures<'r, 't>;
+    fn group_by_capture<'r, 't>(&'r self, text: &'t str, name: &str) -> GroupByCapture<'r, 't>;
+    fn split_with_delimiters<'r, 't>(&'r self, text: &'t str) -> SplitWithDelimiters<'r, 't>;
+    fn matches_with_context<'r, 't>(
+        &'r self,
+        text: &'t str,
+        n: usize,
+    ) -> MatchesWithContext<'r, 't>;
+    fn split_between<'r, 't>(&'r self, text: &'t str, end: &'r Regex) -> SplitBetween<'r, 't>;
+    fn split_inclusive_outside<'r, 't>(
+        &'r self,
+        text: &'t str,
+        mask: &'r Regex,
+    ) -> SplitInclusiveOutside<'r, 't>;
+    fn split_inclusive_tagged<'r, 't>(&'r self, text: &'t str) -> SplitInclusiveTagged<'r, 't>;
+    fn chunks_by_matches<'r, 't>(&'r self, text: &'t str, n: usize) -> ChunksByMatches<'r, 't>;
+    fn split_parts<'r, 't>(&'r self, text: &'t str) -> SplitParts<'r, 't>;
+    fn split_inclusive_indices<'r, 't>(&'r self, text: &'t str) -> SplitInclusiveIndices<'r, 't>;
+    fn split_inclusive_left_indices<'r, 't>(
+        &'r self,
+        text: &'t str,
+    ) -> SplitInclusiveLeftIndices<'r, 't>;
+    fn split_inclusive_ranges<'r, 't>(&'r self, text: &'t str) -> SplitInclusiveRanges<'r, 't>;
+    fn split_inclusive_left_ranges<'r, 't>(
+        &'r self,
+        text: &'t str,
+    ) -> SplitInclusiveLeftRanges<'r, 't>;
+    fn split_inclusive_segments<'r, 't>(&'r self, text: &'t str) -> SplitInclusiveSegments<'r, 't>;
+    fn split_inclusive_left_segments<'r, 't>(
+        &'r self,
+        text: &'t str,
+    ) -> SplitInclusiveLeftSegments<'r, 't>;
+    fn sections<'r, 't>(&'r self, text: &'t str) -> Sections<'r, 't>;
+    fn count_segments(&self, text: &str) -> usize;
+    fn stats(&self, text: &str) -> SegmentStats;
+    fn map_segments<'t, F>(&self, text: &'t str, f: F) -> Cow<'t, str>
+    where
+        F: FnMut(&'t str) -> Cow<'t, str>;
+    fn replace_within_segments<'t, R>(&self, text: &'t str, re: &Regex, rep: R) -> Cow<'t, str>
+    where
+        R: Replacer;
+    fn split_inclusive_owned(&self, text: String) -> SplitInclusiveOwned;
+    fn split_inclusive_left_owned(&self, text: String) -> SplitInclusiveLeftOwned;
+    fn split_inclusive_arc(&self, text: Arc<str>) -> SplitInclusiveArc;
+    fn split_inclusive_left_arc(&self, text: Arc<str>) -> SplitInclusiveLeftArc;
+    fn split_inclusive_cow<'r, 'a>(&'r self, text: Cow<'a, str>) -> SplitInclusiveCow<'r, 'a>;
+    fn split_inclusive_left_cow<'r, 'a>(
+        &'r self,
+        text: Cow<'a, str>,
+    ) -> SplitInclusiveLeftCow<'r, 'a>;
+}
+
+pub trait StrSplitExt {
+    fn split_inclusive_re<'r, 't>(&'t self, re: &'r Regex) -> SplitInclusive<'r, 't>;
+    fn split_inclusive_left_re<'r, 't>(&'t self, re: &'r Regex) -> SplitInclusiveLeft<'r, 't>;
+    fn rsplitn_inclusive_re<'r, 't>(&'t self, re: &'r Regex, n: usize) -> RSplitNInclusive<'r, 't>;
+}
+
+/// Splits text with a pattern that's compiled the first time the macro is
+/// reached and kept for the life of the program, the same as calling
+/// `split_inclusive` on that regex.
+///
+/// # Panics
+///
+/// Panics if the pattern doesn't compile.
+///
+/// # Example
+///
+/// ```rust
+/// # use regex_split::split_inclusive;
+/// # fn main() {
+/// let lines: Vec<&str> = split_inclusive!(r"\r?\n", "one\ntwo\r\nthree").collect();
+/// assert_eq!(lines, ["one\n", "two\r\n", "three"]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! split_inclusive {
+    ($pattern:expr, $text:expr) => {{
+        static REGEX: ::std::sync::OnceLock<$crate::__regex::Regex> = ::std::sync::OnceLock::new();
+        let regex = REGEX.get_or_init(|| $crate::__regex::Regex::new($pattern).unwrap());
+        $crate::RegexSplit::split_inclusive(regex, $text)
+    }};
+}
+
+/// Like [`split_inclusive!`], but the same as calling `split_inclusive_left`
+/// on the regex.
+///
+/// # Panics
+///
+/// Panics if the pattern doesn't compile.
+///
+/// # Example
+///
+/// ```rust
+/// # use regex_split::split_inclusive_left;
+/// # fn main() {
+/// let items: Vec<&str> = split_inclusive_left!(r"(?m)^-", "fruits:\n-apple\n-pear").collect();
+/// assert_eq!(items, ["fruits:\n", "-apple\n", "-pear"]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! split_inclusive_left {
+    ($pattern:expr, $text:expr) => {{
+        static REGEX: ::std::sync::OnceLock<$crate::__regex::Regex> = ::std::sync::OnceLock::new();
+        let regex = REGEX.get_or_init(|| $crate::__regex::Regex::new($pattern).unwrap());
+        $crate::RegexSplit::split_inclusive_left(regex, $text)
+    }};
+}
+
+// How many compiled patterns each thread keeps for the pattern-string
+// functions.
+const CACHE_CAPACITY: usize = 16;
+
+thread_local! {
+    // Most recently used first.
+    static CACHE: RefCell<VecDeque<(String, Regex)>> = const { RefCell::new(VecDeque::new()) };
+}
+
+// Compiles `pattern`, or finds it among the patterns this thread compiled
+// recently. A `Regex` shares its compiled program between clones, so handing
+// out a clone is cheap.
+fn cached_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if let Some(i) = cache.iter().position(|(p, _)| p == pattern) {
+            let entry = cache.remove(i).unwrap();
+            let regex = entry.1.clone();
+            cache.push_front(entry);
+            return Ok(regex);
+        }
+
+        let regex = Regex::new(pattern)?;
+        if cache.len() == CACHE_CAPACITY {
+            cache.pop_back();
+        }
+        cache.push_front((pattern.to_owned(), regex.clone()));
+        Ok(regex)
+    })
+}
+
+/// Splits `text` on matches of `pattern`, with each match included at the
+/// end of the substring before it, and collects the substrings.
+///
+/// The pattern is compiled on first use and kept in a small per-thread cache,
+/// so calling this in a loop with the same few patterns doesn't compile them
+/// again each time.
+///
+/// # Errors
+///
+/// Returns an error if the pattern doesn't compile.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), regex::Error> {
+/// let v = regex_split::split_inclusive(r"\r?\n", "one\ntwo\r\nthree")?;
+/// assert_eq!(v, ["one\n", "two\r\n", "three"]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn split_inclusive<'t>(pattern: &str, text: &'t str) -> Result<Vec<&'t str>, regex::Error> {
+    Ok(cached_regex(pattern)?.split_inclusive(text).collect())
+}
+
+/// Splits `text` on matches of `pattern`, with each match included at the
+/// start of the substring after it, and collects the substrings. The pattern
+/// is cached as with [`split_inclusive`].
+///
+/// # Errors
+///
+/// Returns an error if the pattern doesn't compile.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), regex::Error> {
+/// let v = regex_split::split_inclusive_left(r"(?m)^-", "fruits:\n-apple\n-pear")?;
+/// assert_eq!(v, ["fruits:\n", "-apple\n", "-pear"]);
+/// assert!(regex_split::split_inclusive_left(r"(", "text").is_err());
+/// # Ok(())
+/// # }
+/// ```
+pub fn split_inclusive_left<'t>(
+    pattern: &str,
+    text: &'t str,
+) -> Result<Vec<&'t str>, regex::Error> {
+    Ok(cached_regex(pattern)?.split_inclusive_left(text).collect())
+}
+
+/// A regular expression together with options for splitting text with it.
+/// This is the most flexible way to split; the methods of [`RegexSplit`] are
+/// shortcuts for common configurations.
+///
+/// # Example
+///
+/// ```rust
+/// # use regex::Regex;
+/// # use crate::regex_split::{Keep, Splitter};
+/// # fn main() {
+/// let re = Regex::new(r"\n").unwrap();
+/// let text = "one\n\ntwo\nthree\nfour";
+/// let v: Vec<&str> = Splitter::new(&re)
+///     .keep(Keep::Left)
+///     .skip_empty(true)
+///     .limit(3)
+///     .split(text)
+///     .collect();
+/// assert_eq!(v, ["one", "\n", "\ntwo\nthree\nfour"]);
+/// # }
+/// ```
+pub type Splitter<'r, M = Regex> = haystack::Splitter<'r, str, M>;
+
+/// Yields the substrings of a text delimited by regular expression matches,
+/// as configured by a [`Splitter`].
+///
+/// Iterating from the back with `next_back` or `rev` works too, but since
+/// the regex crate can't search backwards, the first call to `next_back`
+/// splits the rest of the text up front and buffers the substrings.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type Split<'r, 't, M = Regex> = haystack::Split<'r, 't, str, M>;
+
+/// Yields the substrings of a text as `Ok`, as configured by a
+/// [`Splitter`], for a matcher whose searches can fail. Made with
+/// [`Splitter::try_split`](Splitter#method.try_split).
+///
+/// Where a [`Split`] stops early and leaves its `error` method to report
+/// why, this yields the error as an `Err` and then ends. When a search
+/// fails, the substring it was looking for the end of isn't yielded, since
+/// where it ends isn't known.
+///
+/// # Example
+///
+/// ```rust
+/// # use regex::Regex;
+/// # use crate::regex_split::{SplitError, Splitter, ZeroWidth};
+/// # fn main() {
+/// let re = Regex::new(r",|$").unwrap();
+/// let v: Vec<_> = Splitter::new(&re)
+///     .zero_width(ZeroWidth::Error)
+///     .try_split("a,b")
+///     .collect();
+/// assert_eq!(v.len(), 2);
+/// assert_eq!(v[0], Ok("a,"));
+/// assert!(matches!(v[1], Err(SplitError::ZeroWidth(e)) if e.offset() == 3));
+/// # }
+/// ```
+pub type TrySplit<'r, 't, M = Regex> = haystack::TrySplit<'r, 't, str, M>;
+
+/// Yields all substrings delimited by a regular expression match inclusive of
+/// the match.
+///
+/// Iterating from the back with `next_back` or `rev` works too, but since
+/// the regex crate can't search backwards, the first call to `next_back`
+/// splits the rest of the text up front and buffers the substrings.
+///
+/// ```rust
+/// # use regex::Regex;
+/// # use crate::regex_split::RegexSplit;
+/// # fn main() {
+/// let re = Regex::new(r"\n").unwrap();
+/// let text = "one\ntwo\nthree";
+/// let v: Vec<&str> = re.split_inclusive(text).rev().take(2).collect();
+/// assert_eq!(v, ["three", "two\n"]);
+/// # }
+/// ```
+///
+/// `count`, `nth` and `last` run the search without slicing out the
+/// substrings they pass over, and `fold` (and so `for_each`) drives it in a
+/// single loop.
+///
+/// ```rust
+/// # use regex::Regex;
+/// # use crate::regex_split::RegexSplit;
+/// # fn main() {
+/// let re = Regex::new(r"\n").unwrap();
+/// let text = "one\ntwo\nthree\nfour";
+/// assert_eq!(re.split_inclusive(text).count(), 4);
+/// assert_eq!(re.split_inclusive(text).fold(0, |n, s| n + s.len()), text.len());
+/// assert_eq!(re.split_inclusive(text).nth(1), Some("two\n"));
+/// assert_eq!(re.split_inclusive(text).last(), Some("four"));
+/// # }
+/// ```
+///
+/// The iterator is cheap to clone, since it only holds on to its position in
+/// the text, so a copy can be taken to look ahead without losing your place.
+///
+/// ```rust
+/// # use regex::Regex;
+/// # use crate::regex_split::RegexSplit;
+/// # fn main() {
+/// let re = Regex::new(",").unwrap();
+/// let mut iter = re.split_inclusive("a,b,c");
+/// iter.next();
+/// assert_eq!(iter.clone().count(), 2);
+/// let v: Vec<_> = iter.collect();
+/// assert_eq!(v, ["b,", "c"]);
+/// # }
+/// ```
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitInclusive<'r, 't> = haystack::SplitInclusive<'r, 't, str>;
+
+/// Yields all substrings delimited by a regular expression match inclusive of
+/// the match.
+///
+/// Iterating from the back with `next_back` or `rev` works too, but since
+/// the regex crate can't search backwards, the first call to `next_back`
+/// splits the rest of the text up front and buffers the substrings.
+///
+/// ```rust
+/// # use regex::Regex;
+/// # use crate::regex_split::RegexSplit;
+/// # fn main() {
+/// let re = Regex::new(r"\n").unwrap();
+/// let text = "one\ntwo\nthree";
+/// let v: Vec<&str> = re.split_inclusive_left(text).rev().take(2).collect();
+/// assert_eq!(v, ["\nthree", "\ntwo"]);
+/// # }
+/// ```
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitInclusiveLeft<'r, 't> = haystack::SplitInclusiveLeft<'r, 't, str>;
+
+/// Yields at most `n` substrings delimited by a regular expression match
+/// inclusive of the match, starting from the end of the text. The last
+/// substring yielded holds whatever remains at the front of the text.
+///
+/// The regex crate can't search backwards, so the first call to `next` scans
+/// the text once, remembering only the positions of the last `n - 1` matches.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type RSplitNInclusive<'r, 't> = haystack::RSplitNInclusive<'r, 't, str>;
+
+/// Yields all substrings delimited by a regular expression match inclusive of
+/// the match, starting from the end of the text.
+///
+/// The regex crate can't search backwards, so the first call to `next` scans
+/// the text once, recording the position of every match.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitInclusiveRev<'r, 't> = haystack::SplitInclusiveRev<'r, 't, str>;
+
+/// Yields all substrings delimited by a regular expression match inclusive of
+/// the match, starting from the end of the text, with the match at the front
+/// of each substring.
+///
+/// The regex crate can't search backwards, so the first call to `next` scans
+/// the text once, recording the position of every match.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitInclusiveLeftRev<'r, 't> = haystack::SplitInclusiveLeftRev<'r, 't, str>;
+
+/// Yields all substrings delimited by a regular expression match, with each
+/// match included both at the end of the substring before it and at the start
+/// of the substring after it. Neighboring substrings therefore overlap.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitInclusiveBoth<'r, 't> = haystack::SplitInclusiveBoth<'r, 't, str>;
+
+/// Yields all substrings of an owned text delimited by a regular expression
+/// match inclusive of the match, as owned copies. The iterator owns the text
+/// and a clone of the regular expression, so it borrows nothing and can be
+/// returned from a function or kept around.
+pub type SplitInclusiveOwned = haystack::SplitInclusiveOwned<str>;
+
+/// Yields all substrings of an owned text delimited by a regular expression
+/// match, with the match at the front of each substring, as owned copies.
+/// Like [`SplitInclusiveOwned`], it borrows nothing.
+pub type SplitInclusiveLeftOwned = haystack::SplitInclusiveLeftOwned<str>;
+
+/// A substring of a shared text, which holds on to the text rather than
+/// borrowing it. Cloning one is cheap and copies none of the text.
+pub type ArcSegment = haystack::ArcSegment<str>;
+
+impl ArcSegment {
+    /// Returns the substring.
+    pub fn as_str(&self) -> &str {
+        self
+    }
+}
+
+/// Yields all substrings of a shared text delimited by a regular expression
+/// match inclusive of the match, as [`ArcSegment`]s sharing the text. Like
+/// [`SplitInclusiveOwned`], it borrows nothing, but it copies none of the
+/// text either.
+pub type SplitInclusiveArc = haystack::SplitInclusiveArc<str>;
+
+/// Yields all substrings of a shared text delimited by a regular expression
+/// match, with the match at the front of each substring, as [`ArcSegment`]s
+/// sharing the text.
+pub type SplitInclusiveLeftArc = haystack::SplitInclusiveLeftArc<str>;
+
+/// Yields all substrings of a text given as a `Cow` delimited by a regular
+/// expression match inclusive of the match. Substrings of a borrowed text
+/// are borrowed too; those of an owned text are owned copies.
+pub type SplitInclusiveCow<'r, 'a> = haystack::SplitInclusiveCow<'r, 'a, str>;
+
+/// Yields all substrings of a text given as a `Cow` delimited by a regular
+/// expression match, with the match at the front of each substring,
+/// borrowed or owned as the text is.
+pub type SplitInclusiveLeftCow<'r, 'a> = haystack::SplitInclusiveLeftCow<'r, 'a, str>;
+
+/// Yields all substrings delimited by a regular expression match, with each
+/// match given to the substring before it, the one after it, both or neither,
+/// as decided by a closure.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitInclusiveBy<'r, 't, F> = haystack::SplitInclusiveBy<'r, 't, str, F>;
+
+/// Yields all substrings delimited by one capture group of a regular
+/// expression's matches, with the group's text included at the end of the
+/// substring. The rest of each match is left in the substrings around it.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitOnGroup<'r, 't> = haystack::SplitOnGroup<'r, 't, str>;
+
+/// Yields all substrings delimited by a regular expression match, with the
+/// match's `left` group included at the end of the substring before it and
+/// its `right` group included at the start of the substring after it.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitAround<'r, 't> = haystack::SplitAround<'r, 't, str>;
+
+/// Yields all substrings delimited by a regular expression match, with the
+/// match included at the end of each substring, each paired with the capture
+/// groups of that match. The final substring is paired with `None`.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitInclusiveCaptures<'r, 't> = haystack::SplitInclusiveCaptures<'r, 't, str>;
+
+/// Yields all substrings delimited by a regular expression match, with the
+/// match included at the end of each substring, each paired with the name of
+/// the named group that took part in that match.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitInclusiveTagged<'r, 't> = haystack::SplitInclusiveTagged<'r, 't, str>;
+
+/// Yields the sections of a text introduced by regular expression matches,
+/// each paired with the text one capture group of the introducing match
+/// captured. Any text before the first match is paired with `None`.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type GroupByCapture<'r, 't> = haystack::GroupByCapture<'r, 't, str>;
+
+/// Yields the regions of a text that lie between a match of one regular
+/// expression and the next match of another, skipping the text outside them.
+///
+/// `'r` is the lifetime of the compiled regular expressions and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitBetween<'r, 't> = haystack::SplitBetween<'r, 't, str>;
+
+/// Yields all substrings delimited by a regular expression match, with the
+/// match included at the end of the substring, except that matches overlapping
+/// any region matched by a second, masking regular expression don't split.
+///
+/// `'r` is the lifetime of the compiled regular expressions and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitInclusiveOutside<'r, 't> = haystack::SplitInclusiveOutside<'r, 't, str>;
+
+/// Yields contiguous slices of a text that each run through `n` matches of a
+/// regular expression, with the last match included at the end of the slice.
+/// The final slice holds whatever is left and may have fewer matches.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type ChunksByMatches<'r, 't> = haystack::ChunksByMatches<'r, 't, str>;
+
+/// Yields slices of a text that each cover a run of consecutive substrings
+/// from a split, with neighboring runs sharing some substrings. Built by the
+/// `windows` method of the split iterators.
+///
+/// `'t` is the lifetime of the byte string being split and `I` is the split
+/// iterator.
+pub type Windows<'t, I> = haystack::Windows<'t, str, I>;
+
+/// Yields the substrings from a split, each paired with the [`Position`]
+/// where it begins. Built by the `located` method of the split iterators.
+///
+/// `'t` is the lifetime of the byte string being split and `I` is the split
+/// iterator.
+pub type Located<'t, I> = haystack::Located<'t, str, I>;
+
+/// Yields all substrings delimited by a regular expression match, exclusive of
+/// the match, each paired with the match that ended it. The final substring
+/// is paired with `None`.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitWithDelimiters<'r, 't> = haystack::SplitWithDelimiters<'r, 't, str>;
+
+/// Yields each match of a regular expression in a slice of the text with up
+/// to some number of bytes of context on either side, short of splitting a
+/// character, paired with the range of the match within the slice.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the string being searched.
+pub type MatchesWithContext<'r, 't> = haystack::MatchesWithContext<'r, 't, str>;
+
+/// A piece of the text yielded by `split_parts`: either a run of text between
+/// matches or a match of the regular expression.
+pub type Part<'t> = haystack::Part<'t, str>;
+
+impl<'t> Part<'t> {
+    /// Returns the text this part covers, whichever kind of part it is.
+    pub fn as_str(&self) -> &'t str {
+        self.text()
+    }
+}
+
+/// Yields the text between matches and the matches themselves, in order, so
+/// that every byte of the text is covered by exactly one part.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitParts<'r, 't> = haystack::SplitParts<'r, 't, str>;
+
+/// Yields the same substrings as [`SplitInclusive`], each paired with its byte
+/// offset in the text being split.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitInclusiveIndices<'r, 't> = haystack::SplitInclusiveIndices<'r, 't, str>;
+
+/// Yields the same substrings as [`SplitInclusiveLeft`], each paired with its
+/// byte offset in the text being split.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitInclusiveLeftIndices<'r, 't> = haystack::SplitInclusiveLeftIndices<'r, 't, str>;
+
+/// A substring yielded by `split_inclusive_segments` or
+/// `split_inclusive_left_segments`, which can be taken apart into the match
+/// that delimits it and the rest of its text, its body.
+pub type Segment<'t> = haystack::Segment<'t, str>;
+
+/// Yields the same substrings as [`SplitInclusive`] as [`Segment`]s, which
+/// keep track of where the delimiting match is.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitInclusiveSegments<'r, 't> = haystack::SplitInclusiveSegments<'r, 't, str>;
+
+impl<'r, 't> SplitInclusiveSegments<'r, 't> {
+    /// Adapts the iterator to yield each substring as an owned
+    /// [`SegmentRecord`], ready to be serialized. Only available with the
+    /// `serde` feature.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::{RegexSplit, SegmentRecord};
+    /// # fn main() {
+    /// let re = Regex::new(r",").unwrap();
+    /// let records: Vec<SegmentRecord> = re.split_inclusive_segments("a,bc").records().collect();
+    /// assert_eq!(
+    ///     serde_json::to_string(&records).unwrap(),
+    ///     r#"[{"index":0,"start":0,"end":2,"delimiter":",","text":"a,"},"#.to_owned()
+    ///         + r#"{"index":1,"start":2,"end":4,"delimiter":null,"text":"bc"}]"#,
+    /// );
+    /// # }
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn records(self) -> Records<Self> {
+        Records {
+            iter: self,
+            index: 0,
+        }
+    }
+}
+
+/// Yields the same substrings as [`SplitInclusiveLeft`] as [`Segment`]s,
+/// which keep track of where the delimiting match is.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitInclusiveLeftSegments<'r, 't> = haystack::SplitInclusiveLeftSegments<'r, 't, str>;
+
+impl<'r, 't> SplitInclusiveLeftSegments<'r, 't> {
+    /// Adapts the iterator to yield each substring as an owned
+    /// [`SegmentRecord`], ready to be serialized. Only available with the
+    /// `serde` feature.
+    #[cfg(feature = "serde")]
+    pub fn records(self) -> Records<Self> {
+        Records {
+            iter: self,
+            index: 0,
+        }
+    }
+}
+
+/// Yields the substrings from a split into [`Segment`]s, each with its index
+/// and delimiting match. Built by the `enumerated` method of the segment
+/// iterators.
+pub type Enumerated<I> = haystack::Enumerated<I>;
+
+/// An owned record of a substring from a split: its index, its byte range in
+/// the text, the match delimiting it and its whole text. Serializes with
+/// `serde` for handing segmentation results to other tools. Only available
+/// with the `serde` feature.
+#[cfg(feature = "serde")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct SegmentRecord {
+    /// The index of the substring, counting from zero.
+    pub index: usize,
+    /// The byte offset at which the substring starts.
+    pub start: usize,
+    /// The byte offset at which the substring ends.
+    pub end: usize,
+    /// The match delimiting the substring, if any.
+    pub delimiter: Option<String>,
+    /// The whole substring, including its delimiter.
+    pub text: String,
+}
+
+/// Yields the substrings from a split as [`SegmentRecord`]s. Built by the
+/// `records` method of the segment iterators. Only available with the
+/// `serde` feature.
+#[cfg(feature = "serde")]
+#[derive(Clone, Debug)]
+pub struct Records<I> {
+    iter: I,
+    index: usize,
+}
+
+#[cfg(feature = "serde")]
+impl<'t, I> Iterator for Records<I>
+where
+    I: Iterator<Item = Segment<'t>>,
+{
+    type Item = SegmentRecord;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let segment = self.iter.next()?;
+        let index = self.index;
+        self.index += 1;
+        Some(SegmentRecord {
+            index,
+            start: segment.range().start,
+            end: segment.range().end,
+            delimiter: segment.delimiter().map(str::to_owned),
+            text: segment.full().to_owned(),
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'t, I> FusedIterator for Records<I> where I: FusedIterator<Item = Segment<'t>> {}
+
+/// A section of a text yielded by `sections`: a header matched by the
+/// regular expression and the body that follows it up to the next header.
+pub type Section<'t> = haystack::Section<'t, str>;
+
+/// Yields the sections of a text that begin with a regular expression match,
+/// as [`Section`]s.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+pub type Sections<'r, 't> = haystack::Sections<'r, 't, str>;
+
+/// A substring of a text split by several regular expressions in turn, as
+/// built by [`split_tree`], together with the substrings it was split into.
+pub type SegmentTree<'t> = haystack::SegmentTree<'t, str>;
+
+/// Splits `text` with each of `levels` in turn: the first regular expression
+/// splits the whole text, the second splits each of the resulting substrings,
+/// and so on, as with `split_inclusive`. Returns a tree whose root covers the
+/// whole text and whose nodes at depth `n` were split out by `levels[n - 1]`.
+/// A substring with no match at some level has one child, covering all of it.
+///
+/// Substrings are split on their own, so `^` and `$` match at their ends
+/// without multi-line mode.
+///
+/// # Example
+///
+/// ```rust
+/// # use regex::Regex;
+/// # use crate::regex_split::split_tree;
+/// # fn main() {
+/// let paragraphs = Regex::new(r"\n\n").unwrap();
+/// let sentences = Regex::new(r"\. ").unwrap();
+/// let text = "One. Two.\n\nThree.";
+/// let tree = split_tree(text, &[&paragraphs, &sentences]);
+/// let v: Vec<Vec<&str>> = tree
+///     .children()
+///     .iter()
+///     .map(|paragraph| paragraph.children().iter().map(|s| s.full()).collect())
+///     .collect();
+/// assert_eq!(v, [vec!["One. ", "Two.\n\n"], vec!["Three."]]);
+/// assert_eq!(tree.children()[1].range(), 11..17);
+/// # }
+/// ```
+pub fn split_tree<'t, R>(text: &'t str, levels: &[R]) -> SegmentTree<'t>
+where
+    R: Borrow<Regex>,
+{
+    haystack::split_tree(text, levels)
+}
+
+/// Yields the pieces of a text split by a list of regular expressions in
+/// order of priority, as built by [`split_recursive`].
+///
+/// `'r` is the lifetime of the list of regular expressions and `'t` is the
+/// lifetime of the byte string being split.
+pub type SplitRecursive<'r, 't, R> = haystack::SplitRecursive<'r, 't, str, R>;
+
+/// Splits `text` into pieces no longer than `max_len` where possible, trying
+/// `patterns` in order of priority, like the recursive character splitting
+/// of text chunkers. A region longer than `max_len` is split with the first
+/// pattern that matches in it, as with `split_inclusive`, and any piece still
+/// too long is split the same way with the patterns after that one. Pieces
+/// that no remaining pattern can split are yielded as they are, however
+/// long.
+///
+/// Each piece is paired with the index in `patterns` of the pattern whose
+/// match ends it, or `None` for the piece at the end of the text.
+///
+/// This function will *not* copy the text given.
+///
+/// # Example
+///
+/// ```rust
+/// # use regex::Regex;
+/// # use crate::regex_split::split_recursive;
+/// # fn main() {
+/// let patterns = [Regex::new(r"\n\n").unwrap(), Regex::new(r"\. ").unwrap()];
+/// let text = "Short.\n\nA longer one. It goes on.";
+/// let v: Vec<_> = split_recursive(text, &patterns, 16).collect();
+/// assert_eq!(v, [
+///     ("Short.\n\n", Some(0)),
+///     ("A longer one. ", Some(1)),
+///     ("It goes on.", None),
+/// ]);
+/// # }
+/// ```
+pub fn split_recursive<'r, 't, R>(
+    text: &'t str,
+    patterns: &'r [R],
+    max_len: usize,
+) -> SplitRecursive<'r, 't, R>
+where
+    R: Borrow<Regex>,
+{
+    haystack::SplitRecursive::new(text, patterns, max_len)
+}
+
+// The match iterator behind the range iterators, which are shared by the str
+// and bytes implementations.
+#[derive(Clone, Debug)]
+enum RangeFinder<'r, 't> {
+    Str(MatchesAt<'r, 't, str>),
+    Bytes(MatchesAt<'r, 't, [u8]>),
+}
+
+impl<'r, 't> Iterator for RangeFinder<'r, 't> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            RangeFinder::Str(finder) => finder.next().map(|m| m.range()),
+            RangeFinder::Bytes(finder) => finder.next().map(|m| m.range()),
+        }
+    }
+}
+
+/// Yields the byte ranges of all substrings delimited by a regular expression
+/// match inclusive of the match. This is returned for both `str` and `[u8]`
+/// texts.
+///
+/// The ranges don't borrow the text, so they can be stored and applied later
+/// to the same text or to a copy of it.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+#[derive(Clone, Debug)]
+pub struct SplitInclusiveRanges<'r, 't> {
+    finder: RangeFinder<'r, 't>,
+    last: Option<usize>,
+    len: usize,
+}
+
+impl<'r, 't> Iterator for SplitInclusiveRanges<'r, 't> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let last = self.last?;
+        match self.finder.next() {
+            None => {
+                let range = last..self.len;
+                self.last = None;
+                Some(range)
+            }
+            Some(m) => {
+                let range = last..m.end;
+                self.last = Some(m.end);
+                Some(range)
+            }
+        }
+    }
+}
+
+impl<'r, 't> FusedIterator for SplitInclusiveRanges<'r, 't> {}
+
+/// Yields the byte ranges of all substrings delimited by a regular expression
+/// match, with the match at the front of each substring. This is returned for
+/// both `str` and `[u8]` texts.
+///
+/// The ranges don't borrow the text, so they can be stored and applied later
+/// to the same text or to a copy of it.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the byte string being split.
+#[derive(Clone, Debug)]
+pub struct SplitInclusiveLeftRanges<'r, 't> {
+    finder: RangeFinder<'r, 't>,
+    last: Option<usize>,
+    len: usize,
+}
+
+impl<'r, 't> Iterator for SplitInclusiveLeftRanges<'r, 't> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let last = self.last?;
+        match self.finder.next() {
+            None => {
+                let range = last..self.len;
+                self.last = None;
+                Some(range)
+            }
+            Some(m) => {
+                let range = last..m.start;
+                self.last = Some(m.start);
+                Some(range)
+            }
+        }
+    }
+}
+
+impl<'r, 't> FusedIterator for SplitInclusiveLeftRanges<'r, 't> {}
+
+/// Splits text on a match of any of the patterns in a `RegexSet`, telling
+/// which pattern matched. A `RegexSet` can only tell which patterns match, not
+/// where, so the patterns are compiled again one by one.
+///
+/// # Example
+///
+/// ```rust
+/// # use regex::RegexSet;
+/// # use crate::regex_split::SetSplitter;
+/// # fn main() {
+/// let set = RegexSet::new([r"(?m)^[A-Z]+: ", r"(?m)^\[[a-z]+\] "]).unwrap();
+/// let splitter = SetSplitter::new(&set).unwrap();
+/// let text = "ALICE: hi\n[bob] hello\nALICE: bye";
+/// let v: Vec<_> = splitter.split_inclusive_left(text).collect();
+/// assert_eq!(v, [
+///     ("", None),
+///     ("ALICE: hi\n", Some(0)),
+///     ("[bob] hello\n", Some(1)),
+///     ("ALICE: bye", Some(0)),
+/// ]);
+/// # }
+/// ```
+pub type SetSplitter = haystack::SetSplitter<str>;
+
+/// Splits text on a match of any of several regular expressions, telling
+/// which one matched. Where matches of different regular expressions overlap,
+/// the one listed first wins, even if the other begins earlier.
+///
+/// # Example
+///
+/// ```rust
+/// # use regex::Regex;
+/// # use crate::regex_split::MultiSplitter;
+/// # fn main() {
+/// let sentence = Regex::new(r"[.!?] +").unwrap();
+/// let ellipsis = Regex::new(r"\.\.\. +").unwrap();
+/// let splitter = MultiSplitter::new(&[&ellipsis, &sentence]);
+/// let text = "Wait... What? Oh.";
+/// let v: Vec<_> = splitter.split_inclusive(text).collect();
+/// assert_eq!(v, [
+///     ("Wait... ", Some(0)),
+///     ("What? ", Some(1)),
+///     ("Oh.", None),
+/// ]);
+/// # }
+/// ```
+pub type MultiSplitter = haystack::MultiSplitter<str>;
+
+/// Yields all substrings delimited by a match of any pattern of a
+/// [`SetSplitter`] or [`MultiSplitter`], with the match included at the end of
+/// the substring, each paired with the index of the pattern that matched.
+///
+/// `'s` is the lifetime of the splitter and `'t` is the lifetime of the byte
+/// string being split.
+pub type SetSplitInclusive<'s, 't> = haystack::SetSplitInclusive<'s, 't, str>;
+
+/// Yields all substrings delimited by a match of any pattern of a
+/// [`SetSplitter`] or [`MultiSplitter`], with the match included at the start
+/// of the substring, each paired with the index of the pattern that matched.
+///
+/// `'s` is the lifetime of the splitter and `'t` is the lifetime of the byte
+/// string being split.
+pub type SetSplitInclusiveLeft<'s, 't> = haystack::SetSplitInclusiveLeft<'s, 't, str>;
+
+impl RegexSplit for Regex {
+    /// Returns an iterator of substrings of `text` separated by a match of the
+    /// regular expression. Differs from the iterator produced by split in that
+    /// split_inclusive leaves the matched part as the terminator of the
+    /// substring.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = "Mary had a little lamb\nlittle lamb\r\nlittle lamb.";
+    /// let v: Vec<&str> = re.split_inclusive(text).collect();
+    /// assert_eq!(v, [
+    ///     "Mary had a little lamb\n",
+    ///     "little lamb\r\n",
+    ///     "little lamb.",
+    /// ]);
+    /// # }
+    /// ```
+    fn split_inclusive<'r, 't>(&'r self, text: &'t str) -> SplitInclusive<'r, 't> {
+        SplitInclusive::new(self, text)
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of the
+    /// regular expression. Differs from the iterator produced by split in that
+    /// split_inclusive leaves the matched part as the terminator of the
+    /// substring.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = "Mary had a little lamb\nlittle lamb\r\nlittle lamb.";
+    /// let v: Vec<&str> = re.split_inclusive_left(text).collect();
+    /// assert_eq!(v, [
+    ///     "Mary had a little lamb",
+    ///     "\nlittle lamb",
+    ///     "\r\nlittle lamb.",
+    /// ]);
+    /// # }
+    /// ```
+    fn split_inclusive_left<'r, 't>(&'r self, text: &'t str) -> SplitInclusiveLeft<'r, 't> {
+        SplitInclusiveLeft::new(self, text)
+    }
+    /// Returns an iterator of at most `n` substrings of `text` separated by a
+    /// match of the regular expression, starting from the end of the text.
+    /// Like `split_inclusive`, the matched part is left as the terminator of
+    /// each substring. The final item yielded is the unsplit remainder at the
+    /// front of the text.
+    ///
+    /// Only the positions of the last `n - 1` matches are kept, so this is
+    /// cheap even when the text contains many more matches than that.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = "Mary had a little lamb\nlittle lamb\r\nlittle lamb.";
+    /// let v: Vec<&str> = re.rsplitn_inclusive(text, 2).collect();
+    /// assert_eq!(v, [
+    ///     "little lamb.",
+    ///     "Mary had a little lamb\nlittle lamb\r\n",
+    /// ]);
+    /// # }
+    /// ```
+    fn rsplitn_inclusive<'r, 't>(&'r self, text: &'t str, n: usize) -> RSplitNInclusive<'r, 't> {
+        RSplitNInclusive::new(self, text, n)
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of the
+    /// regular expression, exactly like `split_inclusive`, except that the
+    /// trailing substring is skipped if it is empty. This happens when the
+    /// text ends with a match, just as with `split_terminator` in std.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = "Mary had a little lamb\nlittle lamb\r\nlittle lamb.\n";
+    /// let v: Vec<&str> = re.split_inclusive_terminator(text).collect();
+    /// assert_eq!(v, [
+    ///     "Mary had a little lamb\n",
+    ///     "little lamb\r\n",
+    ///     "little lamb.\n",
+    /// ]);
+    /// # }
+    /// ```
+    fn split_inclusive_terminator<'r, 't>(&'r self, text: &'t str) -> SplitInclusive<'r, 't> {
+        self.split_inclusive(text)
+            .trailing_empty(TrailingEmpty::Drop)
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of the
+    /// regular expression, exactly like `split_inclusive_left`, except that
+    /// the trailing substring is skipped if it is empty. This happens when
+    /// the text ends with an empty match.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"(?m)$").unwrap();
+    /// let text = "Mary had a little lamb\nlittle lamb\nlittle lamb.";
+    /// let v: Vec<&str> = re.split_inclusive_left_terminator(text).collect();
+    /// assert_eq!(v, [
+    ///     "Mary had a little lamb",
+    ///     "\nlittle lamb",
+    ///     "\nlittle lamb.",
+    /// ]);
+    /// # }
+    /// ```
+    fn split_inclusive_left_terminator<'r, 't>(
+        &'r self,
+        text: &'t str,
+    ) -> SplitInclusiveLeft<'r, 't> {
+        self.split_inclusive_left(text)
+            .trailing_empty(TrailingEmpty::Drop)
+    }
+
+    /// Returns an iterator of substrings of `text[start..]` separated by a
+    /// match of the regular expression, like `split_inclusive`. Unlike
+    /// splitting a subslice, the regular expression still sees all of `text`,
+    /// so `^`, `\A` and `\b` mean the same thing they would if splitting had
+    /// begun at the start.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start` is greater than the length of `text`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"(?m)\n^#").unwrap();
+    /// let text = "# one\n# two\n# three";
+    /// let v: Vec<&str> = re.split_inclusive_at(text, 2).collect();
+    /// assert_eq!(v, [
+    ///     "one\n#",
+    ///     " two\n#",
+    ///     " three",
+    /// ]);
+    ///
+    /// let re = Regex::new(r"^#").unwrap();
+    /// assert_eq!(re.split_inclusive_at(text, 2).count(), 1);
+    /// # }
+    /// ```
+    fn split_inclusive_at<'r, 't>(&'r self, text: &'t str, start: usize) -> SplitInclusive<'r, 't> {
+        SplitInclusive::new_at(self, text, start)
+    }
+
+    /// Splits `text` in two at the first match of the regular expression,
+    /// leaving the matched part at the end of the first half. Returns `None`
+    /// if there is no match.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n\n").unwrap();
+    /// let text = "From: Mary\n\nlittle lamb\n\nlittle lamb";
+    /// let (head, body) = re.split_once_inclusive(text).unwrap();
+    /// assert_eq!(head, "From: Mary\n\n");
+    /// assert_eq!(body, "little lamb\n\nlittle lamb");
+    /// # }
+    /// ```
+    fn split_once_inclusive<'t>(&self, text: &'t str) -> Option<(&'t str, &'t str)> {
+        let m = self.find(text)?;
+        Some((&text[..m.end()], &text[m.end()..]))
+    }
+
+    /// Splits `text` in two at the first match of the regular expression,
+    /// leaving the matched part at the start of the second half. Returns
+    /// `None` if there is no match.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n\n").unwrap();
+    /// let text = "From: Mary\n\nlittle lamb\n\nlittle lamb";
+    /// let (head, body) = re.split_once_inclusive_left(text).unwrap();
+    /// assert_eq!(head, "From: Mary");
+    /// assert_eq!(body, "\n\nlittle lamb\n\nlittle lamb");
+    /// # }
+    /// ```
+    fn split_once_inclusive_left<'t>(&self, text: &'t str) -> Option<(&'t str, &'t str)> {
+        let m = self.find(text)?;
+        Some((&text[..m.start()], &text[m.start()..]))
+    }
+
+    /// Splits `text` in two at the last match of the regular expression,
+    /// leaving the matched part at the end of the first half. Returns `None`
+    /// if there is no match.
+    ///
+    /// The regex crate can't search backwards, so this scans the whole text.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n\n").unwrap();
+    /// let text = "From: Mary\n\nlittle lamb\n\nlittle lamb";
+    /// let (head, tail) = re.rsplit_once_inclusive(text).unwrap();
+    /// assert_eq!(head, "From: Mary\n\nlittle lamb\n\n");
+    /// assert_eq!(tail, "little lamb");
+    /// # }
+    /// ```
+    fn rsplit_once_inclusive<'t>(&self, text: &'t str) -> Option<(&'t str, &'t str)> {
+        let m = self.find_iter(text).last()?;
+        Some((&text[..m.end()], &text[m.end()..]))
+    }
+
+    /// Splits `text` in two at the last match of the regular expression,
+    /// leaving the matched part at the start of the second half. Returns
+    /// `None` if there is no match.
+    ///
+    /// The regex crate can't search backwards, so this scans the whole text.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n\n").unwrap();
+    /// let text = "From: Mary\n\nlittle lamb\n\nlittle lamb";
+    /// let (head, tail) = re.rsplit_once_inclusive_left(text).unwrap();
+    /// assert_eq!(head, "From: Mary\n\nlittle lamb");
+    /// assert_eq!(tail, "\n\nlittle lamb");
+    /// # }
+    /// ```
+    fn rsplit_once_inclusive_left<'t>(&self, text: &'t str) -> Option<(&'t str, &'t str)> {
+        let m = self.find_iter(text).last()?;
+        Some((&text[..m.start()], &text[m.start()..]))
+    }
+
+    /// Splits `text` in two at the `n`th match of the regular expression,
+    /// counting from zero, leaving the matched part at the end of the first
+    /// half. Returns `None` if there are `n` matches or fewer. Only searches
+    /// as far as the `n`th match.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text = "header\nrecord one\nrecord two\nrecord three";
+    /// let (skipped, rest) = re.partition_at_nth_match(text, 1).unwrap();
+    /// assert_eq!(skipped, "header\nrecord one\n");
+    /// assert_eq!(rest, "record two\nrecord three");
+    /// assert_eq!(re.partition_at_nth_match(text, 3), None);
+    /// # }
+    /// ```
+    fn partition_at_nth_match<'t>(&self, text: &'t str, n: usize) -> Option<(&'t str, &'t str)> {
+        let m = self.find_iter(text).nth(n)?;
+        Some((&text[..m.end()], &text[m.end()..]))
+    }
+
+    /// Splits `text` in two at the `n`th match of the regular expression,
+    /// counting from zero, leaving the matched part at the start of the
+    /// second half. Returns `None` if there are `n` matches or fewer. Only
+    /// searches as far as the `n`th match.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"(?m)^>").unwrap();
+    /// let text = ">one\nACGT\n>two\nTTGA\n>three\nGGCA";
+    /// let (skipped, rest) = re.partition_at_nth_match_left(text, 2).unwrap();
+    /// assert_eq!(skipped, ">one\nACGT\n>two\nTTGA\n");
+    /// assert_eq!(rest, ">three\nGGCA");
+    /// # }
+    /// ```
+    fn partition_at_nth_match_left<'t>(
+        &self,
+        text: &'t str,
+        n: usize,
+    ) -> Option<(&'t str, &'t str)> {
+        let m = self.find_iter(text).nth(n)?;
+        Some((&text[..m.start()], &text[m.start()..]))
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of the
+    /// regular expression, yielding the same substrings as `split_inclusive`
+    /// but starting from the end of the text.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = "Mary had a little lamb\nlittle lamb\r\nlittle lamb.";
+    /// let v: Vec<&str> = re.split_inclusive_rev(text).collect();
+    /// assert_eq!(v, [
+    ///     "little lamb.",
+    ///     "little lamb\r\n",
+    ///     "Mary had a little lamb\n",
+    /// ]);
+    /// # }
+    /// ```
+    fn split_inclusive_rev<'r, 't>(&'r self, text: &'t str) -> SplitInclusiveRev<'r, 't> {
+        SplitInclusiveRev::new(self, text)
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of the
+    /// regular expression, yielding the same substrings as
+    /// `split_inclusive_left` but starting from the end of the text.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = "Mary had a little lamb\nlittle lamb\r\nlittle lamb.";
+    /// let v: Vec<&str> = re.split_inclusive_left_rev(text).collect();
+    /// assert_eq!(v, [
+    ///     "\r\nlittle lamb.",
+    ///     "\nlittle lamb",
+    ///     "Mary had a little lamb",
+    /// ]);
+    /// # }
+    /// ```
+    fn split_inclusive_left_rev<'r, 't>(&'r self, text: &'t str) -> SplitInclusiveLeftRev<'r, 't> {
+        SplitInclusiveLeftRev::new(self, text)
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of the
+    /// regular expression, where each match appears twice: as the terminator
+    /// of the substring before it and as the beginning of the substring after
+    /// it.
+    ///
+    /// This method will *not* copy the text given; the substrings simply
+    /// overlap.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = "Mary had a little lamb\nlittle lamb\r\nlittle lamb.";
+    /// let v: Vec<&str> = re.split_inclusive_both(text).collect();
+    /// assert_eq!(v, [
+    ///     "Mary had a little lamb\n",
+    ///     "\nlittle lamb\r\n",
+    ///     "\r\nlittle lamb.",
+    /// ]);
+    /// # }
+    /// ```
+    fn split_inclusive_both<'r, 't>(&'r self, text: &'t str) -> SplitInclusiveBoth<'r, 't> {
+        SplitInclusiveBoth::new(self, text)
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of the
+    /// regular expression, where `side` decides for each match whether it
+    /// goes at the end of the substring before it, at the start of the one
+    /// after it, in both, or in neither. See [`Side`].
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::{RegexSplit, Side};
+    /// # fn main() {
+    /// let re = Regex::new(r"(?m);\n|^#").unwrap();
+    /// let text = "a;\nb;\n#c\nd;\n#e";
+    /// let v: Vec<&str> = re
+    ///     .split_inclusive_by(text, |m| match m.as_str() {
+    ///         "#" => Side::Right,
+    ///         _ => Side::Left,
+    ///     })
+    ///     .collect();
+    /// assert_eq!(v, [
+    ///     "a;\n",
+    ///     "b;\n",
+    ///     "",
+    ///     "#c\nd;\n",
+    ///     "",
+    ///     "#e",
+    /// ]);
+    /// # }
+    /// ```
+    fn split_inclusive_by<'r, 't, F>(
+        &'r self,
+        text: &'t str,
+        side: F,
+    ) -> SplitInclusiveBy<'r, 't, F>
+    where
+        F: FnMut(&Match<'t>) -> Side,
+    {
+        SplitInclusiveBy::new(self, text, side)
+    }
+
+    /// Returns an iterator of substrings of `text` separated by capture group
+    /// `group` of each match of the regular expression, rather than by the
+    /// whole match. The group's text goes at the end of the substring before
+    /// it, like with `split_inclusive`, and whatever else the match covers is
+    /// left where it is. Matches in which the group doesn't participate are
+    /// ignored.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the regular expression has no group numbered `group`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"(,)\s*").unwrap();
+    /// let text = "one,  two, three";
+    /// let v: Vec<&str> = re.split_on_group(text, 1).collect();
+    /// assert_eq!(v, [
+    ///     "one,",
+    ///     "  two,",
+    ///     " three",
+    /// ]);
+    /// # }
+    /// ```
+    fn split_on_group<'r, 't>(&'r self, text: &'t str, group: usize) -> SplitOnGroup<'r, 't> {
+        assert!(
+            group < self.captures_len(),
+            "no capture group numbered {}",
+            group
+        );
+        SplitOnGroup::new(self, text, group)
+    }
+
+    /// Like `split_on_group`, but names the capture group to split on.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the regular expression has no group named `name`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"(?P<sep>,)\s*").unwrap();
+    /// let text = "one,  two, three";
+    /// let v: Vec<&str> = re.split_on_name(text, "sep").collect();
+    /// assert_eq!(v, [
+    ///     "one,",
+    ///     "  two,",
+    ///     " three",
+    /// ]);
+    /// # }
+    /// ```
+    fn split_on_name<'r, 't>(&'r self, text: &'t str, name: &str) -> SplitOnGroup<'r, 't> {
+        let group = self
+            .capture_names()
+            .position(|n| n == Some(name))
+            .unwrap_or_else(|| panic!("no capture group named {:?}", name));
+        self.split_on_group(text, group)
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of the
+    /// regular expression, where the part of each match captured by a group
+    /// named `left` goes at the end of the substring before it and the part
+    /// captured by a group named `right` goes at the start of the substring
+    /// after it. Anything else in the match is dropped, like with
+    /// `Regex::split`. This does the job of lookbehind and lookahead, which
+    /// the regex crate doesn't support.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"(?P<left>[.?!])\s+(?P<right>[A-Z])").unwrap();
+    /// let text = "It rained. Then it stopped!  Nobody cared.";
+    /// let v: Vec<&str> = re.split_around(text).collect();
+    /// assert_eq!(v, [
+    ///     "It rained.",
+    ///     "Then it stopped!",
+    ///     "Nobody cared.",
+    /// ]);
+    /// # }
+    /// ```
+    fn split_around<'r, 't>(&'r self, text: &'t str) -> SplitAround<'r, 't> {
+        SplitAround::new(self, text)
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of the
+    /// regular expression, like `split_inclusive`, except that each substring
+    /// is paired with the capture groups of the match that ends it. The last
+    /// substring is paired with `None`.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r" @(?P<hour>\d\d):(?P<minute>\d\d)\n").unwrap();
+    /// let text = "started @09:30\nstopped @17:05\nno time";
+    /// let v: Vec<_> = re
+    ///     .split_inclusive_captures(text)
+    ///     .map(|(s, c)| (s, c.map(|c| [&c["hour"], &c["minute"]].join(":"))))
+    ///     .collect();
+    /// assert_eq!(v, [
+    ///     ("started @09:30\n", Some("09:30".to_owned())),
+    ///     ("stopped @17:05\n", Some("17:05".to_owned())),
+    ///     ("no time", None),
+    /// ]);
+    /// # }
+    /// ```
+    fn split_inclusive_captures<'r, 't>(&'r self, text: &'t str) -> SplitInclusiveCaptures<'r, 't> {
+        SplitInclusiveCaptures::new(self, text)
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of the
+    /// regular expression, like `split_inclusive`, except that each substring
+    /// is paired with the name of the first named group that took part in
+    /// the match that ends it. Naming the branches of an alternation this way
+    /// tells which kind of delimiter each one is. The last substring, and any
+    /// ended by a match without a named group, is paired with `None`.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"(?P<crlf>\r\n)|(?P<lf>\n)|(?P<ff>\x0c)").unwrap();
+    /// let text = "one\r\ntwo\nthree\x0cfour";
+    /// let v: Vec<_> = re.split_inclusive_tagged(text).collect();
+    /// assert_eq!(v, [
+    ///     ("one\r\n", Some("crlf")),
+    ///     ("two\n", Some("lf")),
+    ///     ("three\x0c", Some("ff")),
+    ///     ("four", None),
+    /// ]);
+    /// # }
+    /// ```
+    fn split_inclusive_tagged<'r, 't>(&'r self, text: &'t str) -> SplitInclusiveTagged<'r, 't> {
+        SplitInclusiveTagged::new(self, text)
+    }
+
+    /// Returns an iterator of the sections of `text` that begin with a match
+    /// of the regular expression, each paired with the text captured by the
+    /// group named `name` in that match, or `None` if the group didn't
+    /// participate. The sections are split as by `split_inclusive_left`, so
+    /// each begins with its match. Text before the first match, if there is
+    /// any, is paired with `None`.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the regular expression has no group named `name`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"(?m)^\[(?P<name>\w+)\]\n").unwrap();
+    /// let text = "debug = true\n[server]\nport = 80\n[client]\nretries = 3\n";
+    /// let v: Vec<_> = re.group_by_capture(text, "name").collect();
+    /// assert_eq!(v, [
+    ///     (None, "debug = true\n"),
+    ///     (Some("server"), "[server]\nport = 80\n"),
+    ///     (Some("client"), "[client]\nretries = 3\n"),
+    /// ]);
+    /// # }
+    /// ```
+    fn group_by_capture<'r, 't>(&'r self, text: &'t str, name: &str) -> GroupByCapture<'r, 't> {
+        let group = self
+            .capture_names()
+            .position(|n| n == Some(name))
+            .unwrap_or_else(|| panic!("no capture group named {:?}", name));
+        GroupByCapture::new(self, text, group)
+    }
+
+    /// Returns an iterator of the regions of `text` that begin after a match
+    /// of this regular expression and end before the next match of `end`
+    /// that follows it. Text outside the regions is skipped. A region with no
+    /// closing match runs to the end of the text. Use `include_markers` to
+    /// keep the matches in the regions.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let begin = Regex::new(r"(?m)^BEGIN\n").unwrap();
+    /// let end = Regex::new(r"(?m)^END\n").unwrap();
+    /// let text = "noise\nBEGIN\none\nEND\nnoise\nBEGIN\ntwo\nEND\n";
+    /// let v: Vec<&str> = begin.split_between(text, &end).collect();
+    /// assert_eq!(v, [
+    ///     "one\n",
+    ///     "two\n",
+    /// ]);
+    /// # }
+    /// ```
+    fn split_between<'r, 't>(&'r self, text: &'t str, end: &'r Regex) -> SplitBetween<'r, 't> {
+        SplitBetween::new(self, end, text)
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of the
+    /// regular expression, like `split_inclusive`, except that the text is
+    /// never split inside a region matched by `mask`: any match overlapping
+    /// such a region is ignored. This expresses "split on this, unless
+    /// inside that", such as splitting on commas outside quoted strings.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r",").unwrap();
+    /// let quoted = Regex::new(r#""[^"]*""#).unwrap();
+    /// let text = r#"one,"two, three",four"#;
+    /// let v: Vec<&str> = re.split_inclusive_outside(text, &quoted).collect();
+    /// assert_eq!(v, [
+    ///     "one,",
+    ///     r#""two, three","#,
+    ///     "four",
+    /// ]);
+    /// # }
+    /// ```
+    fn split_inclusive_outside<'r, 't>(
+        &'r self,
+        text: &'t str,
+        mask: &'r Regex,
+    ) -> SplitInclusiveOutside<'r, 't> {
+        SplitInclusiveOutside::new(self, mask, text)
+    }
+
+    /// Returns an iterator of contiguous slices of `text` that each end with
+    /// every `n`th match of the regular expression, so that each holds `n`
+    /// records split as by `split_inclusive`. Whatever follows the last full
+    /// chunk is yielded as a shorter one, unless it is empty.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text = "1\n2\n3\n4\n5\n";
+    /// let v: Vec<&str> = re.chunks_by_matches(text, 2).collect();
+    /// assert_eq!(v, [
+    ///     "1\n2\n",
+    ///     "3\n4\n",
+    ///     "5\n",
+    /// ]);
+    /// # }
+    /// ```
+    fn chunks_by_matches<'r, 't>(&'r self, text: &'t str, n: usize) -> ChunksByMatches<'r, 't> {
+        assert!(n != 0, "chunk size must be non-zero");
+        ChunksByMatches::new(self, text, n)
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of the
+    /// regular expression, like `Regex::split`, except that each substring is
+    /// paired with the match that separated it from the next one. The last
+    /// substring is paired with `None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = "Mary had a little lamb\nlittle lamb\r\nlittle lamb.";
+    /// let v: Vec<_> = re
+    ///     .split_with_delimiters(text)
+    ///     .map(|(s, m)| (s, m.map(|m| (m.start(), m.as_str()))))
+    ///     .collect();
+    /// assert_eq!(v, [
+    ///     ("Mary had a little lamb", Some((22, "\n"))),
+    ///     ("little lamb", Some((34, "\r\n"))),
+    ///     ("little lamb.", None),
+    /// ]);
+    /// # }
+    /// ```
+    fn split_with_delimiters<'r, 't>(&'r self, text: &'t str) -> SplitWithDelimiters<'r, 't> {
+        SplitWithDelimiters::new(self, text)
+    }
+
+    /// Returns an iterator of the matches of the regular expression in
+    /// `text`, each in a slice of the text with up to `n` bytes of context on
+    /// either side, along with the range of the match within the slice. The
+    /// context stops short of `n` bytes rather than split a character, and at
+    /// either end of the text. Handy for showing search hits as snippets.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"lamb").unwrap();
+    /// let text = "Mary had a little lamb, whose fleece was white as snow";
+    /// let v: Vec<_> = re.matches_with_context(text, 7).collect();
+    /// assert_eq!(v, [("little lamb, whose", 7..11)]);
+    ///
+    /// let re = Regex::new(r"b").unwrap();
+    /// let v: Vec<_> = re.matches_with_context("ééébééé", 3).collect();
+    /// assert_eq!(v, [("ébé", 2..3)]);
+    /// # }
+    /// ```
+    fn matches_with_context<'r, 't>(
+        &'r self,
+        text: &'t str,
+        n: usize,
+    ) -> MatchesWithContext<'r, 't> {
+        MatchesWithContext::new(self, text, n)
+    }
+
+    /// Returns an iterator that alternates between the text separating matches
+    /// of the regular expression and the matches themselves. Together, the
+    /// parts cover every byte of `text` exactly once; empty runs of text
+    /// between adjacent matches are not yielded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::{Part, RegexSplit};
+    /// # fn main() {
+    /// let re = Regex::new(r"[+-]").unwrap();
+    /// let text = "1+2-3";
+    /// let v: Vec<_> = re
+    ///     .split_parts(text)
+    ///     .map(|part| match part {
+    ///         Part::Text(s) => ("text", s),
+    ///         Part::Delim(m) => ("delim", m.as_str()),
+    ///     })
+    ///     .collect();
+    /// assert_eq!(v, [
+    ///     ("text", "1"),
+    ///     ("delim", "+"),
+    ///     ("text", "2"),
+    ///     ("delim", "-"),
+    ///     ("text", "3"),
+    /// ]);
+    /// # }
+    /// ```
+    fn split_parts<'r, 't>(&'r self, text: &'t str) -> SplitParts<'r, 't> {
+        SplitParts::new(self, text)
+    }
+
+    /// Returns an iterator of the same substrings as `split_inclusive`, each
+    /// paired with the byte offset at which it starts in `text`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = "Mary had a little lamb\nlittle lamb\r\nlittle lamb.";
+    /// let v: Vec<(usize, &str)> = re.split_inclusive_indices(text).collect();
+    /// assert_eq!(v, [
+    ///     (0, "Mary had a little lamb\n"),
+    ///     (23, "little lamb\r\n"),
+    ///     (36, "little lamb."),
+    /// ]);
+    /// # }
+    /// ```
+    fn split_inclusive_indices<'r, 't>(&'r self, text: &'t str) -> SplitInclusiveIndices<'r, 't> {
+        SplitInclusiveIndices::new(self, text)
+    }
 
-pub mod bytes;
+    /// Returns an iterator of the same substrings as `split_inclusive_left`,
+    /// each paired with the byte offset at which it starts in `text`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = "Mary had a little lamb\nlittle lamb\r\nlittle lamb.";
+    /// let v: Vec<(usize, &str)> = re.split_inclusive_left_indices(text).collect();
+    /// assert_eq!(v, [
+    ///     (0, "Mary had a little lamb"),
+    ///     (22, "\nlittle lamb"),
+    ///     (34, "\r\nlittle lamb."),
+    /// ]);
+    /// # }
+    /// ```
+    fn split_inclusive_left_indices<'r, 't>(
+        &'r self,
+        text: &'t str,
+    ) -> SplitInclusiveLeftIndices<'r, 't> {
+        SplitInclusiveLeftIndices::new(self, text)
+    }
 
-use std::iter::FusedIterator;
+    /// Returns an iterator of the byte ranges of the substrings that
+    /// `split_inclusive` would yield. The ranges don't borrow `text`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = "Mary had a little lamb\nlittle lamb\r\nlittle lamb.";
+    /// let v: Vec<_> = re.split_inclusive_ranges(text).collect();
+    /// assert_eq!(v, [0..23, 23..36, 36..48]);
+    /// # }
+    /// ```
+    fn split_inclusive_ranges<'r, 't>(&'r self, text: &'t str) -> SplitInclusiveRanges<'r, 't> {
+        SplitInclusiveRanges {
+            finder: RangeFinder::Str(MatchesAt::new(self, text, 0)),
+            last: Some(0),
+            len: text.len(),
+        }
+    }
 
-use regex::{Matches, Regex};
+    /// Returns an iterator of the byte ranges of the substrings that
+    /// `split_inclusive_left` would yield. The ranges don't borrow `text`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = "Mary had a little lamb\nlittle lamb\r\nlittle lamb.";
+    /// let v: Vec<_> = re.split_inclusive_left_ranges(text).collect();
+    /// assert_eq!(v, [0..22, 22..34, 34..48]);
+    /// # }
+    /// ```
+    fn split_inclusive_left_ranges<'r, 't>(
+        &'r self,
+        text: &'t str,
+    ) -> SplitInclusiveLeftRanges<'r, 't> {
+        SplitInclusiveLeftRanges {
+            finder: RangeFinder::Str(MatchesAt::new(self, text, 0)),
+            last: Some(0),
+            len: text.len(),
+        }
+    }
 
-pub trait RegexSplit {
-    fn split_inclusive<'r, 't>(&'r self, text: &'t str) -> SplitInclusive<'r, 't>;
-    fn split_inclusive_left<'r, 't>(&'r self, text: &'t str) -> SplitInclusiveLeft<'r, 't>;
-}
+    /// Returns an iterator of the same substrings as `split_inclusive`, as
+    /// [`Segment`]s that can be taken apart into body and delimiter.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = "Mary had a little lamb\r\nlittle lamb.";
+    /// let v: Vec<_> = re.split_inclusive_segments(text).collect();
+    /// assert_eq!(v[0].full(), "Mary had a little lamb\r\n");
+    /// assert_eq!(v[0].body(), "Mary had a little lamb");
+    /// assert_eq!(v[0].delimiter(), Some("\r\n"));
+    /// assert_eq!(v[0].delimiter_range(), Some(22..24));
+    /// assert_eq!(v[1].body(), "little lamb.");
+    /// assert_eq!(v[1].delimiter(), None);
+    /// # }
+    /// ```
+    fn split_inclusive_segments<'r, 't>(&'r self, text: &'t str) -> SplitInclusiveSegments<'r, 't> {
+        SplitInclusiveSegments::new(self, text)
+    }
 
-/// Yields all substrings delimited by a regular expression match inclusive of
-/// the match.
-///
-/// `'r` is the lifetime of the compiled regular expression and `'t` is the
-/// lifetime of the byte string being split.
-#[derive(Debug)]
-pub struct SplitInclusive<'r, 't> {
-    finder: Matches<'r, 't>,
-    last: usize,
+    /// Returns an iterator of the same substrings as `split_inclusive_left`,
+    /// as [`Segment`]s that can be taken apart into body and delimiter.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = "Mary had a little lamb\r\nlittle lamb.";
+    /// let v: Vec<_> = re.split_inclusive_left_segments(text).collect();
+    /// assert_eq!(v[0].body(), "Mary had a little lamb");
+    /// assert_eq!(v[0].delimiter(), None);
+    /// assert_eq!(v[1].full(), "\r\nlittle lamb.");
+    /// assert_eq!(v[1].body(), "little lamb.");
+    /// assert_eq!(v[1].body_range(), 24..36);
+    /// assert_eq!(v[1].delimiter(), Some("\r\n"));
+    /// # }
+    /// ```
+    fn split_inclusive_left_segments<'r, 't>(
+        &'r self,
+        text: &'t str,
+    ) -> SplitInclusiveLeftSegments<'r, 't> {
+        SplitInclusiveLeftSegments::new(self, text)
+    }
 
-    // The internals of finder are private, meaning we need to keep a reference
-    // to the text for ourselves. This differs from the previous
-    // implementation.
-    text: &'t str,
-}
+    /// Returns an iterator of the sections of `text`, each a header matched
+    /// by the regular expression and the body following it up to the next
+    /// header, split as by `split_inclusive_left`. Text before the first
+    /// header, if there is any, is yielded as a section without one.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"(?m)^#+ .*\n").unwrap();
+    /// let text = "# Intro\nHello.\n## Usage\nRun it.\n";
+    /// let v: Vec<_> = re
+    ///     .sections(text)
+    ///     .map(|section| (section.header(), section.body()))
+    ///     .collect();
+    /// assert_eq!(v, [
+    ///     (Some("# Intro\n"), "Hello.\n"),
+    ///     (Some("## Usage\n"), "Run it.\n"),
+    /// ]);
+    /// # }
+    /// ```
+    fn sections<'r, 't>(&'r self, text: &'t str) -> Sections<'r, 't> {
+        Sections::new(self, text)
+    }
+
+    /// Returns the number of substrings `split_inclusive` would yield for
+    /// `text`, without slicing any of them out of the text. Use
+    /// [`Splitter::count_segments`](Splitter#method.count_segments) to count with
+    /// other policies.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// assert_eq!(re.count_segments("one\ntwo\r\nthree"), 3);
+    /// assert_eq!(re.count_segments(""), 1);
+    /// # }
+    /// ```
+    fn count_segments(&self, text: &str) -> usize {
+        self.split_inclusive(text).count()
+    }
 
-impl<'r, 't> Iterator for SplitInclusive<'r, 't> {
-    type Item = &'t str;
+    /// Returns a [`SegmentStats`] summary of the substrings
+    /// `split_inclusive` would yield for `text`, gathered in a single pass.
+    /// Good for checking that a pattern splits text the way it should, say
+    /// that it doesn't break it into a pile of empty substrings.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let stats = re.stats("one\ntwo\r\nthree\n");
+    /// assert_eq!(stats.count(), 4);
+    /// assert_eq!(stats.min_len(), 0);
+    /// assert_eq!(stats.max_len(), 6);
+    /// assert_eq!(stats.mean_len(), 3.75);
+    /// assert_eq!(stats.empty(), 1);
+    /// assert_eq!(stats.delimiter_bytes(), 4);
+    /// # }
+    /// ```
+    fn stats(&self, text: &str) -> SegmentStats {
+        let mut stats = SegmentStats::default();
+        for segment in self.split_inclusive_segments(text) {
+            let delimiter = segment.delimiter_range().map_or(0, |range| range.len());
+            stats.add(segment.range().len(), delimiter);
+        }
+        stats
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.finder.next() {
-            None => {
-                if self.last > self.text.len() {
-                    None
-                } else {
-                    let s = &self.text[self.last..];
-                    self.last = self.text.len() + 1; // Next call will return None
-                    Some(s)
-                }
+    /// Rebuilds `text` with the text between matches replaced by what `f`
+    /// returns for it, leaving the matches themselves as they were. If `f`
+    /// changes nothing, the text is returned borrowed, without copying it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::borrow::Cow;
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let text = "  one\r\ntwo  \nthree";
+    /// let trimmed = re.map_segments(text, |body| Cow::Borrowed(body.trim()));
+    /// assert_eq!(trimmed, "one\r\ntwo\nthree");
+    ///
+    /// let unchanged = re.map_segments(text, Cow::Borrowed);
+    /// assert!(matches!(unchanged, Cow::Borrowed(_)));
+    /// # }
+    /// ```
+    fn map_segments<'t, F>(&self, text: &'t str, mut f: F) -> Cow<'t, str>
+    where
+        F: FnMut(&'t str) -> Cow<'t, str>,
+    {
+        // Nothing is copied until a body comes back changed.
+        let mut out: Option<String> = None;
+        for segment in self.split_inclusive_segments(text) {
+            let body = segment.body();
+            let mapped = f(body);
+            if out.is_none() && mapped != body {
+                out = Some(text[..segment.range().start].to_owned());
             }
-            Some(m) => {
-                let matched = &self.text[self.last..m.end()];
-                self.last = m.end();
-                Some(matched)
+            if let Some(out) = &mut out {
+                out.push_str(&mapped);
+                out.push_str(segment.delimiter().unwrap_or_default());
             }
         }
+        out.map_or(Cow::Borrowed(text), Cow::Owned)
     }
-}
 
-impl<'r, 't> FusedIterator for SplitInclusive<'r, 't> {}
-
-/// Yields all substrings delimited by a regular expression match inclusive of
-/// the match.
-///
-/// `'r` is the lifetime of the compiled regular expression and `'t` is the
-/// lifetime of the byte string being split.
-#[derive(Debug)]
-pub struct SplitInclusiveLeft<'r, 't> {
-    finder: Matches<'r, 't>,
-    last: usize,
+    /// Replaces all matches of `re` in `text` with `rep`, like
+    /// `re.replace_all`, except within the matches of this regular
+    /// expression, which are left as they were. `re` searches the text
+    /// between matches one piece at a time, so its anchors match at the ends
+    /// of each piece. If nothing is replaced, the text is returned borrowed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let records = Regex::new(r"\n---\n").unwrap();
+    /// let token = Regex::new(r"token=\S+|-").unwrap();
+    /// let text = "user=a token=abc-1\n---\nuser=b token=def-2";
+    /// let redacted = records.replace_within_segments(text, &token, "token=***");
+    /// assert_eq!(redacted, "user=a token=***\n---\nuser=b token=***");
+    /// # }
+    /// ```
+    fn replace_within_segments<'t, R>(&self, text: &'t str, re: &Regex, mut rep: R) -> Cow<'t, str>
+    where
+        R: Replacer,
+    {
+        self.map_segments(text, |body| re.replace_all(body, rep.by_ref()))
+    }
 
-    // The internals of finder are private, meaning we need to keep a reference
-    // to the text for ourselves. This differs from the previous
-    // implementation.
-    text: &'t str,
-}
+    /// Returns an iterator like `split_inclusive`, except that it takes
+    /// ownership of `text` and yields owned copies of the substrings. It
+    /// borrows neither the text nor the regular expression, so it can outlive
+    /// both.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::{SplitInclusiveOwned, RegexSplit};
+    /// # fn main() {
+    /// fn lines(text: String) -> SplitInclusiveOwned {
+    ///     let re = Regex::new(r"\r?\n").unwrap();
+    ///     re.split_inclusive_owned(text)
+    /// }
+    ///
+    /// let v: Vec<_> = lines("one\ntwo\r\nthree".into()).collect();
+    /// assert_eq!(v, ["one\n", "two\r\n", "three"]);
+    /// # }
+    /// ```
+    fn split_inclusive_owned(&self, text: String) -> SplitInclusiveOwned {
+        SplitInclusiveOwned::new(self, text)
+    }
 
-impl<'r, 't> Iterator for SplitInclusiveLeft<'r, 't> {
-    type Item = &'t str;
+    /// Returns an iterator like `split_inclusive_left`, except that it takes
+    /// ownership of `text` and yields owned copies of the substrings.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"(?m)^-").unwrap();
+    /// let v: Vec<_> = re.split_inclusive_left_owned("fruits:\n-apple\n-pear".into()).collect();
+    /// assert_eq!(v, ["fruits:\n", "-apple\n", "-pear"]);
+    /// # }
+    /// ```
+    fn split_inclusive_left_owned(&self, text: String) -> SplitInclusiveLeftOwned {
+        SplitInclusiveLeftOwned::new(self, text)
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.finder.next() {
-            None => {
-                if self.last > self.text.len() {
-                    None
-                } else {
-                    let s = &self.text[self.last..];
-                    self.last = self.text.len() + 1; // Next call will return None
-                    Some(s)
-                }
-            }
-            Some(m) => {
-                let matched = &self.text[self.last..m.start()];
-                self.last = m.start();
-                Some(matched)
-            }
-        }
+    /// Returns an iterator like `split_inclusive` over a shared text, which
+    /// yields [`ArcSegment`]s holding on to the text instead of slices
+    /// borrowing it. The segments can be sent to other threads or stored
+    /// without copying any of the text.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::sync::Arc;
+    /// # use std::thread;
+    /// # use regex::Regex;
+    /// # use crate::regex_split::{ArcSegment, RegexSplit};
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text: Arc<str> = Arc::from("one\ntwo\nthree");
+    /// let segments: Vec<ArcSegment> = re.split_inclusive_arc(text).collect();
+    /// let handle = thread::spawn(move || segments[1].to_uppercase());
+    /// assert_eq!(handle.join().unwrap(), "TWO\n");
+    /// # }
+    /// ```
+    fn split_inclusive_arc(&self, text: Arc<str>) -> SplitInclusiveArc {
+        SplitInclusiveArc::new(self, text)
     }
-}
 
-impl<'r, 't> FusedIterator for SplitInclusiveLeft<'r, 't> {}
+    /// Returns an iterator like `split_inclusive_left` over a shared text,
+    /// which yields [`ArcSegment`]s holding on to the text.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::sync::Arc;
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"(?m)^-").unwrap();
+    /// let text: Arc<str> = Arc::from("fruits:\n-apple\n-pear");
+    /// let v: Vec<_> = re.split_inclusive_left_arc(text).collect();
+    /// assert_eq!(v[1].as_str(), "-apple\n");
+    /// assert_eq!(v[1].range(), 8..15);
+    /// # }
+    /// ```
+    fn split_inclusive_left_arc(&self, text: Arc<str>) -> SplitInclusiveLeftArc {
+        SplitInclusiveLeftArc::new(self, text)
+    }
 
-impl RegexSplit for Regex {
-    /// Returns an iterator of substrings of `text` separated by a match of the
-    /// regular expression. Differs from the iterator produced by split in that
-    /// split_inclusive leaves the matched part as the terminator of the
-    /// substring.
+    /// Returns an iterator like `split_inclusive` over a text that may be
+    /// borrowed or owned, yielding `Cow`s. A borrowed text is split without
+    /// copying anything, so a pipeline that only sometimes rewrites its text
+    /// before splitting it pays for copies only when it does.
     ///
-    /// This method will *not* copy the text given.
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::borrow::Cow;
+    /// # use regex::Regex;
+    /// # use crate::regex_split::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text: Cow<str> = Cow::Borrowed("one\ntwo");
+    /// let v: Vec<_> = re.split_inclusive_cow(text).collect();
+    /// assert!(matches!(v[0], Cow::Borrowed("one\n")));
+    ///
+    /// let text: Cow<str> = Cow::Owned("one\ntwo".to_uppercase());
+    /// let v: Vec<_> = re.split_inclusive_cow(text).collect();
+    /// assert!(matches!(&v[1], Cow::Owned(s) if s == "TWO"));
+    /// # }
+    /// ```
+    fn split_inclusive_cow<'r, 'a>(&'r self, text: Cow<'a, str>) -> SplitInclusiveCow<'r, 'a> {
+        SplitInclusiveCow::new(self, text)
+    }
+
+    /// Returns an iterator like `split_inclusive_left` over a text that may
+    /// be borrowed or owned, yielding `Cow`s.
     ///
     /// # Example
     ///
     /// ```rust
+    /// # use std::borrow::Cow;
     /// # use regex::Regex;
     /// # use crate::regex_split::RegexSplit;
     /// # fn main() {
+    /// let re = Regex::new(r"(?m)^-").unwrap();
+    /// let text: Cow<str> = Cow::Borrowed("a\n-b");
+    /// let v: Vec<_> = re.split_inclusive_left_cow(text).collect();
+    /// assert_eq!(v, ["a\n", "-b"]);
+    /// # }
+    /// ```
+    fn split_inclusive_left_cow<'r, 'a>(
+        &'r self,
+        text: Cow<'a, str>,
+    ) -> SplitInclusiveLeftCow<'r, 'a> {
+        SplitInclusiveLeftCow::new(self, text)
+    }
+}
+
+impl StrSplitExt for str {
+    /// Splits the text on matches of `re`, like `re.split_inclusive(text)`,
+    /// but with the text as the receiver.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::StrSplitExt;
+    /// # fn main() {
     /// let re = Regex::new(r"\r?\n").unwrap();
-    /// let text = "Mary had a little lamb\nlittle lamb\r\nlittle lamb.";
-    /// let v: Vec<&str> = re.split_inclusive(text).collect();
-    /// assert_eq!(v, [
-    ///     "Mary had a little lamb\n",
-    ///     "little lamb\r\n",
-    ///     "little lamb.",
-    /// ]);
+    /// let text = "one\ntwo\r\nthree";
+    /// let v: Vec<&str> = text.split_inclusive_re(&re).collect();
+    /// assert_eq!(v, ["one\n", "two\r\n", "three"]);
     /// # }
     /// ```
-    fn split_inclusive<'r, 't>(&'r self, text: &'t str) -> SplitInclusive<'r, 't> {
-        SplitInclusive {
-            finder: self.find_iter(text),
-            last: 0,
-            text,
-        }
+    fn split_inclusive_re<'r, 't>(&'t self, re: &'r Regex) -> SplitInclusive<'r, 't> {
+        re.split_inclusive(self)
     }
 
-    /// Returns an iterator of substrings of `text` separated by a match of the
-    /// regular expression. Differs from the iterator produced by split in that
-    /// split_inclusive leaves the matched part as the terminator of the
-    /// substring.
+    /// Splits the text on matches of `re`, like
+    /// `re.split_inclusive_left(text)`, but with the text as the receiver.
     ///
-    /// This method will *not* copy the text given.
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::Regex;
+    /// # use crate::regex_split::StrSplitExt;
+    /// # fn main() {
+    /// let re = Regex::new(r"(?m)^-").unwrap();
+    /// let text = "fruits:\n-apple\n-pear";
+    /// let v: Vec<&str> = text.split_inclusive_left_re(&re).collect();
+    /// assert_eq!(v, ["fruits:\n", "-apple\n", "-pear"]);
+    /// # }
+    /// ```
+    fn split_inclusive_left_re<'r, 't>(&'t self, re: &'r Regex) -> SplitInclusiveLeft<'r, 't> {
+        re.split_inclusive_left(self)
+    }
+
+    /// Splits the text from the end on matches of `re`, like
+    /// `re.rsplitn_inclusive(text, n)`, but with the text as the receiver.
     ///
     /// # Example
     ///
     /// ```rust
     /// # use regex::Regex;
-    /// # use crate::regex_split::RegexSplit;
+    /// # use crate::regex_split::StrSplitExt;
     /// # fn main() {
-    /// let re = Regex::new(r"\r?\n").unwrap();
-    /// let text = "Mary had a little lamb\nlittle lamb\r\nlittle lamb.";
-    /// let v: Vec<&str> = re.split_inclusive_left(text).collect();
-    /// assert_eq!(v, [
-    ///     "Mary had a little lamb",
-    ///     "\nlittle lamb",
-    ///     "\r\nlittle lamb.",
-    /// ]);
+    /// let re = Regex::new(r"/").unwrap();
+    /// let text = "usr/local/bin";
+    /// let v: Vec<&str> = text.rsplitn_inclusive_re(&re, 2).collect();
+    /// assert_eq!(v, ["bin", "usr/local/"]);
     /// # }
     /// ```
-    fn split_inclusive_left<'r, 't>(&'r self, text: &'t str) -> SplitInclusiveLeft<'r, 't> {
-        SplitInclusiveLeft {
-            finder: self.find_iter(text),
-            last: 0,
-            text,
-        }
+    fn rsplitn_inclusive_re<'r, 't>(&'t self, re: &'r Regex, n: usize) -> RSplitNInclusive<'r, 't> {
+        re.rsplitn_inclusive(self, n)
     }
 }
@@ -0,0 +1,514 @@
+//! Finding delimiters with something other than the regex crate, such as
+//! another regex engine or a scanner written by hand.
+//!
+//! A [`Splitter`](crate::Splitter) or [`bytes::Splitter`](crate::bytes::Splitter)
+//! finds its delimiters through the [`Matcher`] trait, which both kinds of
+//! `Regex` implement. Anything else that implements it can be split on with
+//! every option the splitters have, without this crate depending on it. So
+//! can a plain closure, made into a `Matcher` with [`from_fn`].
+
+#[cfg(feature = "fancy-regex")]
+use std::cell::RefCell;
+use std::convert::Infallible;
+use std::fmt;
+use std::ops::Range;
+
+use regex_automata::{meta, Anchored, Input};
+
+use crate::haystack::Haystack;
+
+/// Finds delimiters in a haystack, `str` or `[u8]`, one at a time.
+///
+/// # Example
+///
+/// ```rust
+/// # use std::ops::Range;
+/// # use crate::regex_split::matcher::Matcher;
+/// # use crate::regex_split::{Keep, Splitter};
+/// // Splits on runs of ASCII digits.
+/// struct Digits;
+///
+/// impl Matcher<str> for Digits {
+///     fn find_at(&self, haystack: &str, at: usize) -> Option<Range<usize>> {
+///         let start = at + haystack[at..].find(|c: char| c.is_ascii_digit())?;
+///         let len = haystack[start..]
+///             .find(|c: char| !c.is_ascii_digit())
+///             .unwrap_or(haystack.len() - start);
+///         Some(start..start + len)
+///     }
+/// }
+///
+/// # fn main() {
+/// let v: Vec<&str> = Splitter::with_matcher(&Digits)
+///     .keep(Keep::Neither)
+///     .split("a1b22c")
+///     .collect();
+/// assert_eq!(v, ["a", "b", "c"]);
+/// # }
+/// ```
+pub trait Matcher<H: ?Sized> {
+    /// Returns the byte range of the leftmost match in `haystack` that
+    /// begins at or after `at`, if there is one. The whole haystack is
+    /// given, so that a matcher can look at the text before `at`, as anchors
+    /// and word boundaries do. For a `str`, `at` is always on a character
+    /// boundary.
+    fn find_at(&self, haystack: &H, at: usize) -> Option<Range<usize>>;
+}
+
+/// A [`Matcher`] whose searches can fail, such as one that gives up on a
+/// pattern that backtracks too much. A failed search finds nothing, and the
+/// matcher keeps the error for [`Splitter::try_split`](crate::Splitter#method.try_split)
+/// to take and yield.
+pub trait FallibleMatcher<H: ?Sized>: Matcher<H> {
+    /// The error a failed search reports.
+    type Error;
+
+    /// Takes the error that stopped a search, if one has, so that the
+    /// matcher can search again.
+    fn take_error(&self) -> Option<Self::Error>;
+}
+
+impl Matcher<str> for regex::Regex {
+    fn find_at(&self, haystack: &str, at: usize) -> Option<Range<usize>> {
+        regex::Regex::find_at(self, haystack, at).map(|m| m.range())
+    }
+}
+
+impl Matcher<[u8]> for regex::bytes::Regex {
+    fn find_at(&self, haystack: &[u8], at: usize) -> Option<Range<usize>> {
+        regex::bytes::Regex::find_at(self, haystack, at).map(|m| m.range())
+    }
+}
+
+// The regex crate's searches never fail, but implementing this lets them be
+// given to code written for matchers that can.
+impl FallibleMatcher<str> for regex::Regex {
+    type Error = Infallible;
+
+    fn take_error(&self) -> Option<Infallible> {
+        None
+    }
+}
+
+impl FallibleMatcher<[u8]> for regex::bytes::Regex {
+    type Error = Infallible;
+
+    fn take_error(&self) -> Option<Infallible> {
+        None
+    }
+}
+
+impl Matcher<str> for meta::Regex {
+    fn find_at(&self, haystack: &str, at: usize) -> Option<Range<usize>> {
+        let input = Input::new(haystack).range(at..);
+        self.find(input).map(|m| m.range())
+    }
+}
+
+impl Matcher<[u8]> for meta::Regex {
+    fn find_at(&self, haystack: &[u8], at: usize) -> Option<Range<usize>> {
+        let input = Input::new(haystack).range(at..);
+        self.find(input).map(|m| m.range())
+    }
+}
+
+/// A `regex_automata::meta::Regex` searched with options set on the `Input`
+/// of each search, which the regex crate doesn't expose: anchoring, and
+/// stopping at the earliest match rather than the leftmost-first one.
+///
+/// Splitting a `str` needs matches that begin and end on character
+/// boundaries, which a meta regex only promises with UTF-8 mode left on.
+///
+/// # Example
+///
+/// ```rust
+/// # use regex_automata::meta::Regex;
+/// # use crate::regex_split::matcher::MetaMatcher;
+/// # use crate::regex_split::Splitter;
+/// # fn main() {
+/// let re = Regex::new(r"a+").unwrap();
+/// let v: Vec<&str> = Splitter::with_matcher(&re).split("baaab").collect();
+/// assert_eq!(v, ["baaa", "b"]);
+///
+/// // The earliest match ends as soon as one `a` has been seen.
+/// let earliest = MetaMatcher::new(&re).earliest(true);
+/// let v: Vec<&str> = Splitter::with_matcher(&earliest).split("baaab").collect();
+/// assert_eq!(v, ["ba", "a", "a", "b"]);
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct MetaMatcher<'r> {
+    regex: &'r meta::Regex,
+    anchored: Anchored,
+    earliest: bool,
+}
+
+impl<'r> MetaMatcher<'r> {
+    /// Wraps `regex`, searched unanchored for leftmost-first matches, the
+    /// same as the regex on its own.
+    pub fn new(regex: &'r meta::Regex) -> Self {
+        Self {
+            regex,
+            anchored: Anchored::No,
+            earliest: false,
+        }
+    }
+
+    /// Sets how each search is anchored. With `Anchored::Yes`, a delimiter
+    /// has to begin right where the last one ended, or where splitting began,
+    /// so the splitting stops at the first text that isn't one.
+    pub fn anchored(mut self, anchored: Anchored) -> Self {
+        self.anchored = anchored;
+        self
+    }
+
+    /// Sets whether each search stops as soon as it sees a match, which may
+    /// end before the leftmost-first match would. Off by default.
+    pub fn earliest(mut self, earliest: bool) -> Self {
+        self.earliest = earliest;
+        self
+    }
+
+    fn input<'h>(&self, haystack: &'h [u8], at: usize) -> Input<'h> {
+        Input::new(haystack)
+            .range(at..)
+            .anchored(self.anchored)
+            .earliest(self.earliest)
+    }
+}
+
+impl<'r> Matcher<str> for MetaMatcher<'r> {
+    fn find_at(&self, haystack: &str, at: usize) -> Option<Range<usize>> {
+        let input = self.input(haystack.as_bytes(), at);
+        self.regex.find(input).map(|m| m.range())
+    }
+}
+
+impl<'r> Matcher<[u8]> for MetaMatcher<'r> {
+    fn find_at(&self, haystack: &[u8], at: usize) -> Option<Range<usize>> {
+        self.regex.find(self.input(haystack, at)).map(|m| m.range())
+    }
+}
+
+/// With the `regex-lite` feature, a `regex_lite::Regex` can be split on
+/// with a [`Splitter`](crate::Splitter), for programs that use the smaller
+/// engine everywhere else.
+///
+/// # Example
+///
+/// ```rust
+/// # use crate::regex_split::{Keep, Splitter};
+/// # fn main() {
+/// let re = regex_lite::Regex::new(r"\r?\n").unwrap();
+/// let v: Vec<&str> = Splitter::with_matcher(&re)
+///     .keep(Keep::Left)
+///     .split("one\r\ntwo\nthree")
+///     .collect();
+/// assert_eq!(v, ["one", "\r\ntwo", "\nthree"]);
+/// # }
+/// ```
+#[cfg(feature = "regex-lite")]
+impl Matcher<str> for regex_lite::Regex {
+    fn find_at(&self, haystack: &str, at: usize) -> Option<Range<usize>> {
+        regex_lite::Regex::find_at(self, haystack, at).map(|m| m.range())
+    }
+}
+
+/// With the `fancy-regex` feature, a `fancy_regex::Regex` wrapped in a
+/// `FancyMatcher` can be split on with a [`Splitter`](crate::Splitter), for
+/// delimiters that need lookaround or backreferences.
+///
+/// A fancy regex backtracks, and a search can fail partway through, as when
+/// it runs past its backtrack limit. Rather than panic, the matcher keeps the
+/// error and finds no more delimiters, so `split` ends with the rest of the
+/// text unsplit. Split with [`try_split`](crate::Splitter::try_split) to get
+/// the error as the last item instead, or check
+/// [`error`](FancyMatcher::error) once splitting is done.
+///
+/// # Example
+///
+/// ```rust
+/// # use fancy_regex::Regex;
+/// # use crate::regex_split::matcher::FancyMatcher;
+/// # use crate::regex_split::{Keep, Splitter};
+/// # fn main() {
+/// // Commas, but not those inside double quotes.
+/// let re = Regex::new(r#",(?=(?:[^"]*"[^"]*")*[^"]*$)"#).unwrap();
+/// let matcher = FancyMatcher::new(&re);
+/// let v: Vec<&str> = Splitter::with_matcher(&matcher)
+///     .keep(Keep::Neither)
+///     .split(r#"a,"b,c",d"#)
+///     .collect();
+/// assert_eq!(v, ["a", r#""b,c""#, "d"]);
+/// assert!(matcher.error().is_none());
+/// # }
+/// ```
+#[cfg(feature = "fancy-regex")]
+#[derive(Clone, Debug)]
+pub struct FancyMatcher<'r> {
+    regex: &'r fancy_regex::Regex,
+    error: RefCell<Option<fancy_regex::Error>>,
+}
+
+#[cfg(feature = "fancy-regex")]
+impl<'r> FancyMatcher<'r> {
+    /// Wraps `regex`, with no error recorded.
+    pub fn new(regex: &'r fancy_regex::Regex) -> Self {
+        Self {
+            regex,
+            error: RefCell::new(None),
+        }
+    }
+
+    /// Returns the error that stopped a search, if one has. Once a search
+    /// has failed, every later one finds nothing until the error is taken.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use fancy_regex::RegexBuilder;
+    /// # use crate::regex_split::matcher::FancyMatcher;
+    /// # use crate::regex_split::{SplitError, Splitter};
+    /// # fn main() {
+    /// let re = RegexBuilder::new(r"(a|aa)+\1b")
+    ///     .backtrack_limit(100)
+    ///     .build()
+    ///     .unwrap();
+    /// let matcher = FancyMatcher::new(&re);
+    /// let text = "aaaaaaaaaaaaaaaaaaaaaaaa!";
+    /// let v: Vec<&str> = Splitter::with_matcher(&matcher).split(text).collect();
+    /// assert_eq!(v, [text]);
+    /// assert!(matcher.error().is_some());
+    ///
+    /// let mut iter = Splitter::with_matcher(&matcher).try_split(text);
+    /// assert!(matches!(iter.next(), Some(Err(SplitError::Search(_)))));
+    /// assert!(iter.next().is_none());
+    /// # }
+    /// ```
+    pub fn error(&self) -> Option<fancy_regex::Error> {
+        self.error.borrow().clone()
+    }
+
+    /// Takes the error that stopped a search, if one has, so that the
+    /// matcher can search again.
+    pub fn take_error(&self) -> Option<fancy_regex::Error> {
+        self.error.borrow_mut().take()
+    }
+}
+
+#[cfg(feature = "fancy-regex")]
+impl<'r> FallibleMatcher<str> for FancyMatcher<'r> {
+    type Error = fancy_regex::Error;
+
+    fn take_error(&self) -> Option<fancy_regex::Error> {
+        FancyMatcher::take_error(self)
+    }
+}
+
+#[cfg(feature = "fancy-regex")]
+impl<'r> Matcher<str> for FancyMatcher<'r> {
+    fn find_at(&self, haystack: &str, at: usize) -> Option<Range<usize>> {
+        if self.error.borrow().is_some() {
+            return None;
+        }
+        match self.regex.find_from_pos(haystack, at) {
+            Ok(m) => m.map(|m| m.range()),
+            Err(e) => {
+                *self.error.borrow_mut() = Some(e);
+                None
+            }
+        }
+    }
+}
+
+/// With the `onig` feature, an Oniguruma `onig::Regex` can be split on with
+/// a [`Splitter`](crate::Splitter), for patterns written for Ruby or for
+/// TextMate grammars.
+///
+/// Oniguruma backtracks, and takes the first match its search reaches at
+/// the leftmost place one begins, trying alternatives and repetitions in the
+/// order the pattern gives them. That is the same match the regex crate's
+/// leftmost-first semantics pick for any pattern both can compile, but the
+/// Ruby syntax an `onig::Regex` uses by default reads some patterns
+/// differently: `^` and `$` always match at line boundaries, without `(?m)`,
+/// and `(?m)` makes `.` match a newline instead. Compile the pattern for
+/// another syntax with `Regex::with_options` to change that.
+///
+/// The regex has to be compiled for UTF-8, which it is by default. Searches
+/// panic where Oniguruma reports an error, as `Regex::search_with_options`
+/// does, such as when a search runs past its retry limit.
+///
+/// # Example
+///
+/// ```rust
+/// # use crate::regex_split::{Keep, Splitter};
+/// # fn main() {
+/// let re = onig::Regex::new(r"^-").unwrap();
+/// let v: Vec<&str> = Splitter::with_matcher(&re)
+///     .keep(Keep::Left)
+///     .split("List:\n-apple\n-pear")
+///     .collect();
+/// assert_eq!(v, ["List:\n", "-apple\n", "-pear"]);
+/// # }
+/// ```
+#[cfg(feature = "onig")]
+impl Matcher<str> for onig::Regex {
+    fn find_at(&self, haystack: &str, at: usize) -> Option<Range<usize>> {
+        let mut region = onig::Region::new();
+        self.search_with_options(
+            haystack,
+            at,
+            haystack.len(),
+            onig::SearchOptions::SEARCH_OPTION_NONE,
+            Some(&mut region),
+        )?;
+        region.pos(0).map(|(start, end)| start..end)
+    }
+}
+
+/// Makes a [`Matcher`] of a closure that finds the next delimiter in a
+/// `str`, as `Matcher::find_at` does, for boundaries too simple to need a
+/// regex.
+///
+/// # Example
+///
+/// ```rust
+/// # use crate::regex_split::matcher;
+/// # use crate::regex_split::Splitter;
+/// # fn main() {
+/// // Cuts the text into pieces of four bytes, on character boundaries.
+/// let every_four = matcher::from_fn(|text: &str, at: usize| {
+///     let mut end = (at + 4).min(text.len());
+///     while !text.is_char_boundary(end) {
+///         end += 1;
+///     }
+///     (end < text.len()).then_some(end..end)
+/// });
+/// let v: Vec<&str> = Splitter::with_matcher(&every_four).split("abcdéfghij").collect();
+/// assert_eq!(v, ["abcd", "éfg", "hij"]);
+/// # }
+/// ```
+pub fn from_fn<F>(f: F) -> FromFn<F>
+where
+    F: Fn(&str, usize) -> Option<Range<usize>>,
+{
+    FromFn(f)
+}
+
+/// Makes a [`Matcher`] of a closure that finds the next delimiter in a byte
+/// string, as [`from_fn`] does for a `str`.
+///
+/// # Example
+///
+/// ```rust
+/// # use crate::regex_split::matcher;
+/// # use crate::regex_split::{bytes::Splitter, Keep};
+/// # fn main() {
+/// let nul = matcher::from_fn_bytes(|text: &[u8], at: usize| {
+///     let i = at + text[at..].iter().position(|&b| b == 0)?;
+///     Some(i..i + 1)
+/// });
+/// let v: Vec<&[u8]> = Splitter::with_matcher(&nul)
+///     .keep(Keep::Neither)
+///     .split(b"one\0two\0three")
+///     .collect();
+/// assert_eq!(v, [&b"one"[..], &b"two"[..], &b"three"[..]]);
+/// # }
+/// ```
+pub fn from_fn_bytes<F>(f: F) -> FromFn<F>
+where
+    F: Fn(&[u8], usize) -> Option<Range<usize>>,
+{
+    FromFn(f)
+}
+
+/// A [`Matcher`] that calls a closure to find each delimiter. Made with
+/// [`from_fn`] or [`from_fn_bytes`].
+#[derive(Clone, Copy)]
+pub struct FromFn<F>(F);
+
+impl<F> fmt::Debug for FromFn<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("FromFn").finish_non_exhaustive()
+    }
+}
+
+impl<F> Matcher<str> for FromFn<F>
+where
+    F: Fn(&str, usize) -> Option<Range<usize>>,
+{
+    fn find_at(&self, haystack: &str, at: usize) -> Option<Range<usize>> {
+        (self.0)(haystack, at)
+    }
+}
+
+impl<F> Matcher<[u8]> for FromFn<F>
+where
+    F: Fn(&[u8], usize) -> Option<Range<usize>>,
+{
+    fn find_at(&self, haystack: &[u8], at: usize) -> Option<Range<usize>> {
+        (self.0)(haystack, at)
+    }
+}
+
+// Iterates over the ranges of successive non-overlapping matches of a
+// `Matcher`, the way `MatchesAt` does for a regex, beginning at an offset
+// into the text.
+#[derive(Debug)]
+pub(crate) struct MatcherRanges<'r, 't, M: ?Sized, H: ?Sized> {
+    matcher: &'r M,
+    pub(crate) text: &'t H,
+    pub(crate) at: usize,
+    last_end: Option<usize>,
+}
+
+impl<'r, 't, M: ?Sized, H: ?Sized> MatcherRanges<'r, 't, M, H> {
+    pub(crate) fn new(matcher: &'r M, text: &'t H, at: usize) -> Self {
+        Self {
+            matcher,
+            text,
+            at,
+            last_end: None,
+        }
+    }
+}
+
+// Not derived, which would need `M: Clone`, though only a reference is held.
+impl<'r, 't, M: ?Sized, H: ?Sized> Clone for MatcherRanges<'r, 't, M, H> {
+    fn clone(&self) -> Self {
+        Self {
+            matcher: self.matcher,
+            text: self.text,
+            at: self.at,
+            last_end: self.last_end,
+        }
+    }
+}
+
+impl<'r, 't, M, H> Iterator for MatcherRanges<'r, 't, M, H>
+where
+    M: Matcher<H> + ?Sized,
+    H: Haystack + ?Sized,
+{
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.at > self.text.len() {
+                return None;
+            }
+            let m = self.matcher.find_at(self.text, self.at)?;
+
+            // An empty match right where the last match ended isn't reported,
+            // same as with `find_iter`; look again from the next character,
+            // or the next byte of a byte string.
+            if m.is_empty() && Some(m.end) == self.last_end {
+                self.at += self.text.step_at(self.at);
+                continue;
+            }
+            self.at = m.end;
+            self.last_end = Some(m.end);
+            return Some(m);
+        }
+    }
+}
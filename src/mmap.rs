@@ -0,0 +1,120 @@
+//! Splitting a file mapped into memory, so that a file of many gigabytes can
+//! be split without reading it into a `String` first. Only available with the
+//! `memmap2` feature.
+
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use memmap2::Mmap;
+use regex::bytes::Regex;
+
+use crate::bytes::{RegexSplit, SplitInclusive, SplitInclusiveUtf8};
+use crate::stream::{Bom, UTF16_BOMS, UTF8_BOM};
+
+/// Maps the file at `path` into memory, to be split into segments delimited
+/// by a match of `regex` inclusive of the match.
+///
+/// # Safety
+///
+/// The segments borrow straight from the file's pages, so the file must not
+/// be modified or truncated, by this process or another, while the
+/// [`SplitFile`] is alive. See `memmap2::Mmap::map`.
+///
+/// # Example
+///
+/// ```rust
+/// # use regex::bytes::Regex;
+/// # use crate::regex_split::mmap::split_file;
+/// # fn main() -> std::io::Result<()> {
+/// # let path = std::env::temp_dir().join("regex-split-split-file.log");
+/// # std::fs::write(&path, "one\ntwo\nthree")?;
+/// let re = Regex::new(r"\n").unwrap();
+/// let file = unsafe { split_file(&path, &re)? };
+/// let v: Vec<&[u8]> = file.iter().collect();
+/// assert_eq!(v, [&b"one\n"[..], &b"two\n"[..], &b"three"[..]]);
+/// # Ok(())
+/// # }
+/// ```
+pub unsafe fn split_file<P: AsRef<Path>>(path: P, regex: &Regex) -> io::Result<SplitFile> {
+    let file = File::open(path)?;
+    Ok(SplitFile {
+        map: Mmap::map(&file)?,
+        regex: regex.clone(),
+        start: 0,
+    })
+}
+
+/// A file mapped into memory along with the regular expression to split it
+/// with, which the iterators of its segments borrow from. The map is
+/// unmapped when it is dropped.
+#[derive(Debug)]
+pub struct SplitFile {
+    map: Mmap,
+    regex: Regex,
+
+    // Where the text begins in the map, past any byte order mark dropped.
+    start: usize,
+}
+
+impl SplitFile {
+    /// Sets what to do with a byte order mark at the start of the file. See
+    /// [`Bom`]. The file isn't decoded, so under [`Bom::Honor`] a UTF-16 one
+    /// is an error of kind `InvalidData`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::io::ErrorKind;
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::{mmap::split_file, stream::Bom};
+    /// # fn main() -> std::io::Result<()> {
+    /// # let path = std::env::temp_dir().join("regex-split-bom.txt");
+    /// # std::fs::write(&path, b"\xFF\xFEa\0\n\0")?;
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let file = unsafe { split_file(&path, &re)? };
+    /// let e = file.bom(Bom::Honor).unwrap_err();
+    /// assert_eq!(e.kind(), ErrorKind::InvalidData);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bom(mut self, bom: Bom) -> io::Result<Self> {
+        if bom == Bom::Honor && UTF16_BOMS.iter().any(|mark| self.map.starts_with(mark)) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "file starts with a UTF-16 byte order mark but isn't decoded",
+            ));
+        }
+        self.start = match bom {
+            Bom::Strip | Bom::Honor if self.map.starts_with(UTF8_BOM) => UTF8_BOM.len(),
+            _ => 0,
+        };
+        Ok(self)
+    }
+
+    /// The contents of the file, short of any byte order mark dropped.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.map[self.start..]
+    }
+
+    /// Returns an iterator of the segments of the file, with the match at
+    /// the end of each.
+    pub fn iter(&self) -> SplitInclusive<'_, '_> {
+        self.regex.split_inclusive(self.as_bytes())
+    }
+
+    /// Returns an iterator of the segments of the file as `&str`s, each
+    /// checked for valid UTF-8 when it is yielded.
+    pub fn iter_utf8(&self) -> SplitInclusiveUtf8<'_, '_> {
+        self.regex.split_inclusive_utf8(self.as_bytes())
+    }
+}
+
+impl<'a> IntoIterator for &'a SplitFile {
+    type Item = &'a [u8];
+    type IntoIter = SplitInclusive<'a, 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
@@ -0,0 +1,155 @@
+//! Splitting for `OsStr`, such as environment variables and path lists, with
+//! a `regex::bytes::Regex`.
+//!
+//! The regex searches the platform's encoding of the string: its raw bytes on
+//! Unix and WTF-8 on Windows. An `OsStr` may only be cut next to valid UTF-8,
+//! so a match splits the text only if it is non-empty and valid UTF-8 itself.
+//! Other matches are passed over.
+
+use std::ffi::OsStr;
+use std::iter::FusedIterator;
+use std::str;
+
+use regex::bytes::Regex;
+
+use crate::haystack::MatchesAt;
+
+pub trait RegexSplit {
+    fn split_inclusive<'r, 't>(&'r self, text: &'t OsStr) -> SplitInclusive<'r, 't>;
+    fn split_inclusive_left<'r, 't>(&'r self, text: &'t OsStr) -> SplitInclusiveLeft<'r, 't>;
+}
+
+/// Yields all substrings of an `OsStr` delimited by a regular expression
+/// match inclusive of the match.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the string being split.
+#[derive(Clone, Debug)]
+pub struct SplitInclusive<'r, 't> {
+    finder: MatchesAt<'r, 't, [u8]>,
+    last: Option<usize>,
+    text: &'t [u8],
+}
+
+impl<'r, 't> Iterator for SplitInclusive<'r, 't> {
+    type Item = &'t OsStr;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let last = self.last?;
+        match self.finder.find(|m| can_split(m.as_bytes())) {
+            None => {
+                self.last = None;
+                Some(to_os_str(&self.text[last..]))
+            }
+            Some(m) => {
+                self.last = Some(m.end());
+                Some(to_os_str(&self.text[last..m.end()]))
+            }
+        }
+    }
+}
+
+impl<'r, 't> FusedIterator for SplitInclusive<'r, 't> {}
+
+/// Yields all substrings of an `OsStr` delimited by a regular expression
+/// match, with the match at the front of each substring.
+///
+/// `'r` is the lifetime of the compiled regular expression and `'t` is the
+/// lifetime of the string being split.
+#[derive(Clone, Debug)]
+pub struct SplitInclusiveLeft<'r, 't> {
+    finder: MatchesAt<'r, 't, [u8]>,
+    last: Option<usize>,
+    text: &'t [u8],
+}
+
+impl<'r, 't> Iterator for SplitInclusiveLeft<'r, 't> {
+    type Item = &'t OsStr;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let last = self.last?;
+        match self.finder.find(|m| can_split(m.as_bytes())) {
+            None => {
+                self.last = None;
+                Some(to_os_str(&self.text[last..]))
+            }
+            Some(m) => {
+                self.last = Some(m.start());
+                Some(to_os_str(&self.text[last..m.start()]))
+            }
+        }
+    }
+}
+
+impl<'r, 't> FusedIterator for SplitInclusiveLeft<'r, 't> {}
+
+// Whether the encoded bytes of an `OsStr` may be cut on either side of a
+// match. That is allowed next to any non-empty run of valid UTF-8.
+fn can_split(matched: &[u8]) -> bool {
+    !matched.is_empty() && str::from_utf8(matched).is_ok()
+}
+
+fn to_os_str(bytes: &[u8]) -> &OsStr {
+    // SAFETY: `bytes` came from `OsStr::as_encoded_bytes` and was only cut
+    // at the ends of matches that passed `can_split`.
+    unsafe { OsStr::from_encoded_bytes_unchecked(bytes) }
+}
+
+impl RegexSplit for Regex {
+    /// Returns an iterator of substrings of `text` separated by a match of the
+    /// regular expression, with the match at the end of each substring.
+    /// Matches that are empty or aren't valid UTF-8 don't split the text.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::ffi::OsStr;
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::os_str::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r":").unwrap();
+    /// let path = OsStr::new("/usr/bin:/bin");
+    /// let v: Vec<&OsStr> = re.split_inclusive(path).collect();
+    /// assert_eq!(v, ["/usr/bin:", "/bin"]);
+    /// # }
+    /// ```
+    fn split_inclusive<'r, 't>(&'r self, text: &'t OsStr) -> SplitInclusive<'r, 't> {
+        let text = text.as_encoded_bytes();
+        SplitInclusive {
+            finder: MatchesAt::new(self, text, 0),
+            last: Some(0),
+            text,
+        }
+    }
+
+    /// Returns an iterator of substrings of `text` separated by a match of the
+    /// regular expression, with the match at the start of each substring.
+    /// Matches that are empty or aren't valid UTF-8 don't split the text.
+    ///
+    /// This method will *not* copy the text given.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::ffi::OsStr;
+    /// # use std::path::Path;
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::os_str::RegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"/").unwrap();
+    /// let path = Path::new("usr/local/bin");
+    /// let v: Vec<&OsStr> = re.split_inclusive_left(path.as_os_str()).collect();
+    /// assert_eq!(v, ["usr", "/local", "/bin"]);
+    /// # }
+    /// ```
+    fn split_inclusive_left<'r, 't>(&'r self, text: &'t OsStr) -> SplitInclusiveLeft<'r, 't> {
+        let text = text.as_encoded_bytes();
+        SplitInclusiveLeft {
+            finder: MatchesAt::new(self, text, 0),
+            last: Some(0),
+            text,
+        }
+    }
+}
@@ -0,0 +1,457 @@
+//! Splitting a large text on several threads at once.
+//!
+//! The text is cut into shards, and the matches in each shard are found on a
+//! thread of its own, each search starting at the front of its shard. A match
+//! that straddles the cut between two shards throws the search in the second
+//! shard out of step with the one a single thread would run, so the matches
+//! are then stitched together in order, searching again from the end of each
+//! straddling match until the search falls back into step with the matches
+//! the shard's thread found. The substrings come out the same as with
+//! `split_inclusive` on a single thread.
+//!
+//! With the `rayon` feature, `ParRegexSplit` gives the substrings as an
+//! indexed `ParallelIterator` instead, in order, searching the shards on the
+//! pool's threads rather than threads of their own.
+
+use std::ops::Range;
+use std::thread;
+
+#[cfg(feature = "rayon")]
+use rayon::iter::plumbing::{bridge, Consumer, Producer, ProducerCallback, UnindexedConsumer};
+#[cfg(feature = "rayon")]
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use regex::Regex;
+
+use crate::haystack::Haystack;
+
+#[cfg(feature = "rayon")]
+pub trait ParRegexSplit {
+    fn par_split_inclusive<'t>(&self, text: &'t str) -> ParSplitInclusive<'t>;
+}
+
+/// Splits `text` into substrings delimited by a match of `regex` inclusive of
+/// the match, searching `shards` pieces of it on as many threads. The
+/// substrings are the ones `split_inclusive` gives, in the same order.
+///
+/// # Example
+///
+/// ```rust
+/// # use regex::Regex;
+/// # use crate::regex_split::RegexSplit;
+/// # use crate::regex_split::parallel;
+/// # fn main() {
+/// let re = Regex::new(r"\r?\n").unwrap();
+/// let text = "GET /\r\nGET /about\r\nGET /contact\nGET /faq";
+/// let v = parallel::split_inclusive(&re, text, 4);
+/// assert_eq!(v, re.split_inclusive(text).collect::<Vec<_>>());
+/// # }
+/// ```
+pub fn split_inclusive<'t>(regex: &Regex, text: &'t str, shards: usize) -> Vec<&'t str> {
+    let cuts = cuts(text.len(), shards, |at| text.is_char_boundary(at));
+    let shards = split_shards(text.len(), &cuts, |at| {
+        regex.find_at(text, at).map(|m| m.range())
+    });
+    Segments::new(text, &shards).collect()
+}
+
+/// Splits a byte string, as [`split_inclusive`] does a `&str`.
+///
+/// # Example
+///
+/// ```rust
+/// # use regex::bytes::Regex;
+/// # use crate::regex_split::bytes::RegexSplit;
+/// # use crate::regex_split::parallel;
+/// # fn main() {
+/// let re = Regex::new(r"\n").unwrap();
+/// let text = &b"one\ntwo\nthree\nfour"[..];
+/// let v = parallel::split_inclusive_bytes(&re, text, 3);
+/// assert_eq!(v, re.split_inclusive(text).collect::<Vec<_>>());
+/// # }
+/// ```
+pub fn split_inclusive_bytes<'t>(
+    regex: &regex::bytes::Regex,
+    text: &'t [u8],
+    shards: usize,
+) -> Vec<&'t [u8]> {
+    let cuts = cuts(text.len(), shards, |_| true);
+    let shards = split_shards(text.len(), &cuts, |at| {
+        regex.find_at(text, at).map(|m| m.range())
+    });
+    Segments::new(text, &shards).collect()
+}
+
+/// A parallel iterator over the substrings of a text delimited by a regular
+/// expression match inclusive of the match, which keeps their order when
+/// collected or zipped. Only available with the `rayon` feature.
+///
+/// The matches are found up front, one shard of the text for each thread of
+/// the pool, and kept shard by shard, so that the substrings can be divided
+/// among the pool's threads by index along the same lines. Only their
+/// positions are held; the substrings are borrowed from the text.
+#[cfg(feature = "rayon")]
+#[derive(Clone, Debug)]
+pub struct ParSplitInclusive<'t> {
+    text: &'t str,
+    shards: Vec<Shard>,
+    len: usize,
+}
+
+#[cfg(feature = "rayon")]
+impl<'t> ParallelIterator for ParSplitInclusive<'t> {
+    type Item = &'t str;
+
+    fn drive_unindexed<C: UnindexedConsumer<Self::Item>>(self, consumer: C) -> C::Result {
+        bridge(self, consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<'t> IndexedParallelIterator for ParSplitInclusive<'t> {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> C::Result {
+        bridge(self, consumer)
+    }
+
+    fn with_producer<CB: ProducerCallback<Self::Item>>(self, callback: CB) -> CB::Output {
+        callback.callback(Segments::new(self.text, &self.shards))
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl ParRegexSplit for Regex {
+    /// Returns a parallel iterator over the substrings `split_inclusive`
+    /// gives, searching one shard of the text for each thread of the pool.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rayon::prelude::*;
+    /// # use regex::Regex;
+    /// # use crate::regex_split::parallel::ParRegexSplit;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text = "3\n1\n4\n1\n5";
+    /// let v: Vec<u32> = re
+    ///     .par_split_inclusive(text)
+    ///     .map(|line| line.trim_end().parse::<u32>().unwrap() * 2)
+    ///     .collect();
+    /// assert_eq!(v, [6, 2, 8, 2, 10]);
+    /// # }
+    /// ```
+    fn par_split_inclusive<'t>(&self, text: &'t str) -> ParSplitInclusive<'t> {
+        let cuts = cuts(text.len(), rayon::current_num_threads(), |at| {
+            text.is_char_boundary(at)
+        });
+        let find = |at| self.find_at(text, at).map(|m| m.range());
+        let searched = cuts
+            .par_iter()
+            .zip(shard_ends(&cuts))
+            .map(|(&start, end)| Matches::new(start).until(text.len(), end, &find))
+            .collect();
+        let shards = stitch(text.len(), &cuts, searched, find);
+        let len = shards.iter().map(|shard| shard.ends.len()).sum();
+        ParSplitInclusive { text, shards, len }
+    }
+}
+
+// Where each shard begins, cutting the text into even shards at positions
+// `boundary` allows, which are never empty but for an empty text.
+fn cuts(len: usize, shards: usize, boundary: impl Fn(usize) -> bool) -> Vec<usize> {
+    let shards = shards.clamp(1, len.max(1));
+    let mut cuts = vec![0];
+    for i in 1..shards {
+        let mut at = len / shards * i;
+        while !boundary(at) {
+            at += 1;
+        }
+        if at > *cuts.last().unwrap() && at < len {
+            cuts.push(at);
+        }
+    }
+    cuts
+}
+
+// Where the matches found in each shard may begin before: the start of the
+// next shard, or anywhere for the last one.
+fn shard_ends(cuts: &[usize]) -> Vec<usize> {
+    cuts[1..].iter().copied().chain([usize::MAX]).collect()
+}
+
+// Finds the matches in each shard on a thread of its own, then stitches them
+// into the substrings of each shard. `find` is `find_at` on the whole text.
+fn split_shards<F>(len: usize, cuts: &[usize], find: F) -> Vec<Shard>
+where
+    F: Fn(usize) -> Option<Range<usize>> + Sync,
+{
+    let searched = thread::scope(|scope| {
+        let handles: Vec<_> = cuts
+            .iter()
+            .zip(shard_ends(cuts))
+            .map(|(&start, end)| {
+                let find = &find;
+                scope.spawn(move || Matches::new(start).until(len, end, find))
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    });
+    stitch(len, cuts, searched, find)
+}
+
+// Stitches the ends of the matches each shard's search found into the
+// substrings a single search would give, shard by shard.
+fn stitch<F>(len: usize, cuts: &[usize], searched: Vec<Vec<usize>>, find: F) -> Vec<Shard>
+where
+    F: Fn(usize) -> Option<Range<usize>>,
+{
+    let mut matches = Matches::new(0);
+    let mut last = 0;
+    let mut shards: Vec<Shard> = Vec::with_capacity(cuts.len());
+    for ((&start, end), found) in cuts.iter().zip(shard_ends(cuts)).zip(searched) {
+        // The search is in step with the shard's if it hasn't gone past the
+        // start of the shard, as no match begins before it.
+        let ends = if matches.at < start || (matches.at == start && matches.last_end.is_none()) {
+            matches = Matches::after(found.last(), matches);
+            found
+        } else {
+            // Otherwise it is in step again once a match ends where one the
+            // shard's thread found does.
+            // A match beginning in the next shard is left for it to find.
+            let mut ends = Vec::new();
+            loop {
+                let mut next = matches.clone();
+                let m = match next.next(len, &find) {
+                    Some(m) if m.start < end => m,
+                    _ => break,
+                };
+                matches = next;
+                ends.push(m.end);
+                if let Ok(i) = found.binary_search(&m.end) {
+                    let rest = &found[i + 1..];
+                    matches = Matches::after(rest.last(), matches);
+                    ends.extend_from_slice(rest);
+                    break;
+                }
+            }
+            ends
+        };
+        let start = last;
+        last = ends.last().copied().unwrap_or(last);
+        shards.push(Shard { start, ends });
+    }
+
+    // The text after the last match makes the last substring.
+    shards.last_mut().unwrap().ends.push(len);
+    shards
+}
+
+// The substrings that end in one shard of the text, which are the ones
+// ended by a match found in it, and for the last shard the rest of the text
+// too. The first begins where the last one of an earlier shard ends.
+#[derive(Clone, Debug)]
+struct Shard {
+    start: usize,
+    ends: Vec<usize>,
+}
+
+impl Shard {
+    fn segment(&self, i: usize) -> Range<usize> {
+        let start = if i == 0 { self.start } else { self.ends[i - 1] };
+        start..self.ends[i]
+    }
+}
+
+// The substrings of a run of shards, from the `front`th one of the first
+// shard up to, but not including, the `back`th one of the last. It is both
+// the producer that rayon divides among its threads, splitting it at any
+// index, and the iterator each thread runs through its part with.
+#[derive(Debug)]
+struct Segments<'p, 't, H: ?Sized> {
+    text: &'t H,
+    shards: &'p [Shard],
+    front: usize,
+    back: usize,
+    len: usize,
+}
+
+// Not derived, which would need `H: Copy`, though only a reference is held.
+impl<'p, 't, H: ?Sized> Clone for Segments<'p, 't, H> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'p, 't, H: ?Sized> Copy for Segments<'p, 't, H> {}
+
+impl<'p, 't, H: Haystack + ?Sized> Segments<'p, 't, H> {
+    fn new(text: &'t H, shards: &'p [Shard]) -> Self {
+        Self {
+            text,
+            shards,
+            front: 0,
+            back: shards.last().map_or(0, |shard| shard.ends.len()),
+            len: shards.iter().map(|shard| shard.ends.len()).sum(),
+        }
+    }
+}
+
+impl<'p, 't, H: Haystack + ?Sized> Iterator for Segments<'p, 't, H> {
+    type Item = &'t H;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        loop {
+            let [shard, rest @ ..] = self.shards else {
+                unreachable!("substrings left without a shard");
+            };
+            let end = if rest.is_empty() {
+                self.back
+            } else {
+                shard.ends.len()
+            };
+            if self.front < end {
+                self.front += 1;
+                self.len -= 1;
+                return Some(self.text.slice(shard.segment(self.front - 1)));
+            }
+            self.shards = rest;
+            self.front = 0;
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'p, 't, H: Haystack + ?Sized> DoubleEndedIterator for Segments<'p, 't, H> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        loop {
+            let [rest @ .., shard] = self.shards else {
+                unreachable!("substrings left without a shard");
+            };
+            let start = if rest.is_empty() { self.front } else { 0 };
+            if self.back > start {
+                self.back -= 1;
+                self.len -= 1;
+                return Some(self.text.slice(shard.segment(self.back)));
+            }
+            self.shards = rest;
+            self.back = rest.last().map_or(0, |shard| shard.ends.len());
+        }
+    }
+}
+
+impl<'p, 't, H: Haystack + ?Sized> ExactSizeIterator for Segments<'p, 't, H> {}
+
+#[cfg(feature = "rayon")]
+impl<'p, 't> Producer for Segments<'p, 't, str> {
+    type Item = &'t str;
+    type IntoIter = Self;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self
+    }
+
+    // Splits inside the shard the `index`th substring falls in, which both
+    // parts keep a share of.
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let mut shard = 0;
+        let mut at = self.front + index;
+        while shard + 1 < self.shards.len() && at > self.shards[shard].ends.len() {
+            at -= self.shards[shard].ends.len();
+            shard += 1;
+        }
+        let left = Self {
+            shards: &self.shards[..=shard],
+            back: at,
+            len: index,
+            ..self
+        };
+        let right = Self {
+            shards: &self.shards[shard..],
+            front: at,
+            len: self.len - index,
+            ..self
+        };
+        (left, right)
+    }
+}
+
+// Where the search for successive non-overlapping matches has got to, like
+// `MatchesAt`, for the stitching to tell when two searches are in step.
+#[derive(Clone, Debug)]
+struct Matches {
+    at: usize,
+    last_end: Option<usize>,
+}
+
+impl Matches {
+    fn new(at: usize) -> Self {
+        Self { at, last_end: None }
+    }
+
+    // The search after a match ending at `end`, if there is one, or else
+    // `matches`.
+    fn after(end: Option<&usize>, matches: Self) -> Self {
+        match end {
+            Some(&end) => Self {
+                at: end,
+                last_end: Some(end),
+            },
+            None => matches,
+        }
+    }
+
+    fn next<F>(&mut self, len: usize, find: &F) -> Option<Range<usize>>
+    where
+        F: Fn(usize) -> Option<Range<usize>>,
+    {
+        loop {
+            if self.at > len {
+                return None;
+            }
+            let m = find(self.at)?;
+
+            // An empty match right where the last match ended isn't reported,
+            // same as with `find_iter`; look again one step further on.
+            if m.is_empty() && Some(m.end) == self.last_end {
+                self.at += 1;
+                continue;
+            }
+            self.at = m.end;
+            self.last_end = Some(m.end);
+            return Some(m);
+        }
+    }
+
+    // The ends of the matches that begin before `end`.
+    fn until<F>(mut self, len: usize, end: usize, find: &F) -> Vec<usize>
+    where
+        F: Fn(usize) -> Option<Range<usize>>,
+    {
+        let mut found = Vec::new();
+        while let Some(m) = self.next(len, find) {
+            if m.start >= end {
+                break;
+            }
+            found.push(m.end);
+        }
+        found
+    }
+}
@@ -0,0 +1,117 @@
+//! Splitting generically over both kinds of regex, for code that takes
+//! either a `regex::Regex` and a `str` or a `regex::bytes::Regex` and a
+//! byte string.
+//!
+//! [`Splittable`] is kept out of the crate root because its methods share
+//! names with those of the `RegexSplit` traits; bound generic code on it
+//! rather than importing it alongside them.
+
+use regex::Regex;
+
+use crate::bytes;
+use crate::{RegexSplit, SplitInclusive, SplitInclusiveLeft};
+
+/// A regex of either flavor, `regex::Regex` splitting `str` or
+/// `regex::bytes::Regex` splitting `[u8]`, so that code generic over which
+/// one it's given can be written once. The methods do the same as the
+/// [`RegexSplit`](crate::RegexSplit) and
+/// [`bytes::RegexSplit`](crate::bytes::RegexSplit) methods of the same names.
+///
+/// # Example
+///
+/// ```rust
+/// # use regex::{bytes, Regex};
+/// # use crate::regex_split::splittable::Splittable;
+/// // Written once for both kinds of text.
+/// fn fields<R: Splittable>(re: &R, text: &R::Haystack) -> usize {
+///     re.split_inclusive(text).count()
+/// }
+///
+/// # fn main() {
+/// assert_eq!(fields(&Regex::new(",").unwrap(), "a,b,c"), 3);
+/// assert_eq!(fields(&bytes::Regex::new(",").unwrap(), &b"a,b"[..]), 2);
+/// # }
+/// ```
+pub trait Splittable {
+    /// The text this regex splits: `str` or `[u8]`.
+    type Haystack: ?Sized;
+
+    /// The iterator returned by `split_inclusive`.
+    type SplitInclusive<'r, 't>: Iterator<Item = &'t Self::Haystack>
+    where
+        Self: 'r,
+        Self::Haystack: 't;
+
+    /// The iterator returned by `split_inclusive_left`.
+    type SplitInclusiveLeft<'r, 't>: Iterator<Item = &'t Self::Haystack>
+    where
+        Self: 'r,
+        Self::Haystack: 't;
+
+    fn split_inclusive<'r, 't>(&'r self, text: &'t Self::Haystack) -> Self::SplitInclusive<'r, 't>;
+    fn split_inclusive_left<'r, 't>(
+        &'r self,
+        text: &'t Self::Haystack,
+    ) -> Self::SplitInclusiveLeft<'r, 't>;
+    fn split_once_inclusive<'t>(
+        &self,
+        text: &'t Self::Haystack,
+    ) -> Option<(&'t Self::Haystack, &'t Self::Haystack)>;
+    fn split_once_inclusive_left<'t>(
+        &self,
+        text: &'t Self::Haystack,
+    ) -> Option<(&'t Self::Haystack, &'t Self::Haystack)>;
+    fn count_segments(&self, text: &Self::Haystack) -> usize;
+}
+
+impl Splittable for Regex {
+    type Haystack = str;
+    type SplitInclusive<'r, 't> = SplitInclusive<'r, 't>;
+    type SplitInclusiveLeft<'r, 't> = SplitInclusiveLeft<'r, 't>;
+
+    fn split_inclusive<'r, 't>(&'r self, text: &'t str) -> SplitInclusive<'r, 't> {
+        RegexSplit::split_inclusive(self, text)
+    }
+
+    fn split_inclusive_left<'r, 't>(&'r self, text: &'t str) -> SplitInclusiveLeft<'r, 't> {
+        RegexSplit::split_inclusive_left(self, text)
+    }
+
+    fn split_once_inclusive<'t>(&self, text: &'t str) -> Option<(&'t str, &'t str)> {
+        RegexSplit::split_once_inclusive(self, text)
+    }
+
+    fn split_once_inclusive_left<'t>(&self, text: &'t str) -> Option<(&'t str, &'t str)> {
+        RegexSplit::split_once_inclusive_left(self, text)
+    }
+
+    fn count_segments(&self, text: &str) -> usize {
+        RegexSplit::count_segments(self, text)
+    }
+}
+
+impl Splittable for regex::bytes::Regex {
+    type Haystack = [u8];
+    type SplitInclusive<'r, 't> = bytes::SplitInclusive<'r, 't>;
+    type SplitInclusiveLeft<'r, 't> = bytes::SplitInclusiveLeft<'r, 't>;
+
+    fn split_inclusive<'r, 't>(&'r self, text: &'t [u8]) -> bytes::SplitInclusive<'r, 't> {
+        bytes::RegexSplit::split_inclusive(self, text)
+    }
+
+    fn split_inclusive_left<'r, 't>(&'r self, text: &'t [u8]) -> bytes::SplitInclusiveLeft<'r, 't> {
+        bytes::RegexSplit::split_inclusive_left(self, text)
+    }
+
+    fn split_once_inclusive<'t>(&self, text: &'t [u8]) -> Option<(&'t [u8], &'t [u8])> {
+        bytes::RegexSplit::split_once_inclusive(self, text)
+    }
+
+    fn split_once_inclusive_left<'t>(&self, text: &'t [u8]) -> Option<(&'t [u8], &'t [u8])> {
+        bytes::RegexSplit::split_once_inclusive_left(self, text)
+    }
+
+    fn count_segments(&self, text: &[u8]) -> usize {
+        bytes::RegexSplit::count_segments(self, text)
+    }
+}
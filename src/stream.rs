@@ -0,0 +1,1760 @@
+//! Splitting text as it comes in, whether read from an `io::BufRead` or
+//! `io::Read`, such as a file or a socket, or pushed in a chunk at a time,
+//! into records delimited by a `regex::bytes::Regex`, without holding the
+//! whole of it in memory.
+//!
+//! Text is searched as it comes in, so a match found near the end of what has
+//! been read so far may not be the one the whole text would give: `\n+` could
+//! go on in the text still to come, and with `ab|b`, a `b` could turn out to
+//! be preceded by an `a`. A match is only taken once the text after it rules
+//! that out, which a lazy DFA for the pattern, run over the text alongside
+//! the regex, tells by dying. The DFA is built from the pattern alone, so
+//! options set on a `RegexBuilder` need to be written into the pattern as
+//! flags, like `(?i)`, to be taken into account.
+
+#[cfg(feature = "tokio-util")]
+pub mod codec;
+#[cfg(any(feature = "flate2", feature = "zstd"))]
+pub mod decompress;
+#[cfg(feature = "futures")]
+pub mod futures;
+#[cfg(feature = "tokio")]
+pub mod tokio;
+#[cfg(feature = "encoding_rs")]
+pub mod transcode;
+
+use std::borrow::Cow;
+use std::io::{self, BufRead, Read, Write};
+use std::iter::FusedIterator;
+use std::ops::Range;
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+use std::task::{ready, Context, Poll};
+
+use regex::bytes::Regex;
+use regex_automata::hybrid::dfa::{Cache, DFA};
+use regex_automata::hybrid::LazyStateID;
+use regex_automata::util::syntax;
+use regex_automata::Input;
+
+pub trait RegexSplitRead: BufRead {
+    fn records(self, regex: &Regex) -> Records<Self>
+    where
+        Self: Sized;
+    fn string_records(self, regex: &Regex) -> StringRecords<Self>
+    where
+        Self: Sized;
+}
+
+// How much a `StreamSplitter` buffers at most, unless told otherwise, and
+// how much it reads at a time.
+const DEFAULT_CAPACITY: usize = 64 * 1024;
+const BLOCK_SIZE: usize = 8 * 1024;
+
+// How much of a chunk a `ChunkSplitter` copies at first when a segment
+// straddles into it, doubling each time that isn't enough to finish it.
+const CARRY_STEP: usize = 64;
+
+// How far around a position assertions like `\b` and `(?m)^` may look: one
+// character, which takes up to four bytes.
+pub(crate) const LOOK_AROUND: usize = 4;
+
+// The byte order mark of UTF-8 text.
+pub(crate) const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+// The byte order marks of UTF-16LE and UTF-16BE text, which can't be split
+// without decoding it first.
+pub(crate) const UTF16_BOMS: [&[u8]; 2] = [b"\xFF\xFE", b"\xFE\xFF"];
+
+/// What to do with a byte order mark at the start of the text.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Bom {
+    /// Leave it in the text, at the front of the first segment. This is the
+    /// default.
+    #[default]
+    Keep,
+    /// Drop it if it marks the encoding the text is in: UTF-8, unless it is
+    /// being decoded from another.
+    Strip,
+    /// Drop it, and decode the text from the encoding it marks, UTF-8,
+    /// UTF-16LE or UTF-16BE, rather than the one given. Where the text isn't
+    /// decoded, a UTF-8 mark is dropped as with `Strip`, and a UTF-16 mark is
+    /// an error of kind `InvalidData`, as the text would have to be decoded
+    /// to be split.
+    Honor,
+}
+
+// Finds the matches in a text that comes in a piece at a time, like
+// `MatchesAt`, but only once the text so far settles them. It is kept apart
+// from the text so that it can search whatever holds it. The positions are
+// into that, and shift back as text is dropped from its front.
+#[derive(Clone, Debug)]
+pub(crate) struct Finder {
+    regex: Regex,
+    boundary: Boundary,
+    at: usize,
+    last_end: Option<usize>,
+
+    // Whether the pattern has assertions like `\b`, which look at the text
+    // around a match.
+    looks_around: bool,
+
+    // Set once there is no more text to come, after which the end of the
+    // text searched is the end of the whole text.
+    eof: bool,
+
+    // Whether the text is UTF-8 to be split like a `str`, passing over the
+    // empty matches a `regex::Regex` wouldn't report, which split a
+    // character.
+    utf8: bool,
+
+    // The longest a match can be, if the caller has said, and where the
+    // search for the next match may start without missing it.
+    max_match_len: Option<usize>,
+    scanned: usize,
+}
+
+impl Finder {
+    pub(crate) fn new(regex: &Regex) -> Self {
+        Self {
+            regex: regex.clone(),
+            boundary: Boundary::new(regex),
+            at: 0,
+            last_end: None,
+            looks_around: syntax::parse_with(regex.as_str(), &syntax_config())
+                .map_or(true, |hir| !hir.properties().look_set().is_empty()),
+            eof: false,
+            utf8: false,
+            max_match_len: None,
+            scanned: 0,
+        }
+    }
+
+    #[cfg(feature = "tokio-util")]
+    pub(crate) fn regex(&self) -> &Regex {
+        &self.regex
+    }
+
+    // Takes the longest a match can be, so that a match is settled as soon as
+    // that much text follows its start, and text searched without finding
+    // one isn't searched again, short of that much overlap.
+    pub(crate) fn set_max_match_len(&mut self, max: usize) {
+        self.max_match_len = Some(max);
+    }
+
+    // Whether text before the next match could change it, so that it has to
+    // be kept around when the text before it is dropped.
+    pub(crate) fn looks_around(&self) -> bool {
+        self.looks_around
+    }
+
+    // Marks the end of the text.
+    pub(crate) fn finish(&mut self) {
+        self.eof = true;
+    }
+
+    // Splits the text like a `str`, which it has to be valid UTF-8 for.
+    #[cfg(any(feature = "utf16", feature = "encoding_rs"))]
+    pub(crate) fn set_utf8(&mut self) {
+        self.utf8 = true;
+    }
+
+    // Finds the next match, or `Some(None)` at the end of the text. Returns
+    // `None` if the text so far doesn't settle it.
+    pub(crate) fn next_match(&mut self, text: &[u8]) -> Option<Option<Range<usize>>> {
+        loop {
+            if self.at > text.len() {
+                return self.eof.then_some(None);
+            }
+            if !self.eof && !self.settled(text) {
+                return None;
+            }
+            let m = match self.regex.find_at(text, self.scanned.max(self.at)) {
+                Some(m) => m,
+                None => return self.eof.then_some(None),
+            };
+
+            // An empty match right where the last match ended isn't reported,
+            // same as with `find_iter`, nor one inside a character of UTF-8
+            // text; look again one step further on.
+            let splits_char = self.utf8 && text.get(m.start()).is_some_and(|&b| b & 0xC0 == 0x80);
+            if m.is_empty() && (Some(m.end()) == self.last_end || splits_char) {
+                self.at += 1;
+                continue;
+            }
+            self.at = m.end();
+            self.last_end = Some(m.end());
+            return Some(Some(m.range()));
+        }
+    }
+
+    fn settled(&mut self, text: &[u8]) -> bool {
+        let max = match self.max_match_len {
+            Some(max) => max + LOOK_AROUND,
+            None => return self.boundary.settled(&self.regex, text, self.at),
+        };
+
+        // No match can start before where the last search stopped short of
+        // the end of the text, as it would have ended within it.
+        let from = self.scanned.max(self.at);
+        let start = match self.regex.find_at(text, from) {
+            Some(m) if m.start() + max <= text.len() => return true,
+            Some(m) => m.start(),
+            None => text.len(),
+        };
+        self.scanned = start.min(text.len().saturating_sub(max));
+        false
+    }
+
+    // Whether the last match found ended at `at`.
+    fn matched_at(&self, at: usize) -> bool {
+        self.last_end == Some(at)
+    }
+
+    // Carries on searching from `at`, where the last match ended if
+    // `matched` is set, as if the text before it had already been searched.
+    fn resume_at(&mut self, at: usize, matched: bool) {
+        self.at = at;
+        self.last_end = matched.then_some(at);
+        self.scanned = at;
+    }
+
+    // Moves the positions back `n` bytes, after as many are dropped from the
+    // front of the text. The text dropped must come before the end of the
+    // last match.
+    pub(crate) fn shift(&mut self, n: usize) {
+        self.at -= n;
+        self.last_end = self.last_end.map(|end| end - n);
+        self.scanned = self.scanned.saturating_sub(n);
+        self.boundary.shift(n);
+    }
+}
+
+// How far splitting has got in a text that comes in a piece at a time, with
+// positions into whatever holds the text, as with `Finder`.
+#[derive(Clone, Debug)]
+pub(crate) struct Cursor {
+    finder: Finder,
+    last: Option<usize>,
+}
+
+impl Cursor {
+    pub(crate) fn new(regex: &Regex) -> Self {
+        Self {
+            finder: Finder::new(regex),
+            last: Some(0),
+        }
+    }
+
+    // Where the next substring begins, or `None` once the last one has been
+    // split off.
+    pub(crate) fn start(&self) -> Option<usize> {
+        self.last
+    }
+
+    pub(crate) fn finish(&mut self) {
+        self.finder.finish();
+    }
+
+    pub(crate) fn looks_around(&self) -> bool {
+        self.finder.looks_around()
+    }
+
+    pub(crate) fn set_max_match_len(&mut self, max: usize) {
+        self.finder.set_max_match_len(max);
+    }
+
+    // Stops splitting, as if the last substring had been split off.
+    pub(crate) fn stop(&mut self) {
+        self.last = None;
+    }
+
+    // Whether the last substring split off ended with a match.
+    fn matched(&self) -> bool {
+        self.last.is_some_and(|last| self.finder.matched_at(last))
+    }
+
+    // Carries on splitting from `start`, as `Finder::resume_at` does.
+    fn resume_at(&mut self, start: usize, matched: bool) {
+        self.finder.resume_at(start, matched);
+        self.last = Some(start);
+    }
+
+    // Returns the range in `text` of the next substring, with the match at
+    // its end, or at the start of the next substring if `left` is set.
+    // Returns `None` if more text is needed to tell where it ends, or if the
+    // last substring has been split off.
+    pub(crate) fn next_range(&mut self, text: &[u8], left: bool) -> Option<Range<usize>> {
+        let last = self.last?;
+        match self.finder.next_match(text)? {
+            None => {
+                self.last = None;
+                Some(last..text.len())
+            }
+            Some(m) => {
+                let end = if left { m.start } else { m.end };
+                self.last = Some(end);
+                Some(last..end)
+            }
+        }
+    }
+
+    // Moves the positions back `n` bytes, after as many are dropped from the
+    // front of the text. None of them may be dropped past.
+    pub(crate) fn shift(&mut self, n: usize) {
+        self.finder.shift(n);
+        self.last = self.last.map(|last| last - n);
+    }
+}
+
+// The `poll_fill_buf` of an async reader, which has the same shape in each
+// runtime's `AsyncBufRead`.
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+type PollFillBuf<R> = for<'a> fn(Pin<&'a mut R>, &mut Context<'_>) -> Poll<io::Result<&'a [u8]>>;
+
+// The splitting shared by the streaming iterators: text is pushed into a
+// buffer as it comes and substrings are split off the front as soon as the
+// matches ending them are certain.
+#[derive(Clone, Debug)]
+pub(crate) struct Engine {
+    cursor: Cursor,
+    buf: Vec<u8>,
+
+    // How many bytes of the text have been dropped from the front of the
+    // buffer, which is where the buffer begins in the whole text.
+    dropped: u64,
+
+    // The most bytes of text held but not yet split off, and the longest
+    // segment split off, past which splitting stops with an error.
+    max_buffered: usize,
+    max_segment: usize,
+
+    // Set while a byte order mark is to be dropped from the front of the
+    // text, until enough of it has come in to tell whether there is one.
+    strip_bom: bool,
+
+    // Whether a UTF-16 byte order mark there is an error, and whether one
+    // was found, until the error is handed out.
+    honor_bom: bool,
+    utf16_bom: bool,
+
+    // The counters behind the `Progress` handed out, if any has been.
+    progress: Option<Arc<Counters>>,
+}
+
+impl Engine {
+    pub(crate) fn new(regex: &Regex) -> Self {
+        Self {
+            cursor: Cursor::new(regex),
+            buf: Vec::new(),
+            dropped: 0,
+            max_buffered: usize::MAX,
+            max_segment: usize::MAX,
+            strip_bom: false,
+            honor_bom: false,
+            utf16_bom: false,
+            progress: None,
+        }
+    }
+
+    // Hands out a handle on the engine's progress, counting from the first
+    // one asked for.
+    pub(crate) fn progress(&mut self) -> Progress {
+        let counters = self.progress.get_or_insert_with(Default::default);
+        Progress {
+            counters: Arc::clone(counters),
+        }
+    }
+
+    // Sets what to do with a byte order mark, which only applies at the
+    // start of the text.
+    pub(crate) fn set_bom(&mut self, bom: Bom) {
+        self.strip_bom = bom != Bom::Keep && self.offset() == 0;
+        self.honor_bom = bom == Bom::Honor;
+    }
+
+    pub(crate) fn set_max_buffered(&mut self, max: usize) {
+        self.max_buffered = max;
+    }
+
+    pub(crate) fn set_max_segment(&mut self, max: usize) {
+        self.max_segment = max;
+    }
+
+    pub(crate) fn set_max_match_len(&mut self, max: usize) {
+        self.cursor.set_max_match_len(max);
+    }
+
+    #[cfg(any(feature = "utf16", feature = "encoding_rs"))]
+    pub(crate) fn set_utf8(&mut self) {
+        self.cursor.finder.set_utf8();
+    }
+
+    // Picks up splitting where the engine a checkpoint was taken of left off.
+    fn resume(regex: &Regex, checkpoint: Checkpoint) -> Self {
+        let mut engine = Self::new(regex);
+        let behind = checkpoint.behind.len();
+        engine.buf = checkpoint.behind;
+        engine.buf.extend_from_slice(&checkpoint.pending);
+        engine.dropped = checkpoint.offset - behind as u64;
+        if checkpoint.done {
+            engine.stop();
+        } else {
+            engine.cursor.resume_at(behind, checkpoint.matched);
+        }
+        engine
+    }
+
+    // Where the next substring begins in the whole text, or where the text
+    // ends once the last one has been split off.
+    fn offset(&self) -> u64 {
+        let start = self.cursor.start().unwrap_or(self.buf.len());
+        self.dropped + start as u64
+    }
+
+    fn checkpoint(&self) -> Checkpoint {
+        let (behind, pending) = match self.cursor.start() {
+            Some(last) if self.cursor.looks_around() => {
+                let from = last.saturating_sub(LOOK_AROUND);
+                (&self.buf[from..last], &self.buf[last..])
+            }
+            Some(last) => (&[][..], &self.buf[last..]),
+            None => (&[][..], &[][..]),
+        };
+        Checkpoint {
+            offset: self.offset(),
+            behind: behind.to_vec(),
+            pending: pending.to_vec(),
+            matched: self.cursor.matched(),
+            done: self.is_finished(),
+        }
+    }
+
+    // Whether the last substring has been split off.
+    pub(crate) fn is_finished(&self) -> bool {
+        self.cursor.start().is_none()
+    }
+
+    pub(crate) fn push(&mut self, text: &[u8]) {
+        self.compact();
+        self.buf.extend_from_slice(text);
+        if let Some(progress) = &self.progress {
+            progress
+                .bytes
+                .fetch_add(text.len() as u64, Ordering::Relaxed);
+        }
+    }
+
+    // Reads up to `max` bytes of text from `reader` straight into the
+    // buffer, marking the end of the text if there isn't any. `max` must not
+    // be zero.
+    pub(crate) fn read_from<R: Read>(&mut self, reader: &mut R, max: usize) -> io::Result<()> {
+        self.compact();
+        let len = self.buf.len();
+        self.buf.resize(len + max, 0);
+        let read = reader.read(&mut self.buf[len..]);
+        let n = *read.as_ref().unwrap_or(&0);
+        self.buf.truncate(len + n);
+        if let Some(progress) = &self.progress {
+            progress.bytes.fetch_add(n as u64, Ordering::Relaxed);
+        }
+        if read? == 0 {
+            self.finish();
+        }
+        Ok(())
+    }
+
+    // Takes in a block of text read, where an empty one marks the end of the
+    // text, as much of it as there is room for. Returns how many bytes were
+    // taken.
+    pub(crate) fn fill(&mut self, text: &[u8]) -> usize {
+        if text.is_empty() {
+            self.finish();
+            return 0;
+        }
+        let n = text
+            .len()
+            .min(self.max_buffered.saturating_sub(self.buffered()));
+        self.push(&text[..n]);
+        n
+    }
+
+    pub(crate) fn finish(&mut self) {
+        self.cursor.finish();
+        if self.strip_bom {
+            self.drop_bom(true);
+        }
+    }
+
+    pub(crate) fn stop(&mut self) {
+        self.cursor.stop();
+    }
+
+    // The number of bytes of text buffered but not yet split off.
+    pub(crate) fn buffered(&self) -> usize {
+        self.cursor.start().map_or(0, |last| self.buf.len() - last)
+    }
+
+    // Returns how many more bytes of text there is room for. No room is an
+    // error of kind `InvalidData` that stops splitting, as the text held
+    // has no segment in it that can be split off yet.
+    pub(crate) fn room(&mut self) -> io::Result<usize> {
+        match self.max_buffered.saturating_sub(self.buffered()) {
+            0 => Err(self.overflow()),
+            room => Ok(room),
+        }
+    }
+
+    // Makes sure there is room for `n` more bytes of text, the same as
+    // `room`.
+    pub(crate) fn reserve(&mut self, n: usize) -> io::Result<()> {
+        if n > self.max_buffered.saturating_sub(self.buffered()) {
+            return Err(self.overflow());
+        }
+        Ok(())
+    }
+
+    // Makes sure the text doesn't start with a UTF-16 byte order mark that
+    // `Bom::Honor` was asked to honor, which is an error of kind
+    // `InvalidData` that stops splitting, handed out once.
+    pub(crate) fn check_bom(&mut self) -> io::Result<()> {
+        if self.strip_bom {
+            self.drop_bom(false);
+        }
+        self.bom_error().map_or(Ok(()), Err)
+    }
+
+    fn bom_error(&mut self) -> Option<io::Error> {
+        if !std::mem::take(&mut self.utf16_bom) {
+            return None;
+        }
+        Some(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "text starts with a UTF-16 byte order mark but isn't decoded",
+        ))
+    }
+
+    fn overflow(&mut self) -> io::Error {
+        self.stop();
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "segment does not fit in the stream splitter's buffer",
+        )
+    }
+
+    pub(crate) fn next_range(&mut self, left: bool) -> Option<Range<usize>> {
+        if self.strip_bom && !self.drop_bom(false) {
+            return None;
+        }
+        let range = self.cursor.next_range(&self.buf, left)?;
+        if let Some(progress) = &self.progress {
+            progress.segments.fetch_add(1, Ordering::Relaxed);
+        }
+        Some(range)
+    }
+
+    // Drops a UTF-8 byte order mark from the front of the text, before any
+    // of it has been searched. Returns `false` if too little text has come
+    // in to tell whether there is one, unless it is the whole text, or if
+    // there is a UTF-16 one to honor, which stops splitting.
+    fn drop_bom(&mut self, eof: bool) -> bool {
+        let boms = if self.honor_bom { &UTF16_BOMS[..] } else { &[] };
+        let undecided = |bom: &[u8]| self.buf.len() < bom.len() && bom.starts_with(&self.buf);
+        if !eof && (undecided(UTF8_BOM) || boms.iter().any(|bom| undecided(bom))) {
+            return false;
+        }
+        if boms.iter().any(|bom| self.buf.starts_with(bom)) {
+            self.strip_bom = false;
+            self.utf16_bom = true;
+            self.stop();
+            return false;
+        }
+        if self.buf.starts_with(UTF8_BOM) {
+            self.buf.drain(..UTF8_BOM.len());
+            self.dropped += UTF8_BOM.len() as u64;
+        }
+        self.strip_bom = false;
+        true
+    }
+
+    // Splits off the next segment, with the match at its end, copied out of
+    // the buffer.
+    pub(crate) fn next_segment(&mut self) -> Option<Vec<u8>> {
+        let range = self.next_range(false)?;
+        Some(self.buf[range].to_vec())
+    }
+
+    // Splits off the next segment, as `next_segment` does. A segment longer
+    // than the most allowed is an error of kind `InvalidData` that stops
+    // splitting.
+    pub(crate) fn try_next_segment(&mut self) -> Option<io::Result<Vec<u8>>> {
+        let Some(range) = self.next_range(false) else {
+            return self.bom_error().map(Err);
+        };
+        if range.len() > self.max_segment {
+            self.stop();
+            return Some(Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "segment is longer than the stream splitter's maximum",
+            )));
+        }
+        Some(Ok(self.buf[range].to_vec()))
+    }
+
+    // Polls for the next segment from an async reader, given its
+    // `poll_fill_buf` and `consume`, so that one loop serves the
+    // `AsyncBufRead` traits of each runtime.
+    #[cfg(any(feature = "tokio", feature = "futures-io"))]
+    pub(crate) fn poll_next_segment<R: ?Sized>(
+        &mut self,
+        mut reader: Pin<&mut R>,
+        cx: &mut Context<'_>,
+        poll_fill_buf: PollFillBuf<R>,
+        consume: fn(Pin<&mut R>, usize),
+    ) -> Poll<Option<io::Result<Vec<u8>>>> {
+        loop {
+            if let Some(segment) = self.try_next_segment() {
+                return Poll::Ready(Some(segment));
+            }
+            if self.is_finished() {
+                return Poll::Ready(None);
+            }
+            if let Err(e) = self.room() {
+                return Poll::Ready(Some(Err(e)));
+            }
+            let text = match ready!(poll_fill_buf(reader.as_mut(), cx)) {
+                Ok(text) => text,
+                Err(e) => return Poll::Ready(Some(Err(e))),
+            };
+            let n = self.fill(text);
+            consume(reader.as_mut(), n);
+        }
+    }
+
+    // Drops the text before the next substring from the buffer, short of a
+    // few bytes for look-behind if the pattern needs them.
+    fn compact(&mut self) {
+        let behind = if self.cursor.looks_around() {
+            LOOK_AROUND
+        } else {
+            0
+        };
+        let n = match self.cursor.start() {
+            Some(last) => last.saturating_sub(behind),
+            None => self.buf.len(),
+        };
+        if n == 0 {
+            return;
+        }
+        self.buf.drain(..n);
+        self.dropped += n as u64;
+        if self.cursor.start().is_some() {
+            self.cursor.shift(n);
+        }
+    }
+}
+
+// The syntax of a `regex::bytes::Regex` pattern, which may match invalid
+// UTF-8.
+fn syntax_config() -> syntax::Config {
+    syntax::Config::new().utf8(false)
+}
+
+// Tells whether the leftmost-first match from a position in the text read so
+// far is the one the whole text would give, by running a lazy DFA for the
+// pattern over the rest of the text: once it dies, nothing still to come can
+// change the match.
+#[derive(Clone, Debug)]
+struct Boundary {
+    dfa: Option<(DFA, Cache)>,
+
+    // Where the last scan began, the state it reached and where it stopped,
+    // so that scanning carries on from there once more text comes in.
+    scan: Option<(usize, LazyStateID, usize)>,
+}
+
+impl Boundary {
+    fn new(regex: &Regex) -> Self {
+        let dfa = DFA::builder()
+            .configure(DFA::config().unicode_word_boundary(true))
+            .syntax(syntax_config())
+            .build(regex.as_str())
+            .ok()
+            .map(|dfa| {
+                let cache = dfa.create_cache();
+                (dfa, cache)
+            });
+        Self { dfa, scan: None }
+    }
+
+    fn settled(&mut self, regex: &Regex, text: &[u8], from: usize) -> bool {
+        match self.scan(text, from) {
+            Some(settled) => settled,
+
+            // Without the DFA, which gives up on a Unicode word boundary next
+            // to non-ASCII text, a match is taken once a whole character
+            // follows it.
+            None => regex
+                .find_at(text, from)
+                .is_some_and(|m| m.end() + LOOK_AROUND <= text.len()),
+        }
+    }
+
+    // Runs the DFA from `from` to the end of `text`, picking up where the
+    // last scan from there stopped. Returns whether it died, or `None` if
+    // the DFA can't be used.
+    fn scan(&mut self, text: &[u8], from: usize) -> Option<bool> {
+        let (dfa, cache) = self.dfa.as_mut()?;
+        let (mut sid, mut at) = match self.scan {
+            Some((start, sid, at)) if start == from => (sid, at),
+            _ => {
+                let input = Input::new(text).range(from..);
+                match dfa.start_state_forward(cache, &input) {
+                    Ok(sid) => (sid, from),
+                    Err(_) => return self.give_up(),
+                }
+            }
+        };
+        while !sid.is_dead() && at < text.len() {
+            sid = match dfa.next_state(cache, sid, text[at]) {
+                Ok(sid) if !sid.is_quit() => sid,
+                _ => return self.give_up(),
+            };
+            at += 1;
+        }
+        self.scan = Some((from, sid, at));
+        Some(sid.is_dead())
+    }
+
+    fn give_up(&mut self) -> Option<bool> {
+        self.dfa = None;
+        self.scan = None;
+        None
+    }
+
+    // Moves the scan back `n` bytes, after as many are dropped from the
+    // front of the text, or forgets it if it began in the text dropped.
+    fn shift(&mut self, n: usize) {
+        self.scan = self
+            .scan
+            .and_then(|(start, sid, at)| Some((start.checked_sub(n)?, sid, at - n)));
+    }
+}
+
+/// Yields the records of a reader delimited by a regular expression match
+/// inclusive of the match, as they are read.
+#[derive(Clone, Debug)]
+pub struct Records<R> {
+    reader: R,
+    engine: Engine,
+}
+
+impl<R: BufRead> Records<R> {
+    /// Sets the most bytes of text held at once, which bounds the length of
+    /// a record. Holding that many without finding where the record ends, as
+    /// when no match comes, is an error of kind `InvalidData`, after which
+    /// the iterator yields nothing more. Unbounded by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::io::{Cursor, ErrorKind};
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::stream::RegexSplitRead;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let mut records = Cursor::new("short\nno newline in sight").records(&re).max_buffered(8);
+    /// assert_eq!(records.next().unwrap().unwrap(), b"short\n");
+    /// assert_eq!(records.next().unwrap().unwrap_err().kind(), ErrorKind::InvalidData);
+    /// assert!(records.next().is_none());
+    /// # }
+    /// ```
+    pub fn max_buffered(mut self, max: usize) -> Self {
+        self.engine.set_max_buffered(max);
+        self
+    }
+
+    /// Sets the longest record yielded. A longer one is an error of kind
+    /// `InvalidData`, after which the iterator yields nothing more.
+    /// Unbounded by default.
+    pub fn max_segment_len(mut self, max: usize) -> Self {
+        self.engine.set_max_segment(max);
+        self
+    }
+
+    /// Promises that no match is longer than `max` bytes, as
+    /// [`StreamSplitter::max_delimiter_len`] does.
+    pub fn max_delimiter_len(mut self, max: usize) -> Self {
+        self.engine.set_max_match_len(max);
+        self
+    }
+
+    /// Sets what to do with a byte order mark at the start of the text. See
+    /// [`Bom`].
+    pub fn bom(mut self, bom: Bom) -> Self {
+        self.engine.set_bom(bom);
+        self
+    }
+
+    /// Returns a handle on how far the iterator has got. See [`Progress`].
+    pub fn progress(&mut self) -> Progress {
+        self.engine.progress()
+    }
+
+    // Reads the next block of text into the engine, or marks the end of the
+    // text if there isn't any.
+    fn fill(&mut self) -> io::Result<()> {
+        self.engine.room()?;
+        let text = match self.reader.fill_buf() {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        let n = self.engine.fill(text);
+        self.reader.consume(n);
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Iterator for Records<R> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(segment) = self.engine.try_next_segment() {
+                return Some(segment);
+            }
+            if self.engine.is_finished() {
+                return None;
+            }
+            if let Err(e) = self.fill() {
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+impl<R: BufRead> FusedIterator for Records<R> {}
+
+/// Yields the records of a reader delimited by a regular expression match
+/// inclusive of the match, as `String`s.
+#[derive(Clone, Debug)]
+pub struct StringRecords<R> {
+    inner: Records<R>,
+}
+
+impl<R: BufRead> StringRecords<R> {
+    /// Sets the most bytes of text held at once, as
+    /// [`Records::max_buffered`] does.
+    pub fn max_buffered(mut self, max: usize) -> Self {
+        self.inner = self.inner.max_buffered(max);
+        self
+    }
+
+    /// Sets the longest record yielded, as [`Records::max_segment_len`]
+    /// does.
+    pub fn max_segment_len(mut self, max: usize) -> Self {
+        self.inner = self.inner.max_segment_len(max);
+        self
+    }
+
+    /// Promises that no match is longer than `max` bytes, as
+    /// [`Records::max_delimiter_len`] does.
+    pub fn max_delimiter_len(mut self, max: usize) -> Self {
+        self.inner = self.inner.max_delimiter_len(max);
+        self
+    }
+
+    /// Sets what to do with a byte order mark at the start of the text. See
+    /// [`Bom`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::io::Cursor;
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::stream::{Bom, RegexSplitRead};
+    /// # fn main() -> std::io::Result<()> {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let reader = Cursor::new("\u{FEFF}one\ntwo");
+    /// let v = reader.string_records(&re).bom(Bom::Strip).collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(v, ["one\n", "two"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bom(mut self, bom: Bom) -> Self {
+        self.inner = self.inner.bom(bom);
+        self
+    }
+
+    /// Returns a handle on how far the iterator has got. See [`Progress`].
+    pub fn progress(&mut self) -> Progress {
+        self.inner.progress()
+    }
+}
+
+impl<R: BufRead> Iterator for StringRecords<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = self.inner.next()?;
+        Some(record.and_then(into_string))
+    }
+}
+
+impl<R: BufRead> FusedIterator for StringRecords<R> {}
+
+// Turns a record into a `String`, failing the way `BufRead::lines` does if
+// it isn't valid UTF-8.
+pub(crate) fn into_string(record: Vec<u8>) -> io::Result<String> {
+    String::from_utf8(record).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Splits the text of any reader into segments delimited by a regular
+/// expression match inclusive of the match, holding no more than a set
+/// number of bytes of it at once.
+///
+/// A segment is only yielded once the text after it shows that no match
+/// could end it any later. A segment that, with whatever text has to be read
+/// past it to tell that, doesn't fit in the buffer is an error of kind
+/// `InvalidData`, after which the splitter yields nothing more.
+#[derive(Clone, Debug)]
+pub struct StreamSplitter<R> {
+    reader: R,
+    engine: Engine,
+}
+
+impl<R: Read> StreamSplitter<R> {
+    /// Creates a splitter for the text read from `reader`, which holds up to
+    /// 64 KiB of it at once.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::io::Cursor;
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::stream::StreamSplitter;
+    /// # fn main() -> std::io::Result<()> {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let reader = Cursor::new("one\ntwo\nthree");
+    /// let v = StreamSplitter::new(&re, reader).collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(v, [&b"one\n"[..], &b"two\n"[..], &b"three"[..]]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(regex: &Regex, reader: R) -> Self {
+        let mut engine = Engine::new(regex);
+        engine.set_max_buffered(DEFAULT_CAPACITY);
+        Self { reader, engine }
+    }
+
+    /// Sets the most bytes of text held at once, which bounds the length of
+    /// a segment.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::io::{Cursor, ErrorKind};
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::stream::StreamSplitter;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let reader = Cursor::new("short\nmuch too long\n");
+    /// let mut splitter = StreamSplitter::new(&re, reader).capacity(8);
+    /// assert_eq!(splitter.next().unwrap().unwrap(), b"short\n");
+    /// assert_eq!(splitter.next().unwrap().unwrap_err().kind(), ErrorKind::InvalidData);
+    /// assert!(splitter.next().is_none());
+    /// # }
+    /// ```
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.engine.set_max_buffered(capacity);
+        self
+    }
+
+    /// Sets the longest segment yielded. A longer one is an error of kind
+    /// `InvalidData`, after which the splitter yields nothing more.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::io::{Cursor, ErrorKind};
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::stream::StreamSplitter;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let reader = Cursor::new("short\nmuch too long\n");
+    /// let mut splitter = StreamSplitter::new(&re, reader).max_segment_len(8);
+    /// assert_eq!(splitter.next().unwrap().unwrap(), b"short\n");
+    /// assert_eq!(splitter.next().unwrap().unwrap_err().kind(), ErrorKind::InvalidData);
+    /// assert!(splitter.next().is_none());
+    /// # }
+    /// ```
+    pub fn max_segment_len(mut self, max: usize) -> Self {
+        self.engine.set_max_segment(max);
+        self
+    }
+
+    /// Promises that no match is longer than `max` bytes, as with a fixed
+    /// delimiter like `\r\n\r\n`. A match is then taken as soon as that
+    /// much text follows its start, and text searched without finding one
+    /// isn't searched again as more comes in, short of that much overlap.
+    /// A longer match breaks the promise, and may be taken cut short.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::io::Cursor;
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::stream::StreamSplitter;
+    /// # fn main() -> std::io::Result<()> {
+    /// let re = Regex::new(r"\r\n\r\n").unwrap();
+    /// let reader = Cursor::new("HEAD / HTTP/1.1\r\n\r\nHEAD /about HTTP/1.1\r\n\r\n");
+    /// let splitter = StreamSplitter::new(&re, reader).max_delimiter_len(4);
+    /// let v = splitter.collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(v, [
+    ///     &b"HEAD / HTTP/1.1\r\n\r\n"[..],
+    ///     &b"HEAD /about HTTP/1.1\r\n\r\n"[..],
+    ///     &b""[..],
+    /// ]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn max_delimiter_len(mut self, max: usize) -> Self {
+        self.engine.set_max_match_len(max);
+        self
+    }
+
+    /// Sets what to do with a byte order mark at the start of the text. See
+    /// [`Bom`]. A mark dropped still counts towards offsets, which are in
+    /// the text as read.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::io::{Cursor, ErrorKind};
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::stream::{Bom, StreamSplitter};
+    /// # fn main() -> std::io::Result<()> {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let reader = Cursor::new("\u{FEFF}id,name\n1,one");
+    /// let mut splitter = StreamSplitter::new(&re, reader).bom(Bom::Strip);
+    /// assert_eq!(splitter.next().unwrap()?, b"id,name\n");
+    /// assert_eq!(splitter.offset(), 11);
+    ///
+    /// // UTF-16 has to go through a `TranscodingSplitter` to be split.
+    /// let reader = Cursor::new(b"\xFF\xFEi\0d\0\n\0");
+    /// let mut splitter = StreamSplitter::new(&re, reader).bom(Bom::Honor);
+    /// let e = splitter.next().unwrap().unwrap_err();
+    /// assert_eq!(e.kind(), ErrorKind::InvalidData);
+    /// assert!(splitter.next().is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bom(mut self, bom: Bom) -> Self {
+        self.engine.set_bom(bom);
+        self
+    }
+
+    /// Returns a handle on how far the splitter has got. See [`Progress`].
+    pub fn progress(&mut self) -> Progress {
+        self.engine.progress()
+    }
+
+    /// Creates a splitter that carries on from a checkpoint taken of another
+    /// one, reading the rest of the text from `reader`, which has to be at
+    /// [`Checkpoint::resume_offset`] in it. The regex has to be the same.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::io::{Cursor, Seek, SeekFrom};
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::stream::StreamSplitter;
+    /// # fn main() -> std::io::Result<()> {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text = "one\ntwo\nthree";
+    /// let mut splitter = StreamSplitter::new(&re, Cursor::new(text));
+    /// assert_eq!(splitter.next().unwrap()?, b"one\n");
+    /// let checkpoint = splitter.checkpoint();
+    /// assert_eq!(checkpoint.offset(), 4);
+    ///
+    /// let mut reader = Cursor::new(text);
+    /// reader.seek(SeekFrom::Start(checkpoint.resume_offset()))?;
+    /// let v = StreamSplitter::resume(&re, reader, checkpoint).collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(v, [&b"two\n"[..], &b"three"[..]]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn resume(regex: &Regex, reader: R, checkpoint: Checkpoint) -> Self {
+        let mut engine = Engine::resume(regex, checkpoint);
+        engine.set_max_buffered(DEFAULT_CAPACITY);
+        Self { reader, engine }
+    }
+
+    /// Returns the offset in the text of the next segment, which is the end
+    /// of the last one yielded, or of the text once the last one has been.
+    pub fn offset(&self) -> u64 {
+        self.engine.offset()
+    }
+
+    /// Returns what is needed to carry on splitting from here later, with
+    /// [`StreamSplitter::resume`]: the offset of the next segment and what
+    /// has been read of it.
+    pub fn checkpoint(&self) -> Checkpoint {
+        self.engine.checkpoint()
+    }
+
+    /// Returns the reader, dropping any text read from it but not yet
+    /// yielded.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> Iterator for StreamSplitter<R> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(segment) = self.engine.try_next_segment() {
+                return Some(segment);
+            }
+            if self.engine.is_finished() {
+                return None;
+            }
+            let room = match self.engine.room() {
+                Ok(room) => room,
+                Err(e) => return Some(Err(e)),
+            };
+            match self
+                .engine
+                .read_from(&mut self.reader, room.min(BLOCK_SIZE))
+            {
+                Ok(()) => {}
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+impl<R: Read> FusedIterator for StreamSplitter<R> {}
+
+/// Splits text pushed in a chunk at a time into segments delimited by a
+/// regular expression match inclusive of the match, for when something else
+/// owns the read loop, like an event-driven server.
+///
+/// Each call to `feed` yields the segments the text so far completes, and
+/// `finish` yields the rest once there is no more text to come. Chunks can be
+/// cut anywhere, through a match or a UTF-8 character alike.
+///
+/// # Example
+///
+/// ```rust
+/// # use regex::bytes::Regex;
+/// # use crate::regex_split::stream::IncrementalSplitter;
+/// # fn main() {
+/// let re = Regex::new(r"\r\n").unwrap();
+/// let mut splitter = IncrementalSplitter::new(&re);
+///
+/// assert_eq!(splitter.feed(b"HELO a\r").count(), 0);
+/// let v: Vec<Vec<u8>> = splitter.feed(b"\nMAIL b\r\nRC").collect();
+/// assert_eq!(v, [&b"HELO a\r\n"[..], &b"MAIL b\r\n"[..]]);
+/// let v: Vec<Vec<u8>> = splitter.finish().collect();
+/// assert_eq!(v, [&b"RC"[..]]);
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct IncrementalSplitter {
+    engine: Engine,
+}
+
+impl IncrementalSplitter {
+    /// Creates a splitter with no text in it yet.
+    pub fn new(regex: &Regex) -> Self {
+        Self {
+            engine: Engine::new(regex),
+        }
+    }
+
+    /// Creates a splitter that carries on from a checkpoint taken of another
+    /// one, to be fed the text from [`Checkpoint::resume_offset`] on. The
+    /// regex has to be the same.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::stream::IncrementalSplitter;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let mut splitter = IncrementalSplitter::new(&re);
+    /// assert_eq!(splitter.feed(b"a\nbc").count(), 1);
+    /// let checkpoint = splitter.checkpoint();
+    /// assert_eq!((checkpoint.offset(), checkpoint.pending()), (2, &b"bc"[..]));
+    ///
+    /// let mut splitter = IncrementalSplitter::resume(&re, checkpoint);
+    /// let v: Vec<Vec<u8>> = splitter.feed(b"d\nef").collect();
+    /// assert_eq!(v, [&b"bcd\n"[..]]);
+    /// assert_eq!(splitter.offset(), 6);
+    /// # }
+    /// ```
+    pub fn resume(regex: &Regex, checkpoint: Checkpoint) -> Self {
+        Self {
+            engine: Engine::resume(regex, checkpoint),
+        }
+    }
+
+    /// Returns the offset in the text of the next segment, which is the end
+    /// of the last one yielded.
+    pub fn offset(&self) -> u64 {
+        self.engine.offset()
+    }
+
+    /// Returns what is needed to carry on splitting from here later, with
+    /// [`IncrementalSplitter::resume`]: the offset of the next segment and
+    /// what has been fed of it.
+    pub fn checkpoint(&self) -> Checkpoint {
+        self.engine.checkpoint()
+    }
+
+    /// Sets the most bytes of text held at once by `try_feed`, which bounds
+    /// the length of a segment. Segments completed but not yet yielded
+    /// count towards it. Unbounded by default.
+    pub fn max_buffered(mut self, max: usize) -> Self {
+        self.engine.set_max_buffered(max);
+        self
+    }
+
+    /// Promises that no match is longer than `max` bytes, as
+    /// [`StreamSplitter::max_delimiter_len`] does.
+    pub fn max_delimiter_len(mut self, max: usize) -> Self {
+        self.engine.set_max_match_len(max);
+        self
+    }
+
+    /// Sets what to do with a byte order mark at the start of the text. See
+    /// [`Bom`].
+    pub fn bom(mut self, bom: Bom) -> Self {
+        self.engine.set_bom(bom);
+        self
+    }
+
+    /// Returns a handle on how far the splitter has got. See [`Progress`].
+    pub fn progress(&mut self) -> Progress {
+        self.engine.progress()
+    }
+
+    /// Adds the next chunk of text and returns an iterator of the segments
+    /// it completes. Segments the iterator is dropped before yielding come
+    /// out of the next one instead.
+    pub fn feed(&mut self, chunk: &[u8]) -> Ready<'_> {
+        self.engine.push(chunk);
+        Ready {
+            engine: &mut self.engine,
+        }
+    }
+
+    /// Adds the next chunk of text, as `feed` does, unless that would hold
+    /// more text than the limit set with `max_buffered`. That is an error of
+    /// kind `InvalidData` instead, and the chunk isn't taken, nor any after
+    /// it: no more segments are yielded. So is a UTF-16 byte order mark at
+    /// the start of the text under [`Bom::Honor`], once the chunk with it is
+    /// taken.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::io::ErrorKind;
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::stream::IncrementalSplitter;
+    /// # fn main() {
+    /// let re = Regex::new(r"\r\n").unwrap();
+    /// let mut splitter = IncrementalSplitter::new(&re).max_buffered(16);
+    /// assert_eq!(splitter.try_feed(b"OK\r\nno end").unwrap().count(), 1);
+    /// let e = splitter.try_feed(b" in sight yet").unwrap_err();
+    /// assert_eq!(e.kind(), ErrorKind::InvalidData);
+    /// assert_eq!(splitter.finish().count(), 0);
+    /// # }
+    /// ```
+    pub fn try_feed(&mut self, chunk: &[u8]) -> io::Result<Ready<'_>> {
+        self.engine.reserve(chunk.len())?;
+        self.engine.push(chunk);
+        self.engine.check_bom()?;
+        Ok(Ready {
+            engine: &mut self.engine,
+        })
+    }
+
+    /// Marks the end of the text and returns an iterator of the segments
+    /// left, the last of which is the text after the last match.
+    pub fn finish(mut self) -> Remaining {
+        self.engine.finish();
+        Remaining {
+            engine: self.engine,
+        }
+    }
+}
+
+/// Yields the segments completed by a chunk of text fed to an
+/// [`IncrementalSplitter`].
+#[derive(Debug)]
+pub struct Ready<'a> {
+    engine: &'a mut Engine,
+}
+
+impl<'a> Iterator for Ready<'a> {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.engine.next_segment()
+    }
+}
+
+impl<'a> FusedIterator for Ready<'a> {}
+
+/// Yields the segments left in an [`IncrementalSplitter`] at the end of the
+/// text.
+#[derive(Clone, Debug)]
+pub struct Remaining {
+    engine: Engine,
+}
+
+impl Iterator for Remaining {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.engine.next_segment()
+    }
+}
+
+impl FusedIterator for Remaining {}
+
+/// A writer that splits the text written into it into segments delimited by
+/// a regular expression match inclusive of the match, and hands each segment
+/// to `sink` as soon as it is complete, for code that only writes its output
+/// somewhere. The sink can be any closure, including one that writes each
+/// segment on to another writer.
+///
+/// The text after the last match only makes a segment once there is no more
+/// to come, so call `finish` when done writing: dropping the writer drops it
+/// instead. An error from the sink is returned from the write that completed
+/// the segment, which is lost.
+///
+/// # Example
+///
+/// ```rust
+/// # use std::io::Write;
+/// # use regex::bytes::Regex;
+/// # use crate::regex_split::stream::SplittingWriter;
+/// # fn main() -> std::io::Result<()> {
+/// let re = Regex::new(r"\n").unwrap();
+/// let mut lines = Vec::new();
+/// let mut writer = SplittingWriter::new(&re, |line: &[u8]| {
+///     lines.push(String::from_utf8_lossy(line).into_owned());
+///     Ok(())
+/// });
+/// write!(writer, "{} apples\n{} pe", 3, 4)?;
+/// writer.write_all(b"ars\nno newline")?;
+/// writer.finish()?;
+/// assert_eq!(lines, ["3 apples\n", "4 pears\n", "no newline"]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct SplittingWriter<F> {
+    engine: Engine,
+    sink: F,
+}
+
+impl<F> SplittingWriter<F>
+where
+    F: FnMut(&[u8]) -> io::Result<()>,
+{
+    /// Creates a writer that hands the segments of the text written into it
+    /// to `sink`.
+    pub fn new(regex: &Regex, sink: F) -> Self {
+        Self {
+            engine: Engine::new(regex),
+            sink,
+        }
+    }
+
+    /// Sets the most bytes of text held at once, which bounds the length of
+    /// a segment. Holding that many without finding where the segment ends
+    /// is an error of kind `InvalidData`, after which nothing more is handed
+    /// to the sink. Unbounded by default.
+    pub fn max_buffered(mut self, max: usize) -> Self {
+        self.engine.set_max_buffered(max);
+        self
+    }
+
+    /// Sets the longest segment handed to the sink. A longer one is an error
+    /// of kind `InvalidData`, after which nothing more is. Unbounded by
+    /// default.
+    pub fn max_segment_len(mut self, max: usize) -> Self {
+        self.engine.set_max_segment(max);
+        self
+    }
+
+    /// Promises that no match is longer than `max` bytes, as
+    /// [`StreamSplitter::max_delimiter_len`] does.
+    pub fn max_delimiter_len(mut self, max: usize) -> Self {
+        self.engine.set_max_match_len(max);
+        self
+    }
+
+    /// Sets what to do with a byte order mark at the start of the text. See
+    /// [`Bom`].
+    pub fn bom(mut self, bom: Bom) -> Self {
+        self.engine.set_bom(bom);
+        self
+    }
+
+    /// Returns a handle on how far the writer has got. See [`Progress`].
+    pub fn progress(&mut self) -> Progress {
+        self.engine.progress()
+    }
+
+    /// Marks the end of the text, hands the segments left to the sink, the
+    /// last of which is the text after the last match, and returns the
+    /// sink.
+    pub fn finish(mut self) -> io::Result<F> {
+        self.engine.finish();
+        self.drain()?;
+        Ok(self.sink)
+    }
+
+    // Hands the segments completed so far to the sink.
+    fn drain(&mut self) -> io::Result<()> {
+        while let Some(segment) = self.engine.try_next_segment() {
+            (self.sink)(&segment?)?;
+        }
+        Ok(())
+    }
+}
+
+impl<F> Write for SplittingWriter<F>
+where
+    F: FnMut(&[u8]) -> io::Result<()>,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let n = buf.len().min(self.engine.room()?);
+        self.engine.push(&buf[..n]);
+        self.drain()?;
+        Ok(n)
+    }
+
+    // Segments are handed on as soon as they are complete, and the text
+    // after the last match can't be until the end, so there is nothing to
+    // flush.
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Where a streaming splitter has got to in a text, for a long-running job
+/// to pick up from after a restart: the offset of the next segment and the
+/// text of it read so far. With the `serde` feature, it derives `Serialize`
+/// and `Deserialize` so that it can be saved.
+///
+/// Offsets are `u64`s, so that they go past 4 GiB on 32-bit targets too.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Checkpoint {
+    offset: u64,
+
+    // The few bytes before the next segment, for patterns with look-behind.
+    behind: Vec<u8>,
+    pending: Vec<u8>,
+
+    // Whether the last segment ended with a match, which an empty match at
+    // the start of the next segment must not repeat.
+    matched: bool,
+    done: bool,
+}
+
+impl Checkpoint {
+    /// The offset in the text of the next segment.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// The text of the next segment read so far.
+    pub fn pending(&self) -> &[u8] {
+        &self.pending
+    }
+
+    /// The offset in the text past what has been read, from which the rest
+    /// of the text has to be given to the splitter resumed.
+    pub fn resume_offset(&self) -> u64 {
+        self.offset + self.pending.len() as u64
+    }
+}
+
+/// A handle on how far a streaming splitter has got, for a long job to drive
+/// a progress bar with. It can be cloned and read from any thread while the
+/// splitter runs, and keeps counting for as long as the splitter does, and
+/// any clone of it made after.
+///
+/// Keeping count takes a couple of atomic additions per block read and per
+/// segment, and nothing at all for a splitter no handle was asked of.
+///
+/// # Example
+///
+/// ```rust
+/// # use regex::bytes::Regex;
+/// # use crate::regex_split::stream::StreamSplitter;
+/// # fn main() {
+/// let re = Regex::new(r"\n").unwrap();
+/// let mut splitter = StreamSplitter::new(&re, &b"one\ntwo\nthree"[..]);
+/// let progress = splitter.progress();
+/// assert_eq!(splitter.next().unwrap().unwrap(), b"one\n");
+/// assert_eq!(progress.segments(), 1);
+/// splitter.for_each(drop);
+/// assert_eq!((progress.bytes_read(), progress.segments()), (13, 3));
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Progress {
+    counters: Arc<Counters>,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    bytes: AtomicU64,
+    segments: AtomicU64,
+}
+
+impl Progress {
+    /// The number of bytes of text the splitter has taken in so far.
+    pub fn bytes_read(&self) -> u64 {
+        self.counters.bytes.load(Ordering::Relaxed)
+    }
+
+    /// The number of segments the splitter has split off so far.
+    pub fn segments(&self) -> u64 {
+        self.counters.segments.load(Ordering::Relaxed)
+    }
+}
+
+/// Splits text that comes as an iterator of chunks, such as pages from a
+/// pager or blocks from a decompressor, into segments delimited by a regular
+/// expression match inclusive of the match, without joining the chunks
+/// first.
+///
+/// A segment that lies within a single chunk is borrowed from it. Only a
+/// segment that straddles chunks is copied, along with a few bytes of the
+/// chunk it ends in, enough to settle the match ending it. Text in `&str`
+/// chunks can be split with `.map(str::as_bytes)`.
+///
+/// # Example
+///
+/// ```rust
+/// # use std::borrow::Cow;
+/// # use regex::bytes::Regex;
+/// # use crate::regex_split::stream::ChunkSplitter;
+/// # fn main() {
+/// let re = Regex::new(r"\n").unwrap();
+/// let pages = [&b"one\ntwo\nthr"[..], &b"ee\nfour"[..]];
+/// let v: Vec<Cow<[u8]>> = ChunkSplitter::new(&re, pages).collect();
+/// assert_eq!(v, [&b"one\n"[..], &b"two\n"[..], &b"three\n"[..], &b"four"[..]]);
+/// assert!(matches!(v[0], Cow::Borrowed(_)));
+/// assert!(matches!(v[2], Cow::Owned(_)));
+/// assert!(matches!(v[3], Cow::Borrowed(_)));
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ChunkSplitter<'a, I> {
+    chunks: I,
+    cursor: Cursor,
+
+    // The chunk being split.
+    chunk: &'a [u8],
+
+    // While a segment straddles into the chunk, the text carried over from
+    // the chunks before it, then as much of the chunk as has been copied,
+    // which is searched in place of the chunk. `carried` is how much of it
+    // came from before the chunk, or `None` when the chunk is searched.
+    carry: Vec<u8>,
+    carried: Option<usize>,
+    copied: usize,
+}
+
+impl<'a, I> ChunkSplitter<'a, I>
+where
+    I: Iterator<Item = &'a [u8]>,
+{
+    /// Creates a splitter for the text in `chunks`, taken in order.
+    pub fn new<T>(regex: &Regex, chunks: T) -> Self
+    where
+        T: IntoIterator<IntoIter = I>,
+    {
+        Self {
+            chunks: chunks.into_iter(),
+            cursor: Cursor::new(regex),
+            chunk: &[],
+            carry: Vec::new(),
+            carried: None,
+            copied: 0,
+        }
+    }
+
+    /// Promises that no match is longer than `max` bytes, as
+    /// [`StreamSplitter::max_delimiter_len`] does.
+    pub fn max_delimiter_len(mut self, max: usize) -> Self {
+        self.cursor.set_max_match_len(max);
+        self
+    }
+}
+
+impl<'a, I> Iterator for ChunkSplitter<'a, I>
+where
+    I: Iterator<Item = &'a [u8]>,
+{
+    type Item = Cow<'a, [u8]>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let carried = match self.carried {
+                Some(carried) => carried,
+                None => {
+                    if let Some(range) = self.cursor.next_range(self.chunk, false) {
+                        return Some(Cow::Borrowed(&self.chunk[range]));
+                    }
+                    let last = self.cursor.start()?;
+                    let next = match self.chunks.next() {
+                        Some(next) => next,
+                        None => {
+                            self.cursor.finish();
+                            continue;
+                        }
+                    };
+
+                    // The chunk ends partway through a segment, which is
+                    // carried over into the next one, with a few bytes before
+                    // it for look-behind. There is nothing to carry before
+                    // the first chunk.
+                    if !self.chunk.is_empty() {
+                        let from = last.saturating_sub(LOOK_AROUND);
+                        self.carry.clear();
+                        self.carry.extend_from_slice(&self.chunk[from..]);
+                        self.cursor.shift(from);
+                        self.carried = Some(self.carry.len());
+                        self.copied = 0;
+                    }
+                    self.chunk = next;
+                    continue;
+                }
+            };
+
+            if let Some(range) = self.cursor.next_range(&self.carry, false) {
+                let segment = if range.start >= carried {
+                    Cow::Borrowed(&self.chunk[range.start - carried..range.end - carried])
+                } else {
+                    Cow::Owned(self.carry[range].to_vec())
+                };
+
+                // Once look-behind no longer reaches back before the chunk,
+                // the chunk itself can be searched again.
+                if self
+                    .cursor
+                    .start()
+                    .is_some_and(|last| last >= carried + LOOK_AROUND)
+                {
+                    self.cursor.shift(carried);
+                    self.carried = None;
+                }
+                return Some(segment);
+            }
+            let last = self.cursor.start()?;
+            if self.copied < self.chunk.len() {
+                let n = self
+                    .copied
+                    .max(CARRY_STEP)
+                    .min(self.chunk.len() - self.copied);
+                self.carry
+                    .extend_from_slice(&self.chunk[self.copied..self.copied + n]);
+                self.copied += n;
+            } else if let Some(next) = self.chunks.next() {
+                // The segment straddles this chunk too. Drop what has been
+                // split off before carrying on into the next one.
+                let n = last.saturating_sub(LOOK_AROUND);
+                self.carry.drain(..n);
+                self.cursor.shift(n);
+                self.carried = Some(self.carry.len());
+                self.copied = 0;
+                self.chunk = next;
+            } else {
+                self.cursor.finish();
+            }
+        }
+    }
+}
+
+impl<'a, I> FusedIterator for ChunkSplitter<'a, I> where I: Iterator<Item = &'a [u8]> {}
+
+impl<R: BufRead> RegexSplitRead for R {
+    /// Returns an iterator of the records read from the reader, separated by
+    /// a match of the regular expression, with the match at the end of each
+    /// record. These are the substrings `split_inclusive` would give for the
+    /// whole of the text, read a block at a time, whatever the blocks the
+    /// matches fall across.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::io::BufReader;
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::stream::RegexSplitRead;
+    /// # fn main() -> std::io::Result<()> {
+    /// let re = Regex::new(r"\r?\n\r?\n").unwrap();
+    /// let text = &b"GET / HTTP/1.1\r\n\r\nGET /about HTTP/1.1\r\n\r\n"[..];
+    ///
+    /// // A tiny buffer, so that the matches span reads.
+    /// let reader = BufReader::with_capacity(3, text);
+    /// let v = reader.records(&re).collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(v, [
+    ///     &b"GET / HTTP/1.1\r\n\r\n"[..],
+    ///     &b"GET /about HTTP/1.1\r\n\r\n"[..],
+    ///     &b""[..],
+    /// ]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn records(self, regex: &Regex) -> Records<Self> {
+        Records {
+            reader: self,
+            engine: Engine::new(regex),
+        }
+    }
+
+    /// Returns an iterator like `records` that yields each record as a
+    /// `String`. A record that isn't valid UTF-8 is an error of kind
+    /// `InvalidData`, as with `BufRead::lines`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::io::Cursor;
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::stream::RegexSplitRead;
+    /// # fn main() -> std::io::Result<()> {
+    /// let re = Regex::new(r";+").unwrap();
+    /// let v = Cursor::new("a;;b;c").string_records(&re).collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(v, ["a;;", "b;", "c"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn string_records(self, regex: &Regex) -> StringRecords<Self> {
+        StringRecords {
+            inner: self.records(regex),
+        }
+    }
+}
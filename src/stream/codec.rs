@@ -0,0 +1,284 @@
+//! A `tokio_util` codec that frames a byte stream on matches of a
+//! `regex::bytes::Regex`, for use with `FramedRead`, `FramedWrite` or
+//! `Framed`. Only available with the `tokio-util` feature.
+
+use std::io;
+use std::ops::Range;
+
+use ::bytes::{Buf, BufMut, BytesMut};
+use regex::bytes::Regex;
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::stream::{Finder, LOOK_AROUND};
+use crate::Keep;
+
+/// Decodes the frames of a byte stream delimited by regular expression
+/// matches, with each match kept as [`Keep`] says: at the end of the frame
+/// before it, like `split_inclusive`, which is the default, at the front of
+/// the frame after it, at both, or dropped.
+///
+/// Matches are found the same way as in the rest of the `stream` module, so
+/// a match is only taken once the bytes after it show that the rest of the
+/// stream can't change it. A frame longer than the maximum length is an
+/// error of kind `InvalidData`, as is holding more than that many bytes of a
+/// frame without finding where it ends. An empty frame at the end of the
+/// stream, or at its start when matches are kept at the front, is dropped.
+///
+/// Frames are encoded with the delimiter set with `delimiter` added where
+/// [`Keep`] says the decoder finds it: at the front of the frame for
+/// `Keep::Left`, and at the end for `Keep::Right` and `Keep::Neither`.
+/// Without one, frames are written as they are, and have to carry a match
+/// there already. Frames can't be encoded with `Keep::Both`, where each
+/// match is decoded into the frames on both sides of it, as there is no
+/// writing them out one at a time so that they decode the same again.
+#[derive(Clone, Debug)]
+pub struct RegexSplitCodec {
+    finder: Finder,
+    keep: Keep,
+    max_length: usize,
+    delimiter: Option<Vec<u8>>,
+
+    // Where the frame being decoded begins in the buffer.
+    start: usize,
+    first: bool,
+    done: bool,
+}
+
+impl RegexSplitCodec {
+    /// Creates a codec for frames of up to `max_length` bytes, each ending
+    /// with the match that delimits it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bytes::BytesMut;
+    /// # use regex::bytes::Regex;
+    /// # use tokio_util::codec::Decoder;
+    /// # use crate::regex_split::stream::codec::RegexSplitCodec;
+    /// # fn main() -> std::io::Result<()> {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let mut codec = RegexSplitCodec::new(&re, 1024);
+    /// let mut buf = BytesMut::from(&b"PING\r\nPO"[..]);
+    /// assert_eq!(codec.decode(&mut buf)?.unwrap(), &b"PING\r\n"[..]);
+    /// assert_eq!(codec.decode(&mut buf)?, None);
+    /// buf.extend_from_slice(b"NG\n");
+    /// assert_eq!(codec.decode_eof(&mut buf)?.unwrap(), &b"PONG\n"[..]);
+    /// assert_eq!(codec.decode_eof(&mut buf)?, None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(regex: &Regex, max_length: usize) -> Self {
+        Self {
+            finder: Finder::new(regex),
+            keep: Keep::Right,
+            max_length,
+            delimiter: None,
+            start: 0,
+            first: true,
+            done: false,
+        }
+    }
+
+    /// Sets where frames keep their delimiting matches.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bytes::BytesMut;
+    /// # use regex::bytes::Regex;
+    /// # use tokio_util::codec::Decoder;
+    /// # use crate::regex_split::Keep;
+    /// # use crate::regex_split::stream::codec::RegexSplitCodec;
+    /// # fn main() -> std::io::Result<()> {
+    /// let re = Regex::new(r"\s*;\s*").unwrap();
+    /// let mut codec = RegexSplitCodec::new(&re, 1024).keep(Keep::Neither);
+    /// let mut buf = BytesMut::from(&b"a ; b;c;"[..]);
+    /// let mut frames = Vec::new();
+    /// while let Some(frame) = codec.decode_eof(&mut buf)? {
+    ///     frames.push(frame);
+    /// }
+    /// assert_eq!(frames, [&b"a"[..], &b"b"[..], &b"c"[..]]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn keep(mut self, keep: Keep) -> Self {
+        self.keep = keep;
+        self
+    }
+
+    /// Promises that no match is longer than `max` bytes, as
+    /// [`crate::stream::StreamSplitter::max_delimiter_len`] does.
+    pub fn max_delimiter_len(mut self, max: usize) -> Self {
+        self.finder.set_max_match_len(max);
+        self
+    }
+
+    /// Sets the delimiter added to each frame encoded. It should be a match
+    /// of the regex, for the frames to be decoded again.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bytes::BytesMut;
+    /// # use regex::bytes::Regex;
+    /// # use tokio_util::codec::{Decoder, Encoder};
+    /// # use crate::regex_split::Keep;
+    /// # use crate::regex_split::stream::codec::RegexSplitCodec;
+    /// # fn main() -> std::io::Result<()> {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let mut codec = RegexSplitCodec::new(&re, 1024)
+    ///     .keep(Keep::Neither)
+    ///     .delimiter("\r\n");
+    /// let mut buf = BytesMut::new();
+    /// codec.encode("PING", &mut buf)?;
+    /// codec.encode(b"PONG", &mut buf)?;
+    /// assert_eq!(buf, &b"PING\r\nPONG\r\n"[..]);
+    /// assert_eq!(codec.decode(&mut buf)?.unwrap(), &b"PING"[..]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn delimiter(mut self, delimiter: impl AsRef<[u8]>) -> Self {
+        self.delimiter = Some(delimiter.as_ref().to_vec());
+        self
+    }
+
+    /// The longest frame the codec decodes.
+    pub fn max_length(&self) -> usize {
+        self.max_length
+    }
+
+    // Splits the frame in `range` off the front of `src`, dropping the bytes
+    // before `next`, where the next frame begins, short of any the pattern
+    // needs for look-behind.
+    fn split_frame(
+        &mut self,
+        src: &mut BytesMut,
+        range: Range<usize>,
+        next: usize,
+    ) -> io::Result<BytesMut> {
+        if range.len() > self.max_length {
+            return Err(too_long());
+        }
+        let behind = if self.finder.looks_around() {
+            LOOK_AROUND.min(next)
+        } else {
+            0
+        };
+        let n = next - behind;
+        let frame = if range.start == 0 && range.end <= n {
+            // None of the frame is kept in the buffer, so it can be split off
+            // without copying it.
+            let frame = src.split_to(range.end);
+            src.advance(n - range.end);
+            frame
+        } else {
+            let frame = BytesMut::from(&src[range]);
+            src.advance(n);
+            frame
+        };
+        self.finder.shift(n);
+        self.start = next - n;
+        Ok(frame)
+    }
+}
+
+impl Decoder for RegexSplitCodec {
+    type Item = BytesMut;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> io::Result<Option<BytesMut>> {
+        loop {
+            if self.done {
+                return Ok(None);
+            }
+            let m = match self.finder.next_match(src) {
+                Some(Some(m)) => m,
+                Some(None) => {
+                    self.done = true;
+                    let frame = src.split_off(self.start);
+                    src.clear();
+                    if frame.len() > self.max_length {
+                        return Err(too_long());
+                    }
+                    return Ok((!frame.is_empty()).then_some(frame));
+                }
+                None if src.len() - self.start > self.max_length => return Err(too_long()),
+                None => return Ok(None),
+            };
+            let end = match self.keep {
+                Keep::Right | Keep::Both => m.end,
+                Keep::Left | Keep::Neither => m.start,
+            };
+            let next = match self.keep {
+                Keep::Left | Keep::Both => m.start,
+                Keep::Right | Keep::Neither => m.end,
+            };
+            let first = std::mem::replace(&mut self.first, false);
+            if first && self.keep == Keep::Left && end == self.start {
+                continue;
+            }
+            return self.split_frame(src, self.start..end, next).map(Some);
+        }
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> io::Result<Option<BytesMut>> {
+        self.finder.finish();
+        self.decode(src)
+    }
+}
+
+impl<T: AsRef<[u8]>> Encoder<T> for RegexSplitCodec {
+    type Error = io::Error;
+
+    /// Writes `item` to `dst`, adding the delimiter if one is set. Without
+    /// one, an item that doesn't end with a match of the regex, or begin
+    /// with one for `Keep::Left`, is an error of kind `InvalidInput`, as is
+    /// any item for `Keep::Neither`, where there would be nothing to tell
+    /// where it ends. With `Keep::Both`, every item is an error of kind
+    /// `InvalidInput`.
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> io::Result<()> {
+        let item = item.as_ref();
+        let regex = self.finder.regex();
+        let delimited = match (&self.delimiter, self.keep) {
+            (_, Keep::Both) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "frames that share their delimiters can't be encoded",
+                ));
+            }
+            (Some(delimiter), Keep::Left) => {
+                dst.reserve(delimiter.len() + item.len());
+                dst.put_slice(delimiter);
+                dst.put_slice(item);
+                return Ok(());
+            }
+            (Some(delimiter), _) => {
+                dst.reserve(item.len() + delimiter.len());
+                dst.put_slice(item);
+                dst.put_slice(delimiter);
+                return Ok(());
+            }
+            (None, Keep::Left) => regex.find(item).is_some_and(|m| m.start() == 0),
+            (None, Keep::Right) => regex
+                .find_iter(item)
+                .last()
+                .is_some_and(|m| m.end() == item.len()),
+            (None, Keep::Neither) => false,
+        };
+        if !delimited {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "frame has no delimiter to encode it with",
+            ));
+        }
+        dst.extend_from_slice(item);
+        Ok(())
+    }
+}
+
+fn too_long() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "frame is longer than the codec's maximum length",
+    )
+}
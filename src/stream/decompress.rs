@@ -0,0 +1,163 @@
+//! Decompressing text as it is read, so that a compressed log can be split
+//! without unpacking it first. Only available with the `flate2` feature, for
+//! gzip, or the `zstd` feature, for Zstandard, or both.
+
+use std::fmt;
+use std::io::{self, BufRead, Read};
+
+use regex::bytes::Regex;
+
+use crate::stream::StreamSplitter;
+
+// The first bytes of a gzip member and of a Zstandard frame, and how many
+// bytes it takes to tell them apart from plain text.
+#[cfg(feature = "flate2")]
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+#[cfg(feature = "zstd")]
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+const HEAD_LEN: usize = 4;
+
+// The bytes read ahead to tell the format, put back in front of the rest.
+type Head<R> = io::Chain<io::Cursor<Vec<u8>>, R>;
+
+/// A reader that decompresses what it reads if it is compressed in a format
+/// it knows, telling which from the first bytes, and passes it through as it
+/// is otherwise. Concatenated gzip members and Zstandard frames are read one
+/// after the other, as `gzip -d` and `zstd -d` would.
+#[derive(Debug)]
+pub struct Decompress<R> {
+    inner: Inner<R>,
+}
+
+enum Inner<R> {
+    Plain(Head<R>),
+    #[cfg(feature = "flate2")]
+    Gzip(flate2::bufread::MultiGzDecoder<Head<R>>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::Decoder<'static, Head<R>>),
+}
+
+// The Zstandard decoder doesn't implement `Debug`, so only the format is
+// shown.
+impl<R> fmt::Debug for Inner<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let format = match self {
+            Inner::Plain(_) => "Plain",
+            #[cfg(feature = "flate2")]
+            Inner::Gzip(_) => "Gzip",
+            #[cfg(feature = "zstd")]
+            Inner::Zstd(_) => "Zstd",
+        };
+        f.debug_tuple(format).finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Format {
+    Plain,
+    #[cfg(feature = "flate2")]
+    Gzip,
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+impl Format {
+    fn of(head: &[u8]) -> Self {
+        #[cfg(feature = "flate2")]
+        if head.starts_with(GZIP_MAGIC) {
+            return Format::Gzip;
+        }
+        #[cfg(feature = "zstd")]
+        if head.starts_with(ZSTD_MAGIC) {
+            return Format::Zstd;
+        }
+        Format::Plain
+    }
+}
+
+impl<R: BufRead> Decompress<R> {
+    /// Wraps `reader`, reading its first bytes to tell whether the text is
+    /// compressed. It reads until it has enough of them to tell, or the text
+    /// ends, however few bytes each read gives.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        // The head is only copied out when the reader's buffer is too short
+        // to tell from.
+        let mut head = Vec::new();
+        let format = loop {
+            let buf = match reader.fill_buf() {
+                Ok(buf) => buf,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if head.is_empty() && buf.len() >= HEAD_LEN {
+                break Format::of(buf);
+            }
+            if buf.is_empty() {
+                break Format::of(&head);
+            }
+            let n = buf.len().min(HEAD_LEN - head.len());
+            head.extend_from_slice(&buf[..n]);
+            reader.consume(n);
+            if head.len() == HEAD_LEN {
+                break Format::of(&head);
+            }
+        };
+        let reader = io::Cursor::new(head).chain(reader);
+        let inner = match format {
+            Format::Plain => Inner::Plain(reader),
+            #[cfg(feature = "flate2")]
+            Format::Gzip => Inner::Gzip(flate2::bufread::MultiGzDecoder::new(reader)),
+            #[cfg(feature = "zstd")]
+            Format::Zstd => Inner::Zstd(zstd::Decoder::with_buffer(reader)?),
+        };
+        Ok(Self { inner })
+    }
+
+    /// Whether the text is being decompressed.
+    pub fn is_compressed(&self) -> bool {
+        !matches!(self.inner, Inner::Plain(_))
+    }
+}
+
+impl<R: BufRead> Read for Decompress<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match &mut self.inner {
+            Inner::Plain(reader) => reader.read(buf),
+            #[cfg(feature = "flate2")]
+            Inner::Gzip(decoder) => decoder.read(buf),
+            #[cfg(feature = "zstd")]
+            Inner::Zstd(decoder) => decoder.read(buf),
+        }
+    }
+}
+
+impl<R: BufRead> StreamSplitter<Decompress<R>> {
+    /// Creates a splitter for the text read from `reader`, decompressing it
+    /// first if it is compressed with gzip or Zstandard, as far as the
+    /// features enabled allow.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(feature = "flate2", doc = "```rust")]
+    #[cfg_attr(not(feature = "flate2"), doc = "```ignore")]
+    /// # use std::io::{BufReader, Write};
+    /// # use flate2::write::GzEncoder;
+    /// # use flate2::Compression;
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::stream::StreamSplitter;
+    /// # fn main() -> std::io::Result<()> {
+    /// let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    /// encoder.write_all(b"one\ntwo\nthree")?;
+    /// let archive = encoder.finish()?;
+    ///
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let splitter = StreamSplitter::decompress(&re, BufReader::new(&archive[..]))?;
+    /// let v = splitter.collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(v, [&b"one\n"[..], &b"two\n"[..], &b"three"[..]]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn decompress(regex: &Regex, reader: R) -> io::Result<Self> {
+        Ok(Self::new(regex, Decompress::new(reader)?))
+    }
+}
@@ -0,0 +1,303 @@
+//! Splitting a `Stream` of byte chunks, such as an HTTP body, into frames
+//! delimited by a `regex::bytes::Regex`. Only available with the `futures`
+//! feature. With the `futures-io` feature, text read from a
+//! `futures::io::AsyncBufRead`, as used by async-std and smol, can be split
+//! into records too, the same as with the `tokio` module.
+//!
+//! Chunks rarely line up with the frames in them, so matches are found the
+//! same way as in the rest of the `stream` module, wherever the chunk
+//! boundaries fall.
+
+#[cfg(feature = "futures-io")]
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use ::bytes::Bytes;
+use futures_core::{FusedStream, Stream};
+#[cfg(feature = "futures-io")]
+use futures_io::AsyncBufRead;
+use regex::bytes::Regex;
+
+#[cfg(feature = "futures-io")]
+use crate::stream::{into_string, Bom};
+use crate::stream::{Engine, Progress};
+
+pub trait RegexSplitStream: Stream {
+    fn frames(self, regex: &Regex) -> Frames<Self>
+    where
+        Self: Sized;
+}
+
+#[cfg(feature = "futures-io")]
+pub trait RegexSplitRead: AsyncBufRead {
+    fn records(self, regex: &Regex) -> Records<Self>
+    where
+        Self: Sized;
+    fn string_records(self, regex: &Regex) -> StringRecords<Self>
+    where
+        Self: Sized;
+}
+
+/// A stream of the frames of a stream of byte chunks, delimited by a regular
+/// expression match inclusive of the match.
+#[derive(Clone, Debug)]
+pub struct Frames<S> {
+    chunks: S,
+    engine: Engine,
+}
+
+impl<S> Frames<S> {
+    /// Promises that no match is longer than `max` bytes, as
+    /// [`crate::stream::StreamSplitter::max_delimiter_len`] does.
+    pub fn max_delimiter_len(mut self, max: usize) -> Self {
+        self.engine.set_max_match_len(max);
+        self
+    }
+
+    /// Returns a handle on how far the stream has got. See [`Progress`].
+    pub fn progress(&mut self) -> Progress {
+        self.engine.progress()
+    }
+}
+
+impl<S, B, E> Stream for Frames<S>
+where
+    S: Stream<Item = Result<B, E>> + Unpin,
+    B: AsRef<[u8]>,
+{
+    type Item = Result<Bytes, E>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(segment) = this.engine.next_segment() {
+                return Poll::Ready(Some(Ok(Bytes::from(segment))));
+            }
+            if this.engine.is_finished() {
+                return Poll::Ready(None);
+            }
+            match ready!(Pin::new(&mut this.chunks).poll_next(cx)) {
+                Some(Ok(chunk)) => this.engine.push(chunk.as_ref()),
+                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                None => this.engine.finish(),
+            }
+        }
+    }
+}
+
+impl<S, B, E> FusedStream for Frames<S>
+where
+    S: Stream<Item = Result<B, E>> + Unpin,
+    B: AsRef<[u8]>,
+{
+    fn is_terminated(&self) -> bool {
+        self.engine.is_finished()
+    }
+}
+
+impl<S: Stream> RegexSplitStream for S {
+    /// Returns a stream of the frames in a stream of byte chunks, such as
+    /// the `Bytes` of an HTTP body, separated by a match of the regular
+    /// expression, with the match at the end of each frame. These are the
+    /// substrings `split_inclusive` would give for the chunks joined
+    /// together. An error from the chunk stream is passed on. The chunk
+    /// stream needs to be `Unpin` for the frames to be polled; pin it with
+    /// `Box::pin` if it isn't.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::convert::Infallible;
+    /// # use bytes::Bytes;
+    /// # use futures::executor::block_on;
+    /// # use futures::{stream, StreamExt};
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::stream::futures::RegexSplitStream;
+    /// # fn main() {
+    /// let re = Regex::new(r"\n\n").unwrap();
+    /// let body = stream::iter([
+    ///     Ok::<_, Infallible>(Bytes::from_static(b"data: 1\n")),
+    ///     Ok(Bytes::from_static(b"\ndata: 2\n\n")),
+    /// ]);
+    /// let v: Vec<_> = block_on(body.frames(&re).map(Result::unwrap).collect());
+    /// assert_eq!(v, [&b"data: 1\n\n"[..], &b"data: 2\n\n"[..], &b""[..]]);
+    /// # }
+    /// ```
+    fn frames(self, regex: &Regex) -> Frames<Self> {
+        Frames {
+            chunks: self,
+            engine: Engine::new(regex),
+        }
+    }
+}
+
+/// A stream of the records of an async reader delimited by a regular
+/// expression match inclusive of the match, as they are read. Only available
+/// with the `futures-io` feature.
+#[cfg(feature = "futures-io")]
+#[derive(Clone, Debug)]
+pub struct Records<R> {
+    reader: R,
+    engine: Engine,
+}
+
+#[cfg(feature = "futures-io")]
+impl<R: AsyncBufRead> Records<R> {
+    /// Sets the most bytes of text held at once, which bounds the length of
+    /// a record, as [`crate::stream::Records::max_buffered`] does.
+    /// Unbounded by default.
+    pub fn max_buffered(mut self, max: usize) -> Self {
+        self.engine.set_max_buffered(max);
+        self
+    }
+
+    /// Sets the longest record yielded, as
+    /// [`crate::stream::Records::max_segment_len`] does. Unbounded by
+    /// default.
+    pub fn max_segment_len(mut self, max: usize) -> Self {
+        self.engine.set_max_segment(max);
+        self
+    }
+
+    /// Promises that no match is longer than `max` bytes, as
+    /// [`crate::stream::StreamSplitter::max_delimiter_len`] does.
+    pub fn max_delimiter_len(mut self, max: usize) -> Self {
+        self.engine.set_max_match_len(max);
+        self
+    }
+
+    /// Sets what to do with a byte order mark at the start of the text. See
+    /// [`Bom`].
+    pub fn bom(mut self, bom: Bom) -> Self {
+        self.engine.set_bom(bom);
+        self
+    }
+
+    /// Returns a handle on how far the stream has got. See [`Progress`].
+    pub fn progress(&mut self) -> Progress {
+        self.engine.progress()
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<R: AsyncBufRead + Unpin> Stream for Records<R> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        this.engine
+            .poll_next_segment(Pin::new(&mut this.reader), cx, R::poll_fill_buf, R::consume)
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<R: AsyncBufRead + Unpin> FusedStream for Records<R> {
+    fn is_terminated(&self) -> bool {
+        self.engine.is_finished()
+    }
+}
+
+/// A stream of the records of an async reader delimited by a regular
+/// expression match inclusive of the match, as `String`s. Only available
+/// with the `futures-io` feature.
+#[cfg(feature = "futures-io")]
+#[derive(Clone, Debug)]
+pub struct StringRecords<R> {
+    inner: Records<R>,
+}
+
+#[cfg(feature = "futures-io")]
+impl<R: AsyncBufRead> StringRecords<R> {
+    /// Sets the most bytes of text held at once, as
+    /// [`Records::max_buffered`] does.
+    pub fn max_buffered(mut self, max: usize) -> Self {
+        self.inner = self.inner.max_buffered(max);
+        self
+    }
+
+    /// Sets the longest record yielded, as [`Records::max_segment_len`]
+    /// does.
+    pub fn max_segment_len(mut self, max: usize) -> Self {
+        self.inner = self.inner.max_segment_len(max);
+        self
+    }
+
+    /// Promises that no match is longer than `max` bytes, as
+    /// [`Records::max_delimiter_len`] does.
+    pub fn max_delimiter_len(mut self, max: usize) -> Self {
+        self.inner = self.inner.max_delimiter_len(max);
+        self
+    }
+
+    /// Sets what to do with a byte order mark at the start of the text. See
+    /// [`Bom`].
+    pub fn bom(mut self, bom: Bom) -> Self {
+        self.inner = self.inner.bom(bom);
+        self
+    }
+
+    /// Returns a handle on how far the stream has got. See [`Progress`].
+    pub fn progress(&mut self) -> Progress {
+        self.inner.progress()
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<R: AsyncBufRead + Unpin> Stream for StringRecords<R> {
+    type Item = io::Result<String>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let record = ready!(Pin::new(&mut self.get_mut().inner).poll_next(cx));
+        Poll::Ready(record.map(|record| record.and_then(into_string)))
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<R: AsyncBufRead + Unpin> FusedStream for StringRecords<R> {
+    fn is_terminated(&self) -> bool {
+        self.inner.is_terminated()
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<R: AsyncBufRead> RegexSplitRead for R {
+    /// Returns a stream of the records read from the reader, separated by a
+    /// match of the regular expression, with the match at the end of each
+    /// record. These are the substrings `split_inclusive` would give for the
+    /// whole of the text. The reader needs to be `Unpin` for the stream to
+    /// be polled; pin it with `Box::pin` if it isn't.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use futures::executor::block_on;
+    /// # use futures::io::{BufReader, Cursor};
+    /// # use futures::StreamExt;
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::stream::futures::RegexSplitRead;
+    /// # fn main() -> std::io::Result<()> {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let reader = BufReader::with_capacity(2, Cursor::new(b"one\ntwo\r\nthree"));
+    /// let v: Vec<_> = block_on(reader.records(&re).collect());
+    /// let v = v.into_iter().collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(v, [&b"one\n"[..], &b"two\r\n"[..], &b"three"[..]]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn records(self, regex: &Regex) -> Records<Self> {
+        Records {
+            reader: self,
+            engine: Engine::new(regex),
+        }
+    }
+
+    /// Returns a stream like `records` that yields each record as a
+    /// `String`. A record that isn't valid UTF-8 is an error of kind
+    /// `InvalidData`.
+    fn string_records(self, regex: &Regex) -> StringRecords<Self> {
+        StringRecords {
+            inner: self.records(regex),
+        }
+    }
+}
@@ -0,0 +1,202 @@
+//! Splitting text read from a Tokio `AsyncBufRead` into records delimited by
+//! a `regex::bytes::Regex`, as a `Stream`. Only available with the `tokio`
+//! feature.
+//!
+//! The records are the same as the ones the blocking `RegexSplitRead` gives,
+//! found the same way.
+
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use ::tokio::io::AsyncBufRead;
+use futures_core::{FusedStream, Stream};
+use regex::bytes::Regex;
+
+use crate::stream::{into_string, Bom, Engine, Progress};
+
+pub trait RegexSplitRead: AsyncBufRead {
+    fn records(self, regex: &Regex) -> Records<Self>
+    where
+        Self: Sized;
+    fn string_records(self, regex: &Regex) -> StringRecords<Self>
+    where
+        Self: Sized;
+}
+
+/// A stream of the records of an async reader delimited by a regular
+/// expression match inclusive of the match, as they are read.
+#[derive(Clone, Debug)]
+pub struct Records<R> {
+    reader: R,
+    engine: Engine,
+}
+
+impl<R: AsyncBufRead> Records<R> {
+    /// Sets the most bytes of text held at once, which bounds the length of
+    /// a record, as [`crate::stream::Records::max_buffered`] does.
+    /// Unbounded by default.
+    pub fn max_buffered(mut self, max: usize) -> Self {
+        self.engine.set_max_buffered(max);
+        self
+    }
+
+    /// Sets the longest record yielded, as
+    /// [`crate::stream::Records::max_segment_len`] does. Unbounded by
+    /// default.
+    pub fn max_segment_len(mut self, max: usize) -> Self {
+        self.engine.set_max_segment(max);
+        self
+    }
+
+    /// Promises that no match is longer than `max` bytes, as
+    /// [`crate::stream::StreamSplitter::max_delimiter_len`] does.
+    pub fn max_delimiter_len(mut self, max: usize) -> Self {
+        self.engine.set_max_match_len(max);
+        self
+    }
+
+    /// Sets what to do with a byte order mark at the start of the text. See
+    /// [`Bom`].
+    pub fn bom(mut self, bom: Bom) -> Self {
+        self.engine.set_bom(bom);
+        self
+    }
+
+    /// Returns a handle on how far the stream has got. See [`Progress`].
+    pub fn progress(&mut self) -> Progress {
+        self.engine.progress()
+    }
+}
+
+impl<R: AsyncBufRead + Unpin> Stream for Records<R> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        this.engine
+            .poll_next_segment(Pin::new(&mut this.reader), cx, R::poll_fill_buf, R::consume)
+    }
+}
+
+impl<R: AsyncBufRead + Unpin> FusedStream for Records<R> {
+    fn is_terminated(&self) -> bool {
+        self.engine.is_finished()
+    }
+}
+
+/// A stream of the records of an async reader delimited by a regular
+/// expression match inclusive of the match, as `String`s.
+#[derive(Clone, Debug)]
+pub struct StringRecords<R> {
+    inner: Records<R>,
+}
+
+impl<R: AsyncBufRead> StringRecords<R> {
+    /// Sets the most bytes of text held at once, as
+    /// [`Records::max_buffered`] does.
+    pub fn max_buffered(mut self, max: usize) -> Self {
+        self.inner = self.inner.max_buffered(max);
+        self
+    }
+
+    /// Sets the longest record yielded, as [`Records::max_segment_len`]
+    /// does.
+    pub fn max_segment_len(mut self, max: usize) -> Self {
+        self.inner = self.inner.max_segment_len(max);
+        self
+    }
+
+    /// Promises that no match is longer than `max` bytes, as
+    /// [`Records::max_delimiter_len`] does.
+    pub fn max_delimiter_len(mut self, max: usize) -> Self {
+        self.inner = self.inner.max_delimiter_len(max);
+        self
+    }
+
+    /// Sets what to do with a byte order mark at the start of the text. See
+    /// [`Bom`].
+    pub fn bom(mut self, bom: Bom) -> Self {
+        self.inner = self.inner.bom(bom);
+        self
+    }
+
+    /// Returns a handle on how far the stream has got. See [`Progress`].
+    pub fn progress(&mut self) -> Progress {
+        self.inner.progress()
+    }
+}
+
+impl<R: AsyncBufRead + Unpin> Stream for StringRecords<R> {
+    type Item = io::Result<String>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let record = ready!(Pin::new(&mut self.get_mut().inner).poll_next(cx));
+        Poll::Ready(record.map(|record| record.and_then(into_string)))
+    }
+}
+
+impl<R: AsyncBufRead + Unpin> FusedStream for StringRecords<R> {
+    fn is_terminated(&self) -> bool {
+        self.inner.is_terminated()
+    }
+}
+
+impl<R: AsyncBufRead> RegexSplitRead for R {
+    /// Returns a stream of the records read from the reader, separated by a
+    /// match of the regular expression, with the match at the end of each
+    /// record. These are the substrings `split_inclusive` would give for the
+    /// whole of the text. The reader needs to be `Unpin` for the stream to
+    /// be polled; pin it with `Box::pin` if it isn't.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use futures::executor::block_on;
+    /// # use futures::StreamExt;
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::stream::tokio::RegexSplitRead;
+    /// # fn main() -> std::io::Result<()> {
+    /// let re = Regex::new(r"\r?\n").unwrap();
+    /// let reader = &b"one\ntwo\r\nthree"[..];
+    /// let v: Vec<_> = block_on(reader.records(&re).collect());
+    /// let v = v.into_iter().collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(v, [&b"one\n"[..], &b"two\r\n"[..], &b"three"[..]]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn records(self, regex: &Regex) -> Records<Self> {
+        Records {
+            reader: self,
+            engine: Engine::new(regex),
+        }
+    }
+
+    /// Returns a stream like `records` that yields each record as a
+    /// `String`. A record that isn't valid UTF-8 is an error of kind
+    /// `InvalidData`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use futures::executor::block_on;
+    /// # use futures::StreamExt;
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::stream::tokio::RegexSplitRead;
+    /// # fn main() {
+    /// let re = Regex::new(r";").unwrap();
+    /// let mut records = (&b"a;\xFF;c"[..]).string_records(&re);
+    /// block_on(async {
+    ///     assert_eq!(records.next().await.unwrap().unwrap(), "a;");
+    ///     assert!(records.next().await.unwrap().is_err());
+    ///     assert_eq!(records.next().await.unwrap().unwrap(), "c");
+    ///     assert!(records.next().await.is_none());
+    /// });
+    /// # }
+    /// ```
+    fn string_records(self, regex: &Regex) -> StringRecords<Self> {
+        StringRecords {
+            inner: self.records(regex),
+        }
+    }
+}
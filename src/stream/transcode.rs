@@ -0,0 +1,256 @@
+//! Splitting text in a legacy encoding, such as UTF-16LE, Shift_JIS or
+//! windows-1252, decoding it to UTF-8 a block at a time as it is read. Only
+//! available with the `encoding_rs` feature.
+//!
+//! Each segment is reported with its offsets both in the decoded text and in
+//! the bytes read, so that it can be found again in the original.
+
+use std::io::{self, Read};
+use std::iter::FusedIterator;
+use std::ops::Range;
+
+use encoding_rs::{Decoder, Encoding};
+use regex::bytes::Regex;
+
+use crate::stream::{into_string, Bom, Engine, Progress, BLOCK_SIZE};
+
+/// A segment of decoded text, with where it lies in the decoded text and in
+/// the bytes it was decoded from.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DecodedSegment {
+    /// The segment, decoded, including its delimiter.
+    pub text: String,
+    /// The byte offsets of the segment in the decoded text.
+    pub range: Range<u64>,
+    /// The byte offsets of the segment in the text as it was read.
+    pub source: Range<u64>,
+}
+
+/// Splits text in any encoding `encoding_rs` knows into segments delimited
+/// by a regular expression match inclusive of the match, decoding it as it
+/// is read from `reader`. The regex matches against the text decoded to
+/// UTF-8. Malformed text is decoded with U+FFFD in its place.
+///
+/// Empty matches inside a character are passed over, as they are when
+/// splitting a `str`. A segment that still splits a character, which only a
+/// pattern matching part of one can give, is an error of kind `InvalidData`.
+///
+/// # Example
+///
+/// ```rust
+/// # use encoding_rs::SHIFT_JIS;
+/// # use regex::bytes::Regex;
+/// # use crate::regex_split::stream::transcode::TranscodingSplitter;
+/// # fn main() -> std::io::Result<()> {
+/// let re = Regex::new(r"\n").unwrap();
+/// let (text, _, _) = SHIFT_JIS.encode("あ\nいう\n");
+/// let v = TranscodingSplitter::new(&re, &text[..], SHIFT_JIS).collect::<Result<Vec<_>, _>>()?;
+/// assert_eq!(v[1].text, "いう\n");
+/// assert_eq!((v[1].range.clone(), v[1].source.clone()), (4..11, 3..8));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct TranscodingSplitter<R> {
+    reader: R,
+    encoding: &'static Encoding,
+    decoder: Decoder,
+    engine: Engine,
+    mapper: Mapper,
+    eof: bool,
+}
+
+impl<R: Read> TranscodingSplitter<R> {
+    /// Creates a splitter for the text read from `reader` in `encoding`.
+    pub fn new(regex: &Regex, reader: R, encoding: &'static Encoding) -> Self {
+        let mut engine = Engine::new(regex);
+        engine.set_utf8();
+        Self {
+            reader,
+            encoding,
+            decoder: decoder(encoding, Bom::Keep),
+            engine,
+            mapper: Mapper::new(decoder(encoding, Bom::Keep)),
+            eof: false,
+        }
+    }
+
+    /// Sets what to do with a byte order mark at the start of the text. See
+    /// [`Bom`]. With [`Bom::Honor`], the encoding given is only used for text
+    /// without one. A mark dropped still counts towards the offsets in the
+    /// text as read, but not towards those in the decoded text.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use encoding_rs::WINDOWS_1252;
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::stream::Bom;
+    /// # use crate::regex_split::stream::transcode::TranscodingSplitter;
+    /// # fn main() -> std::io::Result<()> {
+    /// let re = Regex::new(r"\n").unwrap();
+    /// let text = b"\xFF\xFEa\0\n\0b\0";
+    /// let splitter = TranscodingSplitter::new(&re, &text[..], WINDOWS_1252).bom(Bom::Honor);
+    /// let v = splitter.collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!((v[0].text.as_str(), v[0].source.clone()), ("a\n", 0..6));
+    /// assert_eq!((v[1].text.as_str(), v[1].range.clone()), ("b", 2..3));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bom(mut self, bom: Bom) -> Self {
+        self.decoder = decoder(self.encoding, bom);
+        self.mapper = Mapper::new(decoder(self.encoding, bom));
+        self
+    }
+
+    /// Sets the most bytes of decoded text held at once, as
+    /// [`crate::stream::Records::max_buffered`] does. Unbounded by default.
+    pub fn max_buffered(mut self, max: usize) -> Self {
+        self.engine.set_max_buffered(max);
+        self
+    }
+
+    /// Sets the longest segment yielded, in bytes of decoded text, as
+    /// [`crate::stream::Records::max_segment_len`] does. Unbounded by
+    /// default.
+    pub fn max_segment_len(mut self, max: usize) -> Self {
+        self.engine.set_max_segment(max);
+        self
+    }
+
+    /// Returns a handle on how far the splitter has got. See [`Progress`].
+    /// The bytes it counts are bytes of decoded text.
+    pub fn progress(&mut self) -> Progress {
+        self.engine.progress()
+    }
+
+    // Reads the next block of text, decodes it into the engine and keeps it
+    // for the mapper.
+    fn fill(&mut self) -> io::Result<()> {
+        self.engine.room()?;
+        let mut block = [0; BLOCK_SIZE];
+        let n = match self.reader.read(&mut block) {
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        self.eof = n == 0;
+        let block = &block[..n];
+        let mut decoded = vec![0; max_utf8_len(&self.decoder, block.len())];
+        let (_, _, written, _) = self.decoder.decode_to_utf8(block, &mut decoded, self.eof);
+        self.engine.push(&decoded[..written]);
+        self.mapper.raw.extend_from_slice(block);
+        if self.eof {
+            self.engine.finish();
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Iterator for TranscodingSplitter<R> {
+    type Item = io::Result<DecodedSegment>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let start = self.engine.offset();
+            if let Some(segment) = self.engine.try_next_segment() {
+                let text = match segment.and_then(into_string) {
+                    Ok(text) => text,
+                    Err(e) => return Some(Err(e)),
+                };
+                let end = start + text.len() as u64;
+                let source_start = self.mapper.source;
+                let source_end = self.mapper.advance_to(end, self.eof);
+                return Some(Ok(DecodedSegment {
+                    text,
+                    range: start..end,
+                    source: source_start..source_end,
+                }));
+            }
+            if self.engine.is_finished() {
+                return None;
+            }
+            if let Err(e) = self.fill() {
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+impl<R: Read> FusedIterator for TranscodingSplitter<R> {}
+
+// Tells where in the bytes read the decoded text reaches a given offset, by
+// decoding the bytes again with a decoder of its own, which lags behind the
+// one feeding the engine. It only ever goes forward, to the end of each
+// segment in turn.
+#[derive(Debug)]
+struct Mapper {
+    decoder: Decoder,
+
+    // The bytes read that the mapper has yet to decode, and how far it has
+    // got in the bytes read and in the decoded text.
+    raw: Vec<u8>,
+    source: u64,
+    decoded: u64,
+}
+
+impl Mapper {
+    fn new(decoder: Decoder) -> Self {
+        Self {
+            decoder,
+            raw: Vec::new(),
+            source: 0,
+            decoded: 0,
+        }
+    }
+
+    // Decodes up to where the decoded text reaches `target`, and returns the
+    // offset of the byte read just after that. The bytes are taken in
+    // batches that can't decode past `target`, then one at a time, so that
+    // it stops right at the byte that ends the character before `target`.
+    fn advance_to(&mut self, target: u64, eof: bool) -> u64 {
+        let mut taken = 0;
+        let mut scratch = Vec::new();
+        while self.decoded < target {
+            let left = self.raw.len() - taken;
+            let room = usize::try_from(target - self.decoded).unwrap_or(usize::MAX);
+            let mut n = (room / 4).clamp(1, left.max(1)).min(left);
+            while n > 1 && max_utf8_len(&self.decoder, n) >= room {
+                n /= 2;
+            }
+
+            // At the end of the text, the last byte flushes out whatever the
+            // decoder holds on to, as nothing is left to complete it.
+            let last = eof && n == left;
+            if n == 0 && !last {
+                break;
+            }
+            scratch.resize(max_utf8_len(&self.decoder, n), 0);
+            let input = &self.raw[taken..taken + n];
+            let (_, read, written, _) = self.decoder.decode_to_utf8(input, &mut scratch, last);
+            taken += read;
+            self.source += read as u64;
+            self.decoded += written as u64;
+            if n == 0 {
+                break;
+            }
+        }
+        self.raw.drain(..taken);
+        self.source
+    }
+}
+
+fn decoder(encoding: &'static Encoding, bom: Bom) -> Decoder {
+    match bom {
+        Bom::Keep => encoding.new_decoder_without_bom_handling(),
+        Bom::Strip => encoding.new_decoder_with_bom_removal(),
+        Bom::Honor => encoding.new_decoder(),
+    }
+}
+
+// The most bytes of UTF-8 that decoding `n` more bytes can give.
+fn max_utf8_len(decoder: &Decoder, n: usize) -> usize {
+    decoder
+        .max_utf8_buffer_length(n)
+        .expect("block of text too large to decode")
+}
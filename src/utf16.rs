@@ -0,0 +1,214 @@
+//! Splitting for UTF-16 text, such as strings from Windows APIs or
+//! JavaScript, with a `regex::bytes::Regex`.
+//!
+//! The regex crate only searches UTF-8, so the text is transcoded a window at
+//! a time as the search gets to it, with unpaired surrogates searched as
+//! U+FFFD, and split as a stream is: no more of it is held as UTF-8 than the
+//! substring being split off, short of a window. The substrings yielded are
+//! slices of the original `[u16]`, found by walking their lengths back into
+//! UTF-16 code units as they come.
+//!
+//! The regex is a `regex::bytes::Regex`, prepared once with
+//! [`Splitter::new`] and used as it was built, options and all, to find
+//! matches. As in [`crate::stream`], a match found near the end of a window
+//! is only taken once a DFA built from the pattern tells that the text after
+//! it can't change it, so for a match to carry on across windows like it
+//! would in a `str`, options set on a `RegexBuilder` need to be written into
+//! the pattern as flags, like `(?i)`, as well.
+
+use std::char;
+use std::iter::FusedIterator;
+
+use regex::bytes::Regex;
+
+use crate::stream::Engine;
+
+// How many UTF-16 code units are transcoded at a time.
+const WINDOW: usize = 4 * 1024;
+
+/// A regex prepared for splitting UTF-16 text. Preparing it takes building
+/// a DFA for the pattern, so make one and split every text with it.
+///
+/// Empty matches inside a character are passed over, as they are when
+/// splitting a `str`. A match of part of a character, which only a pattern
+/// with Unicode turned off can give, ends its substring after the
+/// character.
+///
+/// # Example
+///
+/// ```rust
+/// # use regex::bytes::Regex;
+/// # use crate::regex_split::utf16::Splitter;
+/// # fn main() {
+/// let splitter = Splitter::new(&Regex::new(r"\r?\n").unwrap());
+/// let text: Vec<u16> = "héllo\r\nwörld".encode_utf16().collect();
+/// let v: Vec<String> = splitter
+///     .split_inclusive(&text)
+///     .map(|s| String::from_utf16(s).unwrap())
+///     .collect();
+/// assert_eq!(v, ["héllo\r\n", "wörld"]);
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Splitter {
+    engine: Engine,
+}
+
+impl Splitter {
+    /// Prepares `regex` for splitting UTF-16 text.
+    pub fn new(regex: &Regex) -> Self {
+        let mut engine = Engine::new(regex);
+        engine.set_utf8();
+        Self { engine }
+    }
+
+    /// Returns an iterator of substrings of UTF-16 `text` separated by a
+    /// match of the regular expression, with the match at the end of each
+    /// substring.
+    pub fn split_inclusive<'t>(&self, text: &'t [u16]) -> SplitInclusive<'t> {
+        SplitInclusive {
+            inner: Utf16Split::new(self.engine.clone(), text),
+        }
+    }
+
+    /// Returns an iterator of substrings of UTF-16 `text` separated by a
+    /// match of the regular expression, with the match at the start of each
+    /// substring.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use regex::bytes::Regex;
+    /// # use crate::regex_split::utf16::Splitter;
+    /// # fn main() {
+    /// let splitter = Splitter::new(&Regex::new(r"-").unwrap());
+    /// let text: Vec<u16> = "😀-a-b".encode_utf16().collect();
+    /// let v: Vec<&[u16]> = splitter.split_inclusive_left(&text).collect();
+    /// assert_eq!(v, [&text[..2], &text[2..4], &text[4..]]);
+    /// # }
+    /// ```
+    pub fn split_inclusive_left<'t>(&self, text: &'t [u16]) -> SplitInclusiveLeft<'t> {
+        SplitInclusiveLeft {
+            inner: Utf16Split::new(self.engine.clone(), text),
+        }
+    }
+}
+
+/// Yields all substrings of a UTF-16 text delimited by a regular expression
+/// match inclusive of the match.
+///
+/// `'t` is the lifetime of the text being split.
+#[derive(Clone, Debug)]
+pub struct SplitInclusive<'t> {
+    inner: Utf16Split<'t>,
+}
+
+impl<'t> Iterator for SplitInclusive<'t> {
+    type Item = &'t [u16];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next_slice(false)
+    }
+}
+
+impl<'t> FusedIterator for SplitInclusive<'t> {}
+
+/// Yields all substrings of a UTF-16 text delimited by a regular expression
+/// match, with the match at the front of each substring.
+///
+/// `'t` is the lifetime of the text being split.
+#[derive(Clone, Debug)]
+pub struct SplitInclusiveLeft<'t> {
+    inner: Utf16Split<'t>,
+}
+
+impl<'t> Iterator for SplitInclusiveLeft<'t> {
+    type Item = &'t [u16];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next_slice(true)
+    }
+}
+
+impl<'t> FusedIterator for SplitInclusiveLeft<'t> {}
+
+#[derive(Clone, Debug)]
+struct Utf16Split<'t> {
+    engine: Engine,
+
+    // How much of the text has been transcoded and handed to the engine,
+    // and where the next substring begins, in UTF-16 code units.
+    fed: usize,
+    utf16: usize,
+    text: &'t [u16],
+
+    // How many bytes of transcoded text the next substring begins past
+    // where the engine has it, after a match that ended inside a character.
+    ahead: usize,
+}
+
+impl<'t> Utf16Split<'t> {
+    fn new(engine: Engine, text: &'t [u16]) -> Self {
+        Self {
+            engine,
+            fed: 0,
+            utf16: 0,
+            text,
+            ahead: 0,
+        }
+    }
+
+    fn next_slice(&mut self, left: bool) -> Option<&'t [u16]> {
+        loop {
+            if let Some(range) = self.engine.next_range(left) {
+                let start = self.utf16;
+                self.walk(range.len());
+                return Some(&self.text[start..self.utf16]);
+            }
+            if self.engine.is_finished() {
+                return None;
+            }
+            self.feed();
+        }
+    }
+
+    // Transcodes the next window of the text for the engine, never cutting
+    // a surrogate pair in two, or marks the end of the text.
+    fn feed(&mut self) {
+        if self.fed == self.text.len() {
+            self.engine.finish();
+            return;
+        }
+        let mut end = (self.fed + WINDOW).min(self.text.len());
+        if end < self.text.len() && (0xD800..0xDC00).contains(&self.text[end - 1]) {
+            end += 1;
+        }
+        let utf8: String = char::decode_utf16(self.text[self.fed..end].iter().copied())
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect();
+        self.engine.push(utf8.as_bytes());
+        self.fed = end;
+    }
+
+    // Moves the start of the next substring on past `len` bytes of the
+    // transcoded text, or past the character they end in.
+    fn walk(&mut self, len: usize) {
+        let mut walked = self.ahead;
+        for c in char::decode_utf16(self.text[self.utf16..].iter().copied()) {
+            if walked >= len {
+                break;
+            }
+            match c {
+                Ok(c) => {
+                    walked += c.len_utf8();
+                    self.utf16 += c.len_utf16();
+                }
+                Err(_) => {
+                    walked += char::REPLACEMENT_CHARACTER.len_utf8();
+                    self.utf16 += 1;
+                }
+            }
+        }
+        self.ahead = walked.saturating_sub(len);
+    }
+}
@@ -0,0 +1,796 @@
+use regex::Regex;
+use regex_split::{RegexSplit, SetSplitter};
+
+fn newline() -> Regex {
+    Regex::new(r"\n").unwrap()
+}
+
+#[test]
+fn empty_input() {
+    let re = newline();
+    let empty: [&str; 1] = [""];
+
+    assert_eq!(re.split_inclusive("").collect::<Vec<_>>(), empty);
+    assert_eq!(re.split_inclusive_left("").collect::<Vec<_>>(), empty);
+    assert_eq!(re.rsplitn_inclusive("", 3).collect::<Vec<_>>(), empty);
+    assert_eq!(re.split_inclusive_rev("").collect::<Vec<_>>(), empty);
+    assert_eq!(re.split_inclusive_left_rev("").collect::<Vec<_>>(), empty);
+    assert_eq!(re.split_inclusive_both("").collect::<Vec<_>>(), empty);
+    assert_eq!(
+        re.split_inclusive_ranges("").collect::<Vec<_>>(),
+        vec![0..0]
+    );
+    assert_eq!(
+        re.split_inclusive_left_ranges("").collect::<Vec<_>>(),
+        vec![0..0]
+    );
+    assert_eq!(re.split_inclusive("").rev().collect::<Vec<_>>(), empty);
+}
+
+#[test]
+fn only_a_match() {
+    let re = newline();
+
+    assert_eq!(re.split_inclusive("\n").collect::<Vec<_>>(), ["\n", ""]);
+    assert_eq!(
+        re.split_inclusive_left("\n").collect::<Vec<_>>(),
+        ["", "\n"]
+    );
+    assert_eq!(
+        re.rsplitn_inclusive("\n", 3).collect::<Vec<_>>(),
+        ["", "\n"]
+    );
+    assert_eq!(
+        re.split_inclusive_both("\n").collect::<Vec<_>>(),
+        ["\n", "\n"]
+    );
+    assert_eq!(
+        re.split_inclusive_ranges("\n").collect::<Vec<_>>(),
+        [0..1, 1..1]
+    );
+    assert_eq!(
+        re.split_inclusive_left_ranges("\n").collect::<Vec<_>>(),
+        [0..0, 0..1]
+    );
+}
+
+#[test]
+fn adjacent_matches() {
+    let re = newline();
+    let text = "a\n\n\nb";
+
+    assert_eq!(
+        re.split_inclusive(text).collect::<Vec<_>>(),
+        ["a\n", "\n", "\n", "b"]
+    );
+    assert_eq!(
+        re.split_inclusive_left(text).collect::<Vec<_>>(),
+        ["a", "\n", "\n", "\nb"]
+    );
+    assert_eq!(
+        re.rsplitn_inclusive(text, 10).collect::<Vec<_>>(),
+        ["b", "\n", "\n", "a\n"]
+    );
+    assert_eq!(
+        re.split_inclusive(text).rev().collect::<Vec<_>>(),
+        ["b", "\n", "\n", "a\n"]
+    );
+    assert_eq!(
+        re.split_inclusive_ranges(text).collect::<Vec<_>>(),
+        [0..2, 2..3, 3..4, 4..5]
+    );
+    assert_eq!(
+        re.split_inclusive_segments(text)
+            .map(|segment| segment.delimiter())
+            .collect::<Vec<_>>(),
+        [Some("\n"), Some("\n"), Some("\n"), None]
+    );
+}
+
+#[test]
+fn leading_match() {
+    let re = newline();
+    let text = "\na";
+
+    assert_eq!(re.split_inclusive(text).collect::<Vec<_>>(), ["\n", "a"]);
+    assert_eq!(
+        re.split_inclusive_left(text).collect::<Vec<_>>(),
+        ["", "\na"]
+    );
+    assert_eq!(
+        re.rsplitn_inclusive(text, 2).collect::<Vec<_>>(),
+        ["a", "\n"]
+    );
+}
+
+#[test]
+fn trailing_match() {
+    let re = newline();
+    let text = "a\nb\n";
+
+    assert_eq!(
+        re.split_inclusive(text).collect::<Vec<_>>(),
+        ["a\n", "b\n", ""]
+    );
+    assert_eq!(
+        re.split_inclusive_terminator(text).collect::<Vec<_>>(),
+        ["a\n", "b\n"]
+    );
+    assert_eq!(
+        re.split_inclusive_left(text).collect::<Vec<_>>(),
+        ["a", "\nb", "\n"]
+    );
+    assert_eq!(
+        re.rsplitn_inclusive(text, 2).collect::<Vec<_>>(),
+        ["", "a\nb\n"]
+    );
+    assert_eq!(
+        re.split_inclusive_ranges(text).collect::<Vec<_>>(),
+        [0..2, 2..4, 4..4]
+    );
+    assert_eq!(
+        re.split_with_delimiters(text)
+            .last()
+            .map(|(s, m)| (s, m.is_some())),
+        Some(("", false))
+    );
+}
+
+#[test]
+fn no_match() {
+    let re = newline();
+
+    assert_eq!(re.split_inclusive("abc").collect::<Vec<_>>(), ["abc"]);
+    assert_eq!(re.split_inclusive_left("abc").collect::<Vec<_>>(), ["abc"]);
+    assert_eq!(re.rsplitn_inclusive("abc", 2).collect::<Vec<_>>(), ["abc"]);
+    assert_eq!(re.split_inclusive_both("abc").collect::<Vec<_>>(), ["abc"]);
+}
+
+#[test]
+fn empty_matches() {
+    let re = Regex::new(r"").unwrap();
+
+    assert_eq!(
+        re.split_inclusive("ab").collect::<Vec<_>>(),
+        ["", "a", "b", ""]
+    );
+    assert_eq!(
+        re.split_inclusive_left("ab").collect::<Vec<_>>(),
+        ["", "a", "b", ""]
+    );
+    assert_eq!(re.split_inclusive("").collect::<Vec<_>>(), ["", ""]);
+
+    // Every finder steps past an empty match by a whole character.
+    let forward: Vec<&str> = re.split_inclusive("aé😀").collect();
+    assert_eq!(forward, ["", "a", "é", "😀", ""]);
+    let mut backward: Vec<&str> = re.split_inclusive_rev("aé😀").collect();
+    backward.reverse();
+    assert_eq!(backward, forward);
+    let v: Vec<&str> = Regex::new(r"()")
+        .unwrap()
+        .split_on_group("é😀", 1)
+        .collect();
+    assert_eq!(v, ["", "é", "😀", ""]);
+}
+
+#[test]
+fn stays_finished() {
+    let re = newline();
+
+    let mut iter = re.split_inclusive("a\n");
+    assert_eq!(iter.by_ref().count(), 2);
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+    assert_eq!(iter.remainder(), None);
+    assert_eq!(iter.offset(), 2);
+
+    let mut iter = re.split_inclusive_left("a\n");
+    assert_eq!(iter.by_ref().count(), 2);
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.remainder(), None);
+
+    let mut iter = re.split_inclusive_both("a\nb");
+    assert_eq!(iter.by_ref().count(), 2);
+    assert_eq!(iter.next(), None);
+
+    let mut iter = re.split_inclusive_captures("a\nb");
+    assert_eq!(iter.by_ref().count(), 2);
+    assert!(iter.next().is_none());
+
+    let mut iter = re.split_inclusive_segments("a\nb");
+    assert_eq!(iter.by_ref().count(), 2);
+    assert!(iter.next().is_none());
+
+    let mut iter = re.split_inclusive_ranges("a\nb");
+    assert_eq!(iter.by_ref().count(), 2);
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+fn size_hint_lower_bound() {
+    use regex_split::{Keep, Splitter, ZeroWidth};
+
+    // Skipping empty substrings can leave nothing to yield, so the lower
+    // bound mustn't promise one.
+    let comma = Regex::new(",").unwrap();
+    let splitter = Splitter::new(&comma).keep(Keep::Neither).skip_empty(true);
+    let split = splitter.split(",");
+    assert_eq!(split.size_hint().0, 0);
+    assert_eq!(split.count(), 0);
+
+    // Neither can a zero-width match that stops iteration.
+    let re = Regex::new(",*").unwrap();
+    let split = re.split_inclusive("a,b").zero_width(ZeroWidth::Error);
+    assert_eq!(split.size_hint().0, 0);
+    assert_eq!(split.count(), 0);
+    let split = re.split_inclusive_left("a,b").zero_width(ZeroWidth::Error);
+    assert_eq!(split.size_hint().0, 0);
+    assert_eq!(split.count(), 0);
+
+    // Without those policies some text left over is always yielded.
+    let split = comma.split_inclusive("a,b");
+    assert_eq!(split.size_hint().0, 1);
+    assert_eq!(split.count(), 2);
+}
+
+#[test]
+fn split_recursive_edges() {
+    use regex_split::split_recursive;
+
+    // A delimiter at the end of a region doesn't leave an empty piece.
+    let patterns = [Regex::new(r"\n\n").unwrap(), Regex::new(r"\. ").unwrap()];
+    let v: Vec<_> = split_recursive("A longer one. It goes on. ", &patterns, 16).collect();
+    assert_eq!(v, [("A longer one. ", Some(1)), ("It goes on. ", None)]);
+
+    let v: Vec<_> = split_recursive("Short.\n\nA longer one. ", &patterns, 8).collect();
+    assert_eq!(v, [("Short.\n\n", Some(0)), ("A longer one. ", None)]);
+
+    // Empty text is still one empty piece, as with `split_inclusive`.
+    let v: Vec<_> = split_recursive("", &patterns, 16).collect();
+    assert_eq!(v, [("", None)]);
+}
+
+#[test]
+fn stream_bom_edges() {
+    use regex::bytes::Regex;
+    use regex_split::stream::{Bom, IncrementalSplitter};
+
+    let re = Regex::new(r"\n").unwrap();
+
+    // A mark split across chunks is still found.
+    let mut splitter = IncrementalSplitter::new(&re).bom(Bom::Honor);
+    assert_eq!(splitter.try_feed(b"\xFF").unwrap().count(), 0);
+    let e = splitter.try_feed(b"\xFEa\0\n\0").unwrap_err();
+    assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(splitter.finish().count(), 0);
+
+    // Only `Honor` minds a UTF-16 mark; a UTF-8 one is dropped all the same.
+    let mut splitter = IncrementalSplitter::new(&re).bom(Bom::Strip);
+    assert!(splitter.try_feed(b"\xFF\xFEa\0\n\0").is_ok());
+    let v: Vec<Vec<u8>> = splitter.finish().collect();
+    assert_eq!(v, [&b"\xFF\xFEa\0\n"[..], &b"\0"[..]]);
+    let mut splitter = IncrementalSplitter::new(&re).bom(Bom::Honor);
+    assert!(splitter.try_feed(b"\xEF\xBB\xBFa\nb").is_ok());
+    let v: Vec<Vec<u8>> = splitter.finish().collect();
+    assert_eq!(v, [&b"a\n"[..], &b"b"[..]]);
+}
+
+#[test]
+fn split_between_edges() {
+    let open = Regex::new(r"\[").unwrap();
+    let close = Regex::new(r"\]").unwrap();
+
+    assert_eq!(open.split_between("", &close).count(), 0);
+    assert_eq!(open.split_between("[]", &close).collect::<Vec<_>>(), [""]);
+    assert_eq!(
+        open.split_between("[a][b", &close).collect::<Vec<_>>(),
+        ["a", "b"]
+    );
+
+    // Empty markers still make progress, one character at a time.
+    let empty = Regex::new(r"").unwrap();
+    assert_eq!(
+        empty.split_between("é", &empty).collect::<Vec<_>>(),
+        ["", ""]
+    );
+}
+
+#[test]
+fn set_splitter_edges() {
+    let set = regex::RegexSet::new([r",", r";"]).unwrap();
+    let splitter = SetSplitter::new(&set).unwrap();
+
+    assert_eq!(
+        splitter.split_inclusive("").collect::<Vec<_>>(),
+        [("", None)]
+    );
+    assert_eq!(
+        splitter.split_inclusive(",;").collect::<Vec<_>>(),
+        [(",", Some(0)), (";", Some(1)), ("", None)]
+    );
+    assert_eq!(
+        splitter.split_inclusive_left(",;").collect::<Vec<_>>(),
+        [("", None), (",", Some(0)), (";", Some(1))]
+    );
+}
+
+#[test]
+fn bytes_edges() {
+    use regex::bytes::Regex;
+    use regex_split::bytes::RegexSplit;
+
+    let re = Regex::new(r"\n").unwrap();
+
+    assert_eq!(re.split_inclusive(b"").collect::<Vec<_>>(), [&b""[..]]);
+    assert_eq!(
+        re.split_inclusive(b"a\n\nb\n").collect::<Vec<_>>(),
+        [&b"a\n"[..], &b"\n"[..], &b"b\n"[..], &b""[..]]
+    );
+    assert_eq!(
+        re.split_inclusive_left(b"\na\n").collect::<Vec<_>>(),
+        [&b""[..], &b"\na"[..], &b"\n"[..]]
+    );
+    assert_eq!(
+        re.rsplitn_inclusive(b"a\n\n", 2).collect::<Vec<_>>(),
+        [&b""[..], &b"a\n\n"[..]]
+    );
+
+    let mut iter = re.split_inclusive(b"a\n");
+    assert_eq!(iter.by_ref().count(), 2);
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.remainder(), None);
+}
+
+#[test]
+fn parallel_edges() {
+    use regex_split::parallel;
+
+    // Matches that straddle the cuts between shards, or span whole shards.
+    for (pattern, text) in [
+        (r"\n+", "a\n\n\n\nb\n\n\n\nc"),
+        (r"x{3,}|x", "xxxxxxxxxxaxx"),
+        (r"a?", "baab"),
+        (r"\b", "one two"),
+        (r"\n", ""),
+        (r"\n", "\n"),
+    ] {
+        let re = Regex::new(pattern).unwrap();
+        let want: Vec<&str> = re.split_inclusive(text).collect();
+        for shards in 0..=text.len() + 1 {
+            assert_eq!(parallel::split_inclusive(&re, text, shards), want);
+        }
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn par_split_inclusive_edges() {
+    use rayon::prelude::*;
+    use regex_split::parallel::ParRegexSplit;
+
+    // However the pool divides the substrings, and from either end, they come
+    // out as on a single thread.
+    for (pattern, text) in [
+        (r"\n+", "a\n\n\n\nb\n\n\n\nc"),
+        (r"x{3,}|x", "xxxxxxxxxxaxx"),
+        (r"a?", "baab"),
+        (r"\n", ""),
+        (r"\n", "1\n2\n3\n4\n5\n6\n7\n8\n9\n"),
+    ] {
+        let re = Regex::new(pattern).unwrap();
+        let want: Vec<&str> = re.split_inclusive(text).collect();
+        for threads in 1..=6 {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            pool.install(|| {
+                let split = re.par_split_inclusive(text);
+                assert_eq!(split.len(), want.len());
+                assert_eq!(split.clone().collect::<Vec<_>>(), want);
+                assert_eq!(split.clone().with_max_len(1).collect::<Vec<_>>(), want);
+                let mut rev: Vec<&str> = split.clone().rev().collect();
+                rev.reverse();
+                assert_eq!(rev, want);
+                let skipped: Vec<&str> = split.clone().skip(1).collect();
+                assert_eq!(skipped, want[1.min(want.len())..]);
+                assert_eq!(
+                    split.filter(|s| !s.is_empty()).count(),
+                    want.iter().filter(|s| !s.is_empty()).count()
+                );
+            });
+        }
+    }
+}
+
+#[cfg(feature = "utf16")]
+#[test]
+fn utf16_edges() {
+    use regex::bytes::RegexBuilder;
+    use regex_split::utf16::Splitter as Utf16Splitter;
+
+    // Long enough to be transcoded in several windows, with surrogate pairs
+    // and matches across where the windows meet.
+    let long = "ab😀\n".repeat(3000) + &"x".repeat(5000) + "😀";
+    for pattern in [r"\n", r"\n|x+", "", r"\b", r"😀+", r"(?m)$"] {
+        let re = Regex::new(pattern).unwrap();
+        let splitter = Utf16Splitter::new(&regex::bytes::Regex::new(pattern).unwrap());
+        for text in ["", "a", "é😀\n", long.as_str()] {
+            let utf16: Vec<u16> = text.encode_utf16().collect();
+            let want: Vec<String> = RegexSplit::split_inclusive(&re, text)
+                .map(str::to_owned)
+                .collect();
+            let got: Vec<String> = splitter
+                .split_inclusive(&utf16)
+                .map(|s| String::from_utf16(s).unwrap())
+                .collect();
+            assert_eq!(got, want, "{pattern:?}");
+            let want: Vec<String> = RegexSplit::split_inclusive_left(&re, text)
+                .map(str::to_owned)
+                .collect();
+            let got: Vec<String> = splitter
+                .split_inclusive_left(&utf16)
+                .map(|s| String::from_utf16(s).unwrap())
+                .collect();
+            assert_eq!(got, want, "{pattern:?}");
+        }
+    }
+
+    // Options the regex was built with are searched with.
+    let re = RegexBuilder::new("x").case_insensitive(true).build().unwrap();
+    let text: Vec<u16> = "aXbxc".encode_utf16().collect();
+    let v: Vec<&[u16]> = Utf16Splitter::new(&re).split_inclusive(&text).collect();
+    assert_eq!(v, [&text[..2], &text[2..4], &text[4..]]);
+
+    // A match of part of a character ends its substring after the character,
+    // without throwing the substrings after it out of step.
+    let re = regex::bytes::Regex::new(r"(?-u)\xC3|-").unwrap();
+    let text: Vec<u16> = "aéb-c".encode_utf16().collect();
+    let v: Vec<&[u16]> = Utf16Splitter::new(&re).split_inclusive(&text).collect();
+    assert_eq!(v, [&text[..2], &text[2..4], &text[4..]]);
+
+    // An unpaired surrogate is searched as U+FFFD but yielded as it is.
+    let re = regex::bytes::Regex::new("\u{FFFD}").unwrap();
+    let text = [0x61, 0xD800, 0x62];
+    let v: Vec<&[u16]> = Utf16Splitter::new(&re).split_inclusive(&text).collect();
+    assert_eq!(v, [&text[..2], &text[2..]]);
+}
+
+#[cfg(feature = "encoding_rs")]
+#[test]
+fn transcoding_edges() {
+    use encoding_rs::{SHIFT_JIS, UTF_16LE, WINDOWS_1252};
+    use regex_split::stream::transcode::TranscodingSplitter;
+
+    // Patterns matching empty mustn't cut a character of the decoded text,
+    // nor throw the offsets in the text as read out of step.
+    let long = "ab\n".repeat(3000) + "é";
+    for pattern in ["", "x*", "a*", r"\n", r"\b"] {
+        let str_re = Regex::new(pattern).unwrap();
+        let re = regex::bytes::Regex::new(pattern).unwrap();
+        for text in ["", "é", "aéb\n", "いう\n", long.as_str()] {
+            let want: Vec<&str> = RegexSplit::split_inclusive(&str_re, text).collect();
+            for encoding in [WINDOWS_1252, UTF_16LE, SHIFT_JIS] {
+                // Encoding into UTF-16 gives UTF-8, as the Encoding Standard
+                // has it, so that one is done by hand.
+                let bytes: Vec<u8> = if encoding == UTF_16LE {
+                    text.encode_utf16().flat_map(u16::to_le_bytes).collect()
+                } else {
+                    match encoding.encode(text) {
+                        (_, _, true) => continue,
+                        (bytes, _, false) => bytes.into_owned(),
+                    }
+                };
+                let got = TranscodingSplitter::new(&re, &bytes[..], encoding)
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap();
+                let texts: Vec<&str> = got.iter().map(|s| s.text.as_str()).collect();
+                assert_eq!(texts, want, "{pattern:?} {}", encoding.name());
+                for segment in &got {
+                    let range = segment.range.start as usize..segment.range.end as usize;
+                    assert_eq!(&text[range], segment.text);
+                    let source = segment.source.start as usize..segment.source.end as usize;
+                    let (decoded, _) = encoding.decode_without_bom_handling(&bytes[source]);
+                    assert_eq!(decoded, segment.text, "{pattern:?} {}", encoding.name());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(any(feature = "flate2", feature = "zstd"))]
+#[test]
+fn decompress_edges() {
+    use regex_split::stream::decompress::Decompress;
+    use std::io::{BufReader, Read};
+
+    // A reader giving one byte at a time still has its format told from
+    // the first bytes, and text shorter than a magic number is plain.
+    let archives = [
+        #[cfg(feature = "flate2")]
+        {
+            use std::io::Write;
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), Default::default());
+            encoder.write_all(b"one\ntwo").unwrap();
+            encoder.finish().unwrap()
+        },
+        #[cfg(feature = "zstd")]
+        zstd::encode_all(&b"one\ntwo"[..], 0).unwrap(),
+    ];
+    for archive in &archives {
+        let mut reader = Decompress::new(BufReader::with_capacity(1, &archive[..])).unwrap();
+        assert!(reader.is_compressed());
+        let mut text = String::new();
+        reader.read_to_string(&mut text).unwrap();
+        assert_eq!(text, "one\ntwo");
+    }
+    for text in ["", "a", "abc", "abcd", "\x1f"] {
+        let mut reader = Decompress::new(BufReader::with_capacity(1, text.as_bytes())).unwrap();
+        assert!(!reader.is_compressed());
+        let mut got = String::new();
+        reader.read_to_string(&mut got).unwrap();
+        assert_eq!(got, text);
+    }
+}
+
+#[cfg(feature = "tokio-util")]
+#[test]
+fn codec_round_trip() {
+    use bytes::BytesMut;
+    use regex::bytes::Regex;
+    use regex_split::stream::codec::RegexSplitCodec;
+    use regex_split::Keep;
+    use tokio_util::codec::{Decoder, Encoder};
+
+    fn round_trip(mut codec: RegexSplitCodec, items: &[&str]) -> Vec<BytesMut> {
+        let mut buf = BytesMut::new();
+        for item in items {
+            codec.encode(item, &mut buf).unwrap();
+        }
+        let mut frames = Vec::new();
+        while let Some(frame) = codec.decode_eof(&mut buf).unwrap() {
+            frames.push(frame);
+        }
+        frames
+    }
+
+    // Frames decode to what was encoded, with the delimiter wherever the
+    // codec keeps it.
+    let re = Regex::new(r"\r?\n").unwrap();
+    let items = ["PING", "", "PONG"];
+    let codec = || RegexSplitCodec::new(&re, 1024).delimiter("\r\n");
+    let v = round_trip(codec().keep(Keep::Right), &items);
+    assert_eq!(v, ["PING\r\n", "\r\n", "PONG\r\n"]);
+    let v = round_trip(codec().keep(Keep::Left), &items);
+    assert_eq!(v, ["\r\nPING", "\r\n", "\r\nPONG"]);
+    let v = round_trip(codec().keep(Keep::Neither), &items);
+    assert_eq!(v, items);
+
+    // Without a delimiter, frames carry their own.
+    let codec = || RegexSplitCodec::new(&re, 1024);
+    let items = ["PING\r\n", "\n", "PONG\n"];
+    assert_eq!(round_trip(codec(), &items), items);
+    let items = ["\r\nPING", "\nPONG"];
+    assert_eq!(round_trip(codec().keep(Keep::Left), &items), items);
+
+    // Both sides of a match would have it, so neither way encodes.
+    let mut buf = BytesMut::new();
+    for mut codec in [codec().keep(Keep::Both), codec().keep(Keep::Both).delimiter("\n")] {
+        let e = codec.encode("PING\n", &mut buf).unwrap_err();
+        assert_eq!(e.kind(), std::io::ErrorKind::InvalidInput);
+    }
+    assert!(buf.is_empty());
+}
+
+#[test]
+fn grep_edges() {
+    use regex_split::grep::Grep;
+
+    let lines = newline();
+    let x = Regex::new(r"x").unwrap();
+    let numbers = |grep: Grep, text| -> Vec<(usize, bool)> {
+        grep.search(text).map(|r| (r.number, r.is_match)).collect()
+    };
+
+    // No records in an empty text, and none after a trailing delimiter.
+    assert_eq!(numbers(Grep::new(&lines, &x).context(2), ""), []);
+    assert_eq!(
+        numbers(Grep::new(&lines, &Regex::new("").unwrap()), "a\n"),
+        [(1, true)]
+    );
+
+    // Context is clipped at either end of the text, and records shared by two
+    // matches' context are yielded once.
+    let text = "x\na\nb\nx\nc\nx";
+    assert_eq!(
+        numbers(Grep::new(&lines, &x).context(2), text),
+        [
+            (1, true),
+            (2, false),
+            (3, false),
+            (4, true),
+            (5, false),
+            (6, true)
+        ]
+    );
+    assert_eq!(
+        numbers(Grep::new(&lines, &x).before_context(1), text),
+        [(1, true), (3, false), (4, true), (5, false), (6, true)]
+    );
+    assert_eq!(
+        numbers(Grep::new(&lines, &x).after_context(1).invert(true), text),
+        [(2, true), (3, true), (4, false), (5, true), (6, false)]
+    );
+
+    // The pattern doesn't see the delimiter.
+    let end = Regex::new(r"a$").unwrap();
+    let v: Vec<_> = Grep::new(&lines, &end).search("ba\nab\n").collect();
+    assert_eq!((v.len(), v[0].text, v[0].range.clone()), (1, "ba\n", 0..3));
+}
+
+#[test]
+fn matches_with_context_edges() {
+    let re = Regex::new(r"x|").unwrap();
+    let v: Vec<_> = re.matches_with_context("éxé", 1).collect();
+    assert_eq!(v, [("", 0..0), ("x", 0..1), ("", 0..0)]);
+
+    let re = Regex::new(r"x").unwrap();
+    assert_eq!(
+        re.matches_with_context("axb", 0).collect::<Vec<_>>(),
+        [("x", 0..1)]
+    );
+    assert_eq!(
+        re.matches_with_context("axb", usize::MAX)
+            .collect::<Vec<_>>(),
+        [("axb", 1..2)]
+    );
+    assert_eq!(re.matches_with_context("", 3).count(), 0);
+}
+
+#[test]
+fn matcher_edges() {
+    use regex_split::matcher::Matcher;
+    use regex_split::{Keep, Splitter};
+    use std::ops::Range;
+
+    // Matches the empty string everywhere, like `Regex::new("")`, stepping
+    // over multi-byte characters without being handed a position inside one.
+    struct Empty;
+
+    impl Matcher<str> for Empty {
+        fn find_at(&self, haystack: &str, at: usize) -> Option<Range<usize>> {
+            assert!(haystack.is_char_boundary(at));
+            Some(at..at)
+        }
+    }
+
+    let re = Regex::new("").unwrap();
+    for text in ["", "a", "éa€"] {
+        for keep in [Keep::Right, Keep::Left, Keep::Neither] {
+            let want: Vec<&str> = Splitter::new(&re).keep(keep).split(text).collect();
+            let matcher: &dyn Matcher<str> = &Empty;
+            let got: Vec<&str> = Splitter::with_matcher(matcher)
+                .keep(keep)
+                .split(text)
+                .collect();
+            assert_eq!(got, want);
+        }
+    }
+
+    let re = regex::bytes::Regex::new(r"\s+").unwrap();
+    let v: Vec<&[u8]> = regex_split::bytes::Splitter::with_matcher(&re)
+        .limit(2)
+        .split(b"a  b c")
+        .collect();
+    assert_eq!(v, [&b"a  "[..], &b"b c"[..]]);
+}
+
+#[cfg(feature = "onig")]
+#[test]
+fn onig_matcher_edges() {
+    use regex_split::{Keep, Splitter};
+
+    // Patterns both engines read alike split alike, in every mode.
+    for pattern in [r"\r?\n", r",\s*", "a|ab", "x*"] {
+        let re = Regex::new(pattern).unwrap();
+        let onig = onig::Regex::new(pattern).unwrap();
+        for text in ["", "a", "one\r\ntwo\n", "ab, a,b", "xxaxé"] {
+            for keep in [Keep::Right, Keep::Left, Keep::Both, Keep::Neither] {
+                let want: Vec<&str> = Splitter::new(&re).keep(keep).split(text).collect();
+                let got: Vec<&str> = Splitter::with_matcher(&onig)
+                    .keep(keep)
+                    .split(text)
+                    .collect();
+                assert_eq!(got, want, "{pattern:?} on {text:?} with {keep:?}");
+            }
+        }
+    }
+
+    // Ruby syntax makes `$` a line anchor, where the regex crate needs `(?m)`.
+    let text = "a\nb\nc";
+    let onig = onig::Regex::new("$").unwrap();
+    let re = Regex::new("$").unwrap();
+    let v: Vec<&str> = Splitter::with_matcher(&onig).split(text).collect();
+    assert_eq!(v, ["a", "\nb", "\nc", ""]);
+    let v: Vec<&str> = Splitter::new(&re).split(text).collect();
+    assert_eq!(v, ["a\nb\nc", ""]);
+}
+
+#[cfg(feature = "memchr")]
+#[test]
+fn literal_fast_path_edges() {
+    use regex::RegexBuilder;
+    use regex_split::matcher::Matcher;
+    use regex_split::{Keep, Splitter};
+
+    // `Splitter::new` may swap the regex for a memmem searcher, but a
+    // splitter made with `with_matcher` always runs the regex itself.
+    let regexes = [
+        Regex::new(r"\n---\n").unwrap(),
+        Regex::new("aa").unwrap(),
+        Regex::new("é").unwrap(),
+        RegexBuilder::new("ab")
+            .case_insensitive(true)
+            .build()
+            .unwrap(),
+    ];
+    for re in &regexes {
+        for text in ["", "aaa", "x\n---\n\n---\ny", "éaéé", "aBAbab"] {
+            for keep in [Keep::Right, Keep::Left, Keep::Both, Keep::Neither] {
+                let matcher: &dyn Matcher<str> = re;
+                let want: Vec<&str> = Splitter::with_matcher(matcher)
+                    .keep(keep)
+                    .split(text)
+                    .collect();
+                let got: Vec<&str> = Splitter::new(re).keep(keep).split(text).collect();
+                assert_eq!(got, want, "{re} on {text:?} with {keep:?}");
+            }
+        }
+    }
+}
+
+#[cfg(feature = "aho-corasick")]
+#[test]
+fn aho_corasick_splitter_reused() {
+    use regex_split::matcher::Matcher;
+    use regex_split::{RegexSplit, Splitter};
+
+    // The shortcut methods split with the regex alone. A splitter keeps the
+    // automaton it built on its first split for the splits after, and its
+    // clones share it; either way, the substrings are the regex's.
+    let re = Regex::new("foo|bar|foobar").unwrap();
+    let matcher: &dyn Matcher<str> = &re;
+    let regex_only = Splitter::with_matcher(matcher);
+    let splitter = Splitter::new(&re);
+    let cloned_before = splitter.clone();
+    for text in ["a foo b", "", "a foo b bar", "foobarfoo", "a foo b"] {
+        let want: Vec<&str> = regex_only.split(text).collect();
+        assert_eq!(re.split_inclusive(text).collect::<Vec<_>>(), want);
+        assert_eq!(splitter.split(text).collect::<Vec<_>>(), want);
+        assert_eq!(splitter.clone().split(text).collect::<Vec<_>>(), want);
+        assert_eq!(cloned_before.split(text).collect::<Vec<_>>(), want);
+    }
+}
+
+#[cfg(feature = "memchr")]
+#[test]
+fn memmem_splitter_reused() {
+    use regex_split::matcher::Matcher;
+    use regex_split::{RegexSplit, Splitter};
+
+    // The shortcut methods split with the regex alone. A splitter keeps the
+    // searcher it worked out on its first split for the splits after, and
+    // its clones share it; either way, the substrings are the regex's.
+    let re = Regex::new(r"\n---\n").unwrap();
+    let matcher: &dyn Matcher<str> = &re;
+    let regex_only = Splitter::with_matcher(matcher);
+    let splitter = Splitter::new(&re);
+    let cloned_before = splitter.clone();
+    for text in ["a\n---\nb", "", "c\n---\n\n---\n", "a\n---\nb"] {
+        let want: Vec<&str> = regex_only.split(text).collect();
+        assert_eq!(re.split_inclusive(text).collect::<Vec<_>>(), want);
+        assert_eq!(splitter.split(text).collect::<Vec<_>>(), want);
+        assert_eq!(splitter.clone().split(text).collect::<Vec<_>>(), want);
+        assert_eq!(cloned_before.split(text).collect::<Vec<_>>(), want);
+    }
+}